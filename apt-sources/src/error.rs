@@ -19,6 +19,9 @@ pub enum RepositoryError {
     Lossy(deb822_lossless::lossy::Error),
     /// Errors in lossless parser
     Lossless(deb822_lossless::lossless::Error),
+    /// A paragraph could not be converted to a `Repository`, e.g. because of a missing or
+    /// invalid field
+    Paragraph(deb822_lossless::convert::Error),
     /// I/O Error
     Io(std::io::Error)
 }
@@ -29,6 +32,18 @@ impl From<std::io::Error> for RepositoryError {
     }
 }
 
+impl From<deb822_lossless::lossless::ParseError> for RepositoryError {
+    fn from(e: deb822_lossless::lossless::ParseError) -> Self {
+        Self::Lossless(e.into())
+    }
+}
+
+impl From<deb822_lossless::convert::Error> for RepositoryError {
+    fn from(e: deb822_lossless::convert::Error) -> Self {
+        Self::Paragraph(e)
+    }
+}
+
 impl std::fmt::Display for RepositoryError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         match self {
@@ -39,7 +54,19 @@ impl std::fmt::Display for RepositoryError {
             Self::InvalidSignature => write!(f, "The field `Signed-By` is incorrect"),
             Self::Lossy(e) => write!(f, "Lossy parser error: {}", e),
             Self::Lossless(e) => write!(f, "Lossless parser error: {}", e),
+            Self::Paragraph(e) => write!(f, "Invalid repository: {}", e),
             Self::Io(e) => write!(f, "IO error: {}", e),
         }
     }
-}
\ No newline at end of file
+}
+
+impl std::error::Error for RepositoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Lossless(e) => Some(e),
+            Self::Paragraph(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -84,9 +84,9 @@ impl From<&RepositoryType> for String {
     }
 }
 
-impl ToString for RepositoryType {
-    fn to_string(&self) -> String {
-        self.into()
+impl std::fmt::Display for RepositoryType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&String::from(self))
     }
 }
 
@@ -125,31 +125,31 @@ impl From<&YesNoForce> for String {
     }
 }
 
-impl ToString for &YesNoForce {
-    fn to_string(&self) -> String {
-        self.to_owned().into()
+impl std::fmt::Display for YesNoForce {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&String::from(self))
     }
 }
 
 fn deserialize_types(text: &str) -> Result<HashSet<RepositoryType>, RepositoryError> {
     text.split_whitespace()
-        .map(|t| RepositoryType::from_str(t))
+        .map(RepositoryType::from_str)
         .collect::<Result<HashSet<RepositoryType>, RepositoryError>>()
 }
 
 fn serialize_types(files: &HashSet<RepositoryType>) -> String {
-    files.into_iter().map(|rt| rt.to_string()).collect::<Vec<String>>().join("\n")
+    files.iter().map(|rt| rt.to_string()).collect::<Vec<String>>().join("\n")
 }
 
 fn deserialize_uris(text: &str) -> Result<Vec<Url>, String> { // TODO: bad error type
     text.split_whitespace()
-        .map(|u| Url::from_str(u))
+        .map(Url::from_str)
         .collect::<Result<Vec<Url>, _>>()
         .map_err(|e| e.to_string()) // TODO: bad error type
 }
 
 fn serialize_uris(uris: &[Url]) -> String {
-    uris.into_iter().map(|u| u.as_str()).collect::<Vec<&str>>().join(" ")
+    uris.iter().map(|u| u.as_str()).collect::<Vec<&str>>().join(" ")
 }
 
 fn deserialize_string_chain(text: &str) -> Result<Vec<String>, String> { // TODO: bad error type
@@ -216,7 +216,7 @@ fn serialize_string_chain(chain: &[String]) -> String {
 #[derive(FromDeb822, ToDeb822, Clone, PartialEq, /*Eq,*/ Debug, Default)]
 pub struct Repository {
     /// If `no` (false) the repository is ignored by APT
-    #[deb822(field = "Enabled", deserialize_with = deserialize_yesno, serialize_with = serializer_yesno)] // TODO: support for `default` if omitted is missing
+    #[deb822(field = "Enabled", deserialize_with = deserialize_yesno, serialize_with = serializer_yesno)]
     enabled: Option<bool>,
 
     /// The value `RepositoryType::Binary` (`deb`) or/and `RepositoryType::Source` (`deb-src`)
@@ -233,7 +233,7 @@ pub struct Repository {
     components: Vec<String>,
 
     /// (Optional) Architectures binaries from this repository run on
-    #[deb822(field = "Architectures", deserialize_with = deserialize_string_chain, serialize_with = serialize_string_chain)]
+    #[deb822(field = "Architectures", deserialize_with = deserialize_string_chain, serialize_with = serialize_string_chain, default, skip_serializing_if = Vec::is_empty)]
     architectures: Vec<String>,
     /// (Optional) Translations support to download
     #[deb822(field = "Languages", deserialize_with = deserialize_string_chain, serialize_with = serialize_string_chain)]
@@ -270,12 +270,19 @@ pub struct Repository {
 
     /// (Optional) Field not present in the man page, but used in APT unit tests, potentially to hold the repository description
     #[deb822(field = "Description")]
-    description: Option<String>
+    description: Option<String>,
 
-    // options: HashMap<String, String> // My original parser kept remaining optional fields in the hash map, is this right approach?
+    /// Fields not known to this library, kept so that they are written back
+    #[deb822(rest)]
+    extra_fields: Vec<(String, String)>
 }
 
 impl Repository {
+    /// Returns whether APT uses this repository; `Enabled` defaults to yes when absent
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
     /// Returns slice of strings containing suites for which this repository provides
     pub fn suites(&self) -> &[String] {
         self.suites.as_slice()
@@ -303,24 +310,25 @@ impl Repositories {
 }
 
 impl std::str::FromStr for Repositories {
-    type Err = String;
+    type Err = RepositoryError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let deb822: deb822_lossless::Deb822 = s
             .parse()
-            .map_err(|e: deb822_lossless::ParseError| e.to_string())?;
+            .map_err(|e: deb822_lossless::ParseError| RepositoryError::from(e))?;
 
-        let repos = deb822.paragraphs().map(|p| Repository::from_paragraph(&p)).collect::<Result<Vec<Repository>, Self::Err>>()?;
+        let repos = deb822.paragraphs().map(|p| Repository::from_paragraph(&p)).collect::<Result<Vec<Repository>, _>>()?;
         Ok(Repositories(repos))
     }
 }
 
-impl ToString for Repositories {
-    fn to_string(&self) -> String {
-        self.0.iter()
+impl std::fmt::Display for Repositories {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let result = self.0.iter()
             .map(|r| { let p: deb822_lossless::lossy::Paragraph = r.to_paragraph(); p.to_string() })
             .collect::<Vec<_>>()
-            .join("\n")
+            .join("\n");
+        f.write_str(&result)
     }
 }
 
@@ -339,7 +347,7 @@ mod tests {
     use indoc::indoc;
     use url::Url;
 
-    use crate::{signature::Signature, Repositories, Repository, RepositoryType};
+    use crate::{error::RepositoryError, signature::Signature, Repositories, Repository, RepositoryType};
 
     #[test]
     fn test_not_machine_readable() {
@@ -349,7 +357,9 @@ mod tests {
         let ret = s.parse::<Repositories>();
         assert!(ret.is_err());
         //assert_eq!(ret.unwrap_err(), "Not machine readable".to_string());
-        assert_eq!(ret.unwrap_err(), "expected ':', got Some(NEWLINE)\n".to_owned());
+        let err = ret.unwrap_err();
+        assert!(matches!(err, RepositoryError::Lossless(_)));
+        assert_eq!(err.to_string(), "Lossless parser error: expected ':', got Some(NEWLINE)\n".to_owned());
     }
 
     #[test]
@@ -394,12 +404,78 @@ mod tests {
         assert!(matches!(reps[0].signature, Some(Signature::KeyPath(_))));
     }
 
+    #[test]
+    fn test_field_errors() {
+        let s = indoc!(r#"
+            Types: deb
+            Suites: noble
+            Components: stable
+        "#);
+        let err = s.parse::<Repositories>().unwrap_err();
+        assert!(matches!(
+            err,
+            RepositoryError::Paragraph(deb822_lossless::convert::Error::MissingField { ref field }) if field == "URIs"
+        ));
+
+        let s = indoc!(r#"
+            Types: deb
+            URIs: http://ports.ubuntu.com/
+            Suites: noble
+            Components: stable
+            Enabled: maybe
+        "#);
+        let err = s.parse::<Repositories>().unwrap_err();
+        match err {
+            RepositoryError::Paragraph(e) => {
+                assert_eq!(e.field(), Some("Enabled"));
+                assert_eq!(&s[e.range().unwrap()], "maybe");
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_enabled_default() {
+        let s = indoc!(r#"
+            Types: deb
+            URIs: http://ports.ubuntu.com/
+            Suites: noble
+            Components: stable
+
+            Enabled: no
+            Types: deb
+            URIs: http://ports.ubuntu.com/
+            Suites: noble
+            Components: stable
+        "#);
+
+        let reps = s.parse::<Repositories>().expect("Shall be parsed flawlessly");
+        assert!(reps[0].is_enabled());
+        assert!(!reps[1].is_enabled());
+        assert!(!reps.to_string().starts_with("Enabled"));
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let s = indoc!(r#"
+            Types: deb
+            URIs: http://ports.ubuntu.com/
+            Suites: noble
+            Components: stable
+            X-Custom: value
+        "#);
+
+        let reps = s.parse::<Repositories>().expect("Shall be parsed flawlessly");
+        assert_eq!(reps[0].extra_fields, vec![("X-Custom".to_owned(), "value".to_owned())]);
+        assert!(reps.to_string().ends_with("X-Custom: value\n"));
+    }
+
     #[test]
     fn test_serialize() {
         //let repos = Repositories::empty();
         let repos = Repositories::new([
             Repository {
-                enabled: Some(true), // TODO: looks odd, as only `Enabled: no` in meaningful
+                enabled: Some(true),
                 types: HashSet::from([RepositoryType::Binary]),
                 architectures: vec!["arm64".to_owned()],
                 uris: vec![Url::from_str("https://deb.debian.org/debian").unwrap()],
@@ -0,0 +1,32 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use deb822_lossless::borrowed::BorrowedDeb822;
+use deb822_lossless::convert::ReadableParagraph;
+
+/// Count the paragraphs that have a `Package` field, to make sure values are accessed.
+fn count_packages<'a, P: ReadableParagraph + 'a>(paragraphs: impl Iterator<Item = &'a P>) -> usize {
+    paragraphs.filter(|p| p.get("Package").is_some()).count()
+}
+
+fn parse_deb822_benchmark(c: &mut Criterion) {
+    let control_data =
+        std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/bench/Sources"))
+            .expect("Could not read control file");
+
+    let mut group = c.benchmark_group("parse_and_read");
+    group.bench_function("lossy", |b| {
+        b.iter(|| {
+            let deb822: deb822_lossless::lossy::Deb822 = control_data.parse().unwrap();
+            count_packages(deb822.iter())
+        });
+    });
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            let deb822 = BorrowedDeb822::parse(&control_data).unwrap();
+            count_packages(deb822.iter())
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse_deb822_benchmark);
+criterion_main!(benches);
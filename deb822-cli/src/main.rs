@@ -0,0 +1,638 @@
+//! Command-line tool for querying and editing deb822 files.
+//!
+//! All edits are made on the lossless syntax tree, so comments, field order and the formatting
+//! of fields that are not touched are preserved.
+use clap::{Args, Parser, Subcommand};
+use deb822_lossless::json::{paragraph_to_json, JsonOptions};
+use deb822_lossless::merge::{Merge, MergeOptions};
+use deb822_lossless::{Deb822, Paragraph};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(name = "deb822", version, about = "Query and edit deb822 files")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Which paragraphs a command applies to.
+///
+/// Unless specified otherwise, commands only apply to the first paragraph.
+#[derive(Args, Debug, Default, Clone, PartialEq, Eq)]
+#[group(multiple = false)]
+struct Selection {
+    /// Select the paragraph with the given index (0-based)
+    #[arg(short, long, value_name = "N")]
+    paragraph: Option<usize>,
+
+    /// Select all paragraphs in which a field has the given value
+    #[arg(short, long = "match", value_name = "FIELD=VALUE", value_parser = parse_match)]
+    matches: Option<(String, String)>,
+
+    /// Select all paragraphs
+    #[arg(short, long)]
+    all: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the value of a field
+    ///
+    /// Exits with status 1 if none of the selected paragraphs contain the field.
+    Get {
+        /// The file to read, or "-" for standard input
+        file: PathBuf,
+
+        #[command(flatten)]
+        selection: Selection,
+
+        /// The name of the field
+        field: String,
+    },
+
+    /// Set the value of a field, adding it if it does not exist yet
+    ///
+    /// Newlines in the value start continuation lines; empty lines are written as " .".
+    Set {
+        /// The file to edit, or "-" to read standard input and write to standard output
+        file: PathBuf,
+
+        #[command(flatten)]
+        selection: Selection,
+
+        /// The name of the field
+        field: String,
+
+        /// The new value
+        value: String,
+    },
+
+    /// Remove a field
+    Remove {
+        /// The file to edit, or "-" to read standard input and write to standard output
+        file: PathBuf,
+
+        #[command(flatten)]
+        selection: Selection,
+
+        /// The name of the field
+        field: String,
+    },
+
+    /// Rename a field, keeping its value and formatting
+    Rename {
+        /// The file to edit, or "-" to read standard input and write to standard output
+        file: PathBuf,
+
+        #[command(flatten)]
+        selection: Selection,
+
+        /// The current name of the field
+        old: String,
+
+        /// The new name of the field
+        new: String,
+    },
+
+    /// List the names of the fields
+    ListFields {
+        /// The file to read, or "-" for standard input
+        file: PathBuf,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+
+    /// Print the paragraphs as a JSON array of objects
+    ///
+    /// Unless a selection is given, all paragraphs are printed.
+    ToJson {
+        /// The file to read, or "-" for standard input
+        file: PathBuf,
+
+        #[command(flatten)]
+        selection: Selection,
+    },
+
+    /// Normalize insignificant whitespace
+    ///
+    /// Field values get a single space after the colon, trailing whitespace is removed, empty
+    /// continuation lines are written as " ." and paragraphs are separated by a single empty
+    /// line. Comments, field order and the indentation of continuation lines are kept.
+    Fmt {
+        /// The file to format, or "-" to read standard input and write to standard output
+        file: PathBuf,
+
+        /// Don't write the file, but exit with status 1 if it is not formatted
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Merge the changes between BASE and THEIRS into OURS
+    ///
+    /// Paragraphs are matched by the first of the key fields they contain, and fields are merged
+    /// independently; relation fields like Depends are merged entry by entry. Conflicts are
+    /// reported on standard error and leave our version in place, in which case the exit status
+    /// is 1. To use this as a git merge driver, set its command to "deb822 merge %O %A %B".
+    Merge {
+        /// The common ancestor
+        base: PathBuf,
+
+        /// Our version, which is replaced by the result
+        ours: PathBuf,
+
+        /// Their version
+        theirs: PathBuf,
+
+        /// Field used to match paragraphs; can be given more than once
+        #[arg(short, long = "key", value_name = "FIELD", default_values = ["Package", "Source"])]
+        keys: Vec<String>,
+    },
+}
+
+/// Error running a command.
+#[derive(Debug)]
+enum Error {
+    /// An I/O error occurred.
+    Io(PathBuf, std::io::Error),
+
+    /// The input could not be parsed.
+    Parse(PathBuf, deb822_lossless::ParseError),
+
+    /// The selection did not match any paragraphs.
+    NoParagraphs,
+
+    /// The field to rename does not exist.
+    NoSuchField(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            Error::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+            Error::NoParagraphs => write!(f, "no matching paragraphs"),
+            Error::NoSuchField(field) => write!(f, "no such field: {}", field),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn parse_match(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((field, value)) if !field.is_empty() => Ok((field.to_string(), value.to_string())),
+        _ => Err(format!("expected FIELD=VALUE, got {:?}", s)),
+    }
+}
+
+impl Selection {
+    /// Returns the selected paragraphs, or all paragraphs if nothing was selected explicitly
+    /// and `default_all` is set.
+    fn select(&self, deb822: &Deb822, default_all: bool) -> Result<Vec<Paragraph>, Error> {
+        let paragraphs: Vec<Paragraph> = if let Some(index) = self.paragraph {
+            deb822.paragraphs().nth(index).into_iter().collect()
+        } else if let Some((field, value)) = &self.matches {
+            deb822
+                .paragraphs()
+                .filter(|p| p.get(field).as_deref() == Some(value.as_str()))
+                .collect()
+        } else if self.all || default_all {
+            deb822.paragraphs().collect()
+        } else {
+            deb822.paragraphs().take(1).collect()
+        };
+        if paragraphs.is_empty() {
+            return Err(Error::NoParagraphs);
+        }
+        Ok(paragraphs)
+    }
+}
+
+fn read(path: &Path) -> Result<(String, Deb822), Error> {
+    let mut text = String::new();
+    if path == Path::new("-") {
+        std::io::stdin().read_to_string(&mut text)
+    } else {
+        std::fs::File::open(path).and_then(|mut f| f.read_to_string(&mut text))
+    }
+    .map_err(|e| Error::Io(path.to_path_buf(), e))?;
+    let deb822 = text
+        .parse()
+        .map_err(|e| Error::Parse(path.to_path_buf(), e))?;
+    Ok((text, deb822))
+}
+
+/// Write the edited file back, unless its contents did not change.
+fn write(path: &Path, original: &str, deb822: &Deb822) -> Result<(), Error> {
+    let text = deb822.to_string();
+    if path == Path::new("-") {
+        std::io::stdout().write_all(text.as_bytes())
+    } else if text != original {
+        deb822_lossless::editor::write_atomic(path, text.as_bytes())
+    } else {
+        Ok(())
+    }
+    .map_err(|e| Error::Io(path.to_path_buf(), e))
+}
+
+/// Returns the values of `field` in the given paragraphs, skipping paragraphs without it.
+fn get(paragraphs: &[Paragraph], field: &str) -> Vec<String> {
+    paragraphs.iter().filter_map(|p| p.get(field)).collect()
+}
+
+fn set(paragraphs: &mut [Paragraph], field: &str, value: &str) {
+    let value = deb822_lossless::multiline::MultiLineValue::from_text(value);
+    for p in paragraphs {
+        p.set_multiline(field, &value);
+    }
+}
+
+fn remove(paragraphs: &mut [Paragraph], field: &str) {
+    for p in paragraphs {
+        p.remove(field);
+    }
+}
+
+fn rename(paragraphs: &mut [Paragraph], old: &str, new: &str) -> Result<(), Error> {
+    let mut found = false;
+    for p in paragraphs {
+        found |= p.rename(old, new);
+    }
+    if found {
+        Ok(())
+    } else {
+        Err(Error::NoSuchField(old.to_string()))
+    }
+}
+
+fn list_fields(paragraphs: &[Paragraph]) -> Vec<String> {
+    let mut fields: Vec<String> = vec![];
+    for key in paragraphs.iter().flat_map(|p| p.keys()) {
+        if !fields.iter().any(|f| f.eq_ignore_ascii_case(&key)) {
+            fields.push(key);
+        }
+    }
+    fields
+}
+
+fn to_json(paragraphs: &[Paragraph]) -> serde_json::Value {
+    let options = JsonOptions::default();
+    serde_json::Value::Array(
+        paragraphs
+            .iter()
+            .map(|p| paragraph_to_json(p, &options))
+            .collect(),
+    )
+}
+
+fn format(deb822: &Deb822) -> Deb822 {
+    let formatted = deb822.wrap_and_sort(None, None);
+    for mut p in formatted.paragraphs() {
+        let mut seen: Vec<String> = vec![];
+        for entry in p.entries().collect::<Vec<_>>() {
+            let Some(key) = entry.key() else {
+                continue;
+            };
+            // Only the first of any duplicate fields can be addressed by name.
+            if seen.iter().any(|k| k.eq_ignore_ascii_case(&key)) {
+                continue;
+            }
+            let mut value = entry.multiline_value();
+            value.first_line = value.first_line.trim().to_string();
+            for line in value.lines.iter_mut() {
+                if let deb822_lossless::multiline::ContinuationLine::Text(text) = line {
+                    text.truncate(text.trim_end().len());
+                }
+            }
+            p.set_multiline(&key, &value);
+            seen.push(key);
+        }
+    }
+    formatted
+}
+
+fn merge(base: &Deb822, ours: &Deb822, theirs: &Deb822, keys: &[String]) -> Merge {
+    let key = |p: &Paragraph| keys.iter().find_map(|k| p.get(k));
+    deb822_lossless::merge::merge(base, ours, theirs, key, &MergeOptions::default())
+}
+
+fn run(command: Command) -> Result<ExitCode, Error> {
+    match command {
+        Command::Get {
+            file,
+            selection,
+            field,
+        } => {
+            let (_, deb822) = read(&file)?;
+            let values = get(&selection.select(&deb822, false)?, &field);
+            for value in &values {
+                println!("{}", value);
+            }
+            if values.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Set {
+            file,
+            selection,
+            field,
+            value,
+        } => {
+            let (original, deb822) = read(&file)?;
+            set(&mut selection.select(&deb822, false)?, &field, &value);
+            write(&file, &original, &deb822)?;
+        }
+        Command::Remove {
+            file,
+            selection,
+            field,
+        } => {
+            let (original, deb822) = read(&file)?;
+            remove(&mut selection.select(&deb822, false)?, &field);
+            write(&file, &original, &deb822)?;
+        }
+        Command::Rename {
+            file,
+            selection,
+            old,
+            new,
+        } => {
+            let (original, deb822) = read(&file)?;
+            rename(&mut selection.select(&deb822, false)?, &old, &new)?;
+            write(&file, &original, &deb822)?;
+        }
+        Command::ListFields { file, selection } => {
+            let (_, deb822) = read(&file)?;
+            for field in list_fields(&selection.select(&deb822, false)?) {
+                println!("{}", field);
+            }
+        }
+        Command::ToJson { file, selection } => {
+            let (_, deb822) = read(&file)?;
+            let json = to_json(&selection.select(&deb822, true)?);
+            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        }
+        Command::Fmt { file, check } => {
+            let (original, deb822) = read(&file)?;
+            let formatted = format(&deb822);
+            if check {
+                if formatted.to_string() != original {
+                    eprintln!("{}: not formatted", file.display());
+                    return Ok(ExitCode::FAILURE);
+                }
+            } else {
+                write(&file, &original, &formatted)?;
+            }
+        }
+        Command::Merge {
+            base,
+            ours,
+            theirs,
+            keys,
+        } => {
+            let (original, ours_deb822) = read(&ours)?;
+            let merged = merge(&read(&base)?.1, &ours_deb822, &read(&theirs)?.1, &keys);
+            write(&ours, &original, &merged.result)?;
+            for conflict in &merged.conflicts {
+                eprintln!("{}: {}", ours.display(), conflict);
+            }
+            if !merged.conflicts.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("deb822: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTROL: &str = r#"Source: foo
+# The maintainer
+Maintainer: Joe <joe@example.com>
+Build-Depends: debhelper-compat (= 13),
+               libfoo-dev
+
+Package: foo
+Architecture: any
+Description: short
+ long
+ .
+ text
+
+Package: foo-doc
+Architecture: all
+"#;
+
+    fn selection(args: &[&str]) -> Selection {
+        let args = ["deb822", "list-fields", "-"]
+            .into_iter()
+            .chain(args.iter().copied());
+        match Cli::parse_from(args).command {
+            Command::ListFields { selection, .. } => selection,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_cli() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+        assert!(Cli::try_parse_from(["deb822", "get", "-", "-p", "1", "-a", "Package"]).is_err());
+        assert!(Cli::try_parse_from(["deb822", "get", "-", "-m", "Package", "Version"]).is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let deb822: Deb822 = CONTROL.parse().unwrap();
+        let names = |paragraphs: Vec<Paragraph>| {
+            paragraphs
+                .iter()
+                .map(|p| {
+                    let (key, value) = p.items().next().unwrap();
+                    format!("{}:{}", key, value)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(selection(&[]).select(&deb822, false).unwrap()),
+            vec!["Source:foo"]
+        );
+        assert_eq!(
+            names(selection(&[]).select(&deb822, true).unwrap()).len(),
+            3
+        );
+        assert_eq!(
+            names(selection(&["--all"]).select(&deb822, false).unwrap()).len(),
+            3
+        );
+        assert_eq!(
+            names(selection(&["-p", "2"]).select(&deb822, false).unwrap()),
+            vec!["Package:foo-doc"]
+        );
+        assert_eq!(
+            names(
+                selection(&["--match", "Architecture=any"])
+                    .select(&deb822, false)
+                    .unwrap()
+            ),
+            vec!["Package:foo"]
+        );
+        assert!(matches!(
+            selection(&["-p", "3"]).select(&deb822, false),
+            Err(Error::NoParagraphs)
+        ));
+    }
+
+    #[test]
+    fn test_get() {
+        let deb822: Deb822 = CONTROL.parse().unwrap();
+        let all = selection(&["--all"]).select(&deb822, false).unwrap();
+        assert_eq!(get(&all, "package"), vec!["foo", "foo-doc"]);
+        assert_eq!(get(&all, "Description"), vec!["short\nlong\n.\ntext"]);
+        assert!(get(&all, "Nonexistent").is_empty());
+    }
+
+    #[test]
+    fn test_set() {
+        let deb822: Deb822 = CONTROL.parse().unwrap();
+        let mut paragraphs = selection(&["-m", "Package=foo"])
+            .select(&deb822, false)
+            .unwrap();
+        set(&mut paragraphs, "Description", "new\nfirst\n\nsecond");
+        set(&mut paragraphs, "Section", "libs");
+        assert_eq!(
+            deb822.to_string(),
+            CONTROL.replace(
+                "Description: short\n long\n .\n text\n",
+                "Description: new\n first\n .\n second\nSection: libs\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_remove_rename() {
+        let deb822: Deb822 = CONTROL.parse().unwrap();
+        let mut paragraphs = selection(&[]).select(&deb822, false).unwrap();
+        rename(&mut paragraphs, "Build-Depends", "Build-Depends-Arch").unwrap();
+        assert!(matches!(
+            rename(&mut paragraphs, "Build-Depends", "Foo"),
+            Err(Error::NoSuchField(_))
+        ));
+        remove(&mut paragraphs, "Maintainer");
+        assert_eq!(
+            deb822.to_string(),
+            CONTROL
+                .replace("Maintainer: Joe <joe@example.com>\n", "")
+                .replace("Build-Depends:", "Build-Depends-Arch:")
+        );
+    }
+
+    #[test]
+    fn test_list_fields() {
+        let deb822: Deb822 = CONTROL.parse().unwrap();
+        let paragraphs = selection(&["-a"]).select(&deb822, false).unwrap();
+        assert_eq!(
+            list_fields(&paragraphs[1..]),
+            vec!["Package", "Architecture", "Description"]
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let deb822: Deb822 = "Package: foo\nVersion: 1.0\n\nPackage: bar\n"
+            .parse()
+            .unwrap();
+        let paragraphs = selection(&[]).select(&deb822, true).unwrap();
+        assert_eq!(
+            serde_json::to_string(&to_json(&paragraphs)).unwrap(),
+            r#"[{"Package":"foo","Version":"1.0"},{"Package":"bar"}]"#
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        let deb822: Deb822 = concat!(
+            "Source:   foo  \n",
+            "Build-Depends: debhelper,  \n",
+            "               libfoo-dev\n",
+            "\n",
+            "\n",
+            "Package: foo\n",
+            "# comment\n",
+            "Depends:a,\n",
+            "  b  \n",
+            "Description: short\n",
+            " long\n",
+            " \n",
+            "   verbatim\n",
+        )
+        .parse()
+        .unwrap();
+        let formatted = format(&deb822);
+        assert_eq!(
+            formatted.to_string(),
+            concat!(
+                "Source: foo\n",
+                "Build-Depends: debhelper,\n",
+                "               libfoo-dev\n",
+                "\n",
+                "Package: foo\n",
+                "# comment\n",
+                "Depends: a,\n",
+                "  b\n",
+                "Description: short\n",
+                " long\n",
+                " .\n",
+                "   verbatim\n",
+            )
+        );
+        assert_eq!(format(&formatted).to_string(), formatted.to_string());
+        assert_eq!(format(&CONTROL.parse().unwrap()).to_string(), CONTROL);
+    }
+
+    #[test]
+    fn test_merge() {
+        let keys = ["Package".to_string(), "Source".to_string()];
+        let base: Deb822 = CONTROL.parse().unwrap();
+        let ours: Deb822 = CONTROL
+            .replace("libfoo-dev", "libfoo-dev,\n               libbar-dev")
+            .parse()
+            .unwrap();
+        let theirs: Deb822 = CONTROL
+            .replace("(= 13)", "(= 14)")
+            .replace(
+                "Architecture: all",
+                "Architecture: all\nMulti-Arch: foreign",
+            )
+            .parse()
+            .unwrap();
+        let merged = merge(&base, &ours, &theirs, &keys);
+        assert!(merged.conflicts.is_empty());
+        assert_eq!(
+            merged.result.to_string(),
+            CONTROL
+                .replace("(= 13)", "(= 14)")
+                .replace("libfoo-dev", "libfoo-dev,\n               libbar-dev")
+                .replace(
+                    "Architecture: all",
+                    "Architecture: all\nMulti-Arch: foreign"
+                )
+        );
+    }
+}
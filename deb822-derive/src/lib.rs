@@ -33,19 +33,16 @@ fn is_option(ty: &syn::Type) -> bool {
 // ```rust
 //
 // impl<P: deb822_lossless::convert::Deb822LikeParagraph> FromDeb822Paragraph<P> for X {
-//     fn from_paragraph(para: &P) -> Result<Self, String> {
+//     fn from_paragraph(para: &P) -> Result<Self, deb822_lossless::convert::Error> {
 //     Ok(Self {
-//         a: para.get("a").ok_or_else(|| "missing field: a")?.parse().map_err(|e| format!("parsing field a: {}", e))?,
-//         b: para.get("b").ok_or_else(|| "missing field: b")?.parse().map_err(|e| format!("parsing field b: {}", e))?,
-//         c: para.get("c").map(|v| v.parse().map_err(|e| format!("parsing field c: {}", e))).transpose()?,
-//         d: para.get("d").ok_or_else(|| "missing field: d")?.split_whitespace().map(|s| s.to_string()).collect(),
-//         e: para.get("E").ok_or_else(|| "missing field: e")?.parse().map_err(|e| format!("parsing field E: {}", e))?,
+//         a: para.get("a").ok_or_else(|| Error::MissingField { field: "a".to_string() })?.parse().map_err(|e| Error::invalid_value("a", para.value_range("a"), e))?,
+//         ...
 //     })
 // }
 //
 // And:
 //
-//// ```rust
+// ```rust
 // #[derive(ToDeb822)]
 // struct X {
 //    a: i32,
@@ -86,165 +83,485 @@ fn is_option(ty: &syn::Type) -> bool {
 //     }
 // }
 // ```
+//
+// Fields can also be given these attributes:
+//
+// * `#[deb822(default)]` or `#[deb822(default = path)]`: if the field is missing, use
+//   `Default::default()` or the result of calling `path()` instead of failing.
+// * `#[deb822(skip)]`: the field is not read from or written to the paragraph, and is set to
+//   its default when converting from a paragraph.
+// * `#[deb822(skip_serializing_if = path)]`: the field is not written if `path(&value)`
+//   returns true, and is removed when updating a paragraph.
+// * `#[deb822(rest)]` (or `#[deb822(flatten)]`): the field collects all fields that are not
+//   read by any other field, as a `Vec<(String, String)>`, and writes them back.
+//
+// With `#[deb822(deny_unknown_fields)]` on a struct, enum or variant, converting a paragraph
+// that contains fields not read by any field fails with `Error::UnknownField`. Errors carry the
+// name of the field and, for lossless paragraphs, the range of the value in the source text.
+//
+// A tuple struct with a single field is transparent: it is converted by converting the field,
+// which can be a paragraph or another type implementing the traits.
+//
+// For enums, each variant is either a newtype variant or has named fields, and the variant to
+// convert a paragraph to is selected by a discriminating field:
+//
+// ```rust
+// #[derive(FromDeb822, ToDeb822)]
+// enum Paragraph {
+//     #[deb822(tag_field = "Source")]
+//     Source(Source),
+//     #[deb822(tag_field = "Package")]
+//     Binary(Binary),
+// }
+// ```
+//
+// The variants are tried in order; a variant matches if its `tag_field` is present. With
+// `#[deb822(tag_field = "Type", tag = "deb")]` the field must also have the given value, and
+// when converting the variant back to a paragraph the field is set to that value. Setting
+// `tag_field` on the enum itself applies it to all variants, with the tag defaulting to the
+// name of the variant. The last variant may have no `tag_field`, in which case it matches any
+// paragraph.
 
 struct FieldAttributes {
     field: Option<String>,
     serialize_with: Option<syn::ExprPath>,
     deserialize_with: Option<syn::ExprPath>,
+    default: Option<proc_macro2::TokenStream>,
+    skip: bool,
+    skip_serializing_if: Option<syn::ExprPath>,
+    rest: bool,
+}
+
+fn expect_path(value: syn::Expr) -> Result<syn::ExprPath, syn::Error> {
+    if let syn::Expr::Path(s) = value {
+        Ok(s)
+    } else {
+        Err(syn::Error::new(
+            value.span(),
+            "expected path in deb822 attribute",
+        ))
+    }
 }
 
 fn extract_field_attributes(attrs: &[syn::Attribute]) -> Result<FieldAttributes, syn::Error> {
-    let mut field = None;
-    let mut serialize_with = None;
-    let mut deserialize_with = None;
+    let mut ret = FieldAttributes {
+        field: None,
+        serialize_with: None,
+        deserialize_with: None,
+        default: None,
+        skip: false,
+        skip_serializing_if: None,
+        rest: false,
+    };
     for attr in attrs {
         if !attr.path().is_ident("deb822") {
             continue;
         }
-        let name_values: syn::punctuated::Punctuated<syn::MetaNameValue, syn::Token![,]> =
+        let metas: syn::punctuated::Punctuated<syn::Meta, syn::Token![,]> =
             attr.parse_args_with(syn::punctuated::Punctuated::parse_terminated)?;
-        for nv in name_values {
-            if nv.path.is_ident("field") {
-                if let syn::Expr::Lit(syn::ExprLit {
-                    lit: syn::Lit::Str(s),
-                    ..
-                }) = nv.value
-                {
-                    field = Some(s.value());
-                } else {
-                    return Err(syn::Error::new(
-                        nv.value.span(),
-                        "expected string literal in deb822 attribute",
-                    ));
+        for meta in metas {
+            match meta {
+                syn::Meta::Path(path) if path.is_ident("default") => {
+                    ret.default = Some(quote! { Default::default() });
                 }
-            } else if nv.path.is_ident("serialize_with") {
-                if let syn::Expr::Path(s) = nv.value {
-                    serialize_with = Some(s);
-                } else {
+                syn::Meta::Path(path) if path.is_ident("skip") => {
+                    ret.skip = true;
+                }
+                syn::Meta::Path(path) if path.is_ident("rest") || path.is_ident("flatten") => {
+                    ret.rest = true;
+                }
+                syn::Meta::NameValue(nv) if nv.path.is_ident("field") => {
+                    if let syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }) = nv.value
+                    {
+                        ret.field = Some(s.value());
+                    } else {
+                        return Err(syn::Error::new(
+                            nv.value.span(),
+                            "expected string literal in deb822 attribute",
+                        ));
+                    }
+                }
+                syn::Meta::NameValue(nv) if nv.path.is_ident("serialize_with") => {
+                    ret.serialize_with = Some(expect_path(nv.value)?);
+                }
+                syn::Meta::NameValue(nv) if nv.path.is_ident("deserialize_with") => {
+                    ret.deserialize_with = Some(expect_path(nv.value)?);
+                }
+                syn::Meta::NameValue(nv) if nv.path.is_ident("default") => {
+                    let path = expect_path(nv.value)?;
+                    ret.default = Some(quote! { #path() });
+                }
+                syn::Meta::NameValue(nv) if nv.path.is_ident("skip_serializing_if") => {
+                    ret.skip_serializing_if = Some(expect_path(nv.value)?);
+                }
+                meta => {
+                    let path = meta.path();
                     return Err(syn::Error::new(
-                        nv.value.span(),
-                        "expected path in deb822 attribute",
+                        meta.span(),
+                        format!("unsupported attribute: {}", quote! { #path }),
                     ));
                 }
-            } else if nv.path.is_ident("deserialize_with") {
-                if let syn::Expr::Path(s) = nv.value {
-                    deserialize_with = Some(s);
-                } else {
+            }
+        }
+    }
+    if ret.rest
+        && (ret.field.is_some()
+            || ret.serialize_with.is_some()
+            || ret.deserialize_with.is_some()
+            || ret.default.is_some()
+            || ret.skip
+            || ret.skip_serializing_if.is_some())
+    {
+        return Err(syn::Error::new(
+            attrs[0].span(),
+            "rest can not be combined with other deb822 attributes",
+        ));
+    }
+    Ok(ret)
+}
+
+/// A named field of a struct or variant, along with its attributes.
+struct NamedField<'a> {
+    ident: &'a syn::Ident,
+    ty: &'a syn::Type,
+    key: String,
+    attrs: FieldAttributes,
+}
+
+/// Parse the attributes of the named fields.
+///
+/// Also returns the keys of the fields that are read from and written to the paragraph, which
+/// are not collected by a `rest` field.
+fn named_fields(
+    fields: &syn::FieldsNamed,
+) -> Result<(Vec<NamedField<'_>>, Vec<String>), syn::Error> {
+    let mut ret = vec![];
+    let mut known = vec![];
+    let mut has_rest = false;
+    for f in fields.named.iter() {
+        let attrs = extract_field_attributes(&f.attrs)?;
+        let ident = f.ident.as_ref().unwrap();
+        // Get key either from the #[deb822(field = "foo")] attribute, or derive it from the
+        // field name
+        let key = attrs.field.clone().unwrap_or_else(|| ident.to_string());
+        if attrs.rest {
+            if has_rest {
+                return Err(syn::Error::new(
+                    f.span(),
+                    "only one field can collect the remaining fields",
+                ));
+            }
+            has_rest = true;
+        } else if !attrs.skip {
+            known.push(key.clone());
+        }
+        ret.push(NamedField {
+            ident,
+            ty: &f.ty,
+            key,
+            attrs,
+        });
+    }
+    Ok((ret, known))
+}
+
+/// Attributes on a struct or enum, or on one of the variants of an enum.
+#[derive(Default)]
+struct ContainerAttributes {
+    tag_field: Option<String>,
+    tag: Option<String>,
+    deny_unknown_fields: bool,
+}
+
+fn extract_container_attributes(
+    attrs: &[syn::Attribute],
+) -> Result<ContainerAttributes, syn::Error> {
+    let mut ret = ContainerAttributes::default();
+    for attr in attrs {
+        if !attr.path().is_ident("deb822") {
+            continue;
+        }
+        let metas: syn::punctuated::Punctuated<syn::Meta, syn::Token![,]> =
+            attr.parse_args_with(syn::punctuated::Punctuated::parse_terminated)?;
+        for meta in metas {
+            let nv = match meta {
+                syn::Meta::Path(path) if path.is_ident("deny_unknown_fields") => {
+                    ret.deny_unknown_fields = true;
+                    continue;
+                }
+                syn::Meta::NameValue(nv)
+                    if nv.path.is_ident("tag_field") || nv.path.is_ident("tag") =>
+                {
+                    nv
+                }
+                meta => {
+                    let path = meta.path();
                     return Err(syn::Error::new(
-                        nv.value.span(),
-                        "expected path in deb822 attribute",
+                        meta.span(),
+                        format!("unsupported attribute: {}", quote! { #path }),
                     ));
                 }
+            };
+            let target = if nv.path.is_ident("tag_field") {
+                &mut ret.tag_field
+            } else {
+                &mut ret.tag
+            };
+            if let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) = nv.value
+            {
+                *target = Some(s.value());
             } else {
                 return Err(syn::Error::new(
-                    nv.span(),
-                    format!("unsupported attribute: {}", nv.path.get_ident().unwrap()),
+                    nv.value.span(),
+                    "expected string literal in deb822 attribute",
                 ));
             }
         }
     }
-    Ok(FieldAttributes {
-        field,
-        serialize_with,
-        deserialize_with,
-    })
+    Ok(ret)
 }
 
-#[proc_macro_derive(FromDeb822, attributes(deb822))]
-pub fn derive_from_deb822(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
-    let name = &input.ident;
+/// How a paragraph is matched to an enum variant.
+enum Rule {
+    /// The field is present.
+    Present(String),
+    /// The field has the given value.
+    Tag(String, String),
+    /// Any paragraph.
+    Any,
+}
 
-    let s = if let syn::Data::Struct(s) = &input.data {
-        s
-    } else {
-        panic!("FromDeb822 can only be derived for structs")
-    };
+impl Rule {
+    fn for_variant(enum_attrs: &ContainerAttributes, v: &syn::Variant) -> Result<Self, syn::Error> {
+        let attrs = extract_container_attributes(&v.attrs)?;
+        let tag_field = attrs.tag_field.or_else(|| enum_attrs.tag_field.clone());
+        let tag = if enum_attrs.tag_field.is_some() {
+            Some(attrs.tag.unwrap_or_else(|| v.ident.to_string()))
+        } else {
+            attrs.tag
+        };
+        match (tag_field, tag) {
+            (Some(field), Some(tag)) => Ok(Rule::Tag(field, tag)),
+            (Some(field), None) => Ok(Rule::Present(field)),
+            (None, None) => Ok(Rule::Any),
+            (None, Some(_)) => Err(syn::Error::new(
+                v.span(),
+                "tag can only be used together with tag_field",
+            )),
+        }
+    }
+
+    /// A description of the paragraphs that match, for error messages.
+    fn describe(&self) -> Option<String> {
+        match self {
+            Rule::Present(field) => Some(field.clone()),
+            Rule::Tag(field, tag) => Some(format!("{}: {}", field, tag)),
+            Rule::Any => None,
+        }
+    }
 
-    let from_fields = s.fields.iter().map(|f| {
-        let attrs = extract_field_attributes(&f.attrs).unwrap();
-            let ident = &f.ident;
-            // Get key either from the #[deb822(field = "foo")] attribute, or derive it from the
-            // field name
-            let key = attrs.field.unwrap_or_else(||ident.as_ref().unwrap().to_string());
+    /// The field the rule checks, if any.
+    fn field(&self) -> Option<&str> {
+        match self {
+            Rule::Present(field) | Rule::Tag(field, _) => Some(field),
+            Rule::Any => None,
+        }
+    }
+
+    /// The condition under which a paragraph matches, or `None` if any paragraph matches.
+    fn condition(&self) -> Option<proc_macro2::TokenStream> {
+        match self {
+            Rule::Present(field) => Some(
+                quote! { deb822_lossless::convert::Deb822LikeParagraph::get(para, #field).is_some() },
+            ),
+            Rule::Tag(field, tag) => Some(
+                quote! { deb822_lossless::convert::Deb822LikeParagraph::get(para, #field).as_deref() == Some(#tag) },
+            ),
+            Rule::Any => None,
+        }
+    }
+}
+
+/// Generate the expressions that read the named fields from `para`.
+///
+/// If `deny_unknown_fields` is set, also generate a check that fails if the paragraph contains
+/// fields other than those read and the given extra fields.
+fn from_named_fields(
+    fields: &syn::FieldsNamed,
+    deny_unknown_fields: Option<&[String]>,
+) -> Result<(Vec<proc_macro2::TokenStream>, proc_macro2::TokenStream), syn::Error> {
+    let (fields, known) = named_fields(fields)?;
+    let check = match deny_unknown_fields {
+        Some(_) if fields.iter().any(|f| f.attrs.rest) => {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "deny_unknown_fields can not be combined with a rest field",
+            ))
+        }
+        Some(extra) => {
+            let allowed = known.iter().chain(extra);
+            quote! {
+                for k in deb822_lossless::convert::ReadableParagraph::keys(para) {
+                    if ![#(#allowed),*].iter().any(|n| n.eq_ignore_ascii_case(&k)) {
+                        return Err(deb822_lossless::convert::Error::UnknownField {
+                            range: para.value_range(&k),
+                            field: k,
+                        });
+                    }
+                }
+            }
+        }
+        None => quote! {},
+    };
+    let exprs = fields
+        .into_iter()
+        .map(|f| {
+            let NamedField {
+                ident,
+                ty,
+                key,
+                attrs,
+            } = f;
+            if attrs.skip {
+                let default = attrs
+                    .default
+                    .unwrap_or_else(|| quote! { Default::default() });
+                return quote! { #ident: #default };
+            }
+            if attrs.rest {
+                // Collect the fields that are not read by any other field
+                return quote! {
+                    #ident: deb822_lossless::convert::ReadableParagraph::keys(para)
+                        .into_iter()
+                        .filter(|k| ![#(#known),*].iter().any(|n| n.eq_ignore_ascii_case(k)))
+                        .filter_map(|k| deb822_lossless::convert::Deb822LikeParagraph::get(para, &k).map(|v| (k, v)))
+                        .collect()
+                };
+            }
             let deserialize_with = if let Some(deserialize_with) = attrs.deserialize_with {
                 quote! { #deserialize_with }
             } else {
                 quote! { std::str::FromStr::from_str }
             };
             // Check if the field is optional or not
-            let ty = &f.ty;
             let is_option = is_option(ty);
+            let parse = quote! {
+                #deserialize_with(&v).map_err(|e| {
+                    deb822_lossless::convert::Error::invalid_value(#key, para.value_range(#key), e)
+                })
+            };
 
-            if is_option {
+            if let Some(default) = attrs.default {
+                // Use the default if the field is missing
+                let value = if is_option {
+                    quote! { Some(#parse?) }
+                } else {
+                    quote! { #parse? }
+                };
+                quote! {
+                    #ident: match deb822_lossless::convert::Deb822LikeParagraph::get(para, #key) {
+                        Some(v) => #value,
+                        None => #default,
+                    }
+                }
+            } else if is_option {
                 // Allow the field to be missing
                 quote! {
-                    #ident: para.get(#key).map(|v| #deserialize_with(&v).map_err(|e| format!("parsing field {}: {}", #key, e))).transpose()?
+                    #ident: deb822_lossless::convert::Deb822LikeParagraph::get(para, #key).map(|v| #parse).transpose()?
                 }
             } else {
                 // The field is required
                 quote! {
-                    #ident: #deserialize_with(&para.get(#key).ok_or_else(|| format!("missing field: {}", #key))?).map_err(|e| format!("parsing field {}: {}", #key, e))?
+                    #ident: {
+                        let v = deb822_lossless::convert::Deb822LikeParagraph::get(para, #key).ok_or_else(|| {
+                            deb822_lossless::convert::Error::MissingField {
+                                field: #key.to_string(),
+                            }
+                        })?;
+                        #parse?
+                    }
                 }
             }
-        }).collect::<Vec<_>>();
-
-    let gen = quote! {
-        impl<P: deb822_lossless::convert::Deb822LikeParagraph> deb822_lossless::FromDeb822Paragraph<P> for #name {
-            fn from_paragraph(para: &P) -> Result<Self, String> {
-                Ok(Self {
-                    #(#from_fields,)*
-                })
-            }
-        }
-    };
-    gen.into()
+        })
+        .collect();
+    Ok((exprs, check))
 }
 
-#[proc_macro_derive(ToDeb822, attributes(deb822))]
-pub fn derive_to_deb822(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
-    let name = &input.ident;
-
-    let s = if let syn::Data::Struct(s) = &input.data {
-        s
-    } else {
-        panic!("Deb822 can only be derived for structs")
-    };
-
+/// Generate the statements that write the named fields to the `fields` list and to `para`.
+///
+/// `access` returns the place expression for a field, e.g. `self.foo`.
+fn to_named_fields(
+    fields: &syn::FieldsNamed,
+    access: impl Fn(&syn::Ident) -> proc_macro2::TokenStream,
+) -> Result<(Vec<proc_macro2::TokenStream>, Vec<proc_macro2::TokenStream>), syn::Error> {
     let mut to_fields = vec![];
     let mut update_fields = vec![];
 
-    for f in s.fields.iter() {
-        let attrs = extract_field_attributes(&f.attrs).unwrap();
-        let ident = &f.ident;
-        let key = attrs
-            .field
-            .unwrap_or_else(|| ident.as_ref().unwrap().to_string());
+    let (fields, known) = named_fields(fields)?;
+    for f in fields {
+        let NamedField {
+            ident,
+            ty,
+            key,
+            attrs,
+        } = f;
+        if attrs.skip {
+            continue;
+        }
+        let value = access(ident);
+
+        if attrs.rest {
+            to_fields.push(quote! {
+                for (k, v) in &#value {
+                    fields.push((k.to_string(), v.to_string()));
+                }
+            });
+            // Remove the fields that are neither known nor collected, then write back the
+            // collected ones
+            update_fields.push(quote! {
+                for k in deb822_lossless::convert::ReadableParagraph::keys(para) {
+                    if ![#(#known),*].iter().any(|n| n.eq_ignore_ascii_case(&k))
+                        && !(&#value).into_iter().any(|(r, _)| r.eq_ignore_ascii_case(&k))
+                    {
+                        para.remove(&k);
+                    }
+                }
+                for (k, v) in &#value {
+                    para.set(k, v);
+                }
+            });
+            continue;
+        }
+
         let serialize_with = if let Some(serialize_with) = attrs.serialize_with {
             quote! { #serialize_with }
         } else {
             quote! { ToString::to_string }
         };
 
-        let ty = &f.ty;
         let is_option = is_option(ty);
 
-        to_fields.push(if is_option {
+        let mut to_field = if is_option {
             quote! {
-                if let Some(v) = &self.#ident {
+                if let Some(v) = &#value {
                     fields.push((#key.to_string(), #serialize_with(&v)));
                 }
             }
         } else {
             quote! {
-                fields.push((#key.to_string(), #serialize_with(&self.#ident)));
+                fields.push((#key.to_string(), #serialize_with(&#value)));
             }
-        });
+        };
 
-        update_fields.push(if is_option {
+        let mut update_field = if is_option {
             quote! {
-                if let Some(v) = &self.#ident {
+                if let Some(v) = &#value {
                     para.set(#key, #serialize_with(&v).as_str());
                 } else {
                     para.remove(#key);
@@ -252,23 +569,365 @@ pub fn derive_to_deb822(input: TokenStream) -> TokenStream {
             }
         } else {
             quote! {
-                para.set(#key, #serialize_with(&self.#ident).as_str());
+                para.set(#key, #serialize_with(&#value).as_str());
             }
-        });
+        };
+
+        if let Some(skip_serializing_if) = attrs.skip_serializing_if {
+            to_field = quote! {
+                if !#skip_serializing_if(&#value) {
+                    #to_field
+                }
+            };
+            update_field = quote! {
+                if #skip_serializing_if(&#value) {
+                    para.remove(#key);
+                } else {
+                    #update_field
+                }
+            };
+        }
+
+        to_fields.push(to_field);
+        update_fields.push(update_field);
+    }
+    Ok((to_fields, update_fields))
+}
+
+/// Returns the type of the field of a newtype struct or variant.
+fn newtype_field(fields: &syn::FieldsUnnamed) -> Result<&syn::Type, syn::Error> {
+    if fields.unnamed.len() != 1 {
+        return Err(syn::Error::new(
+            fields.span(),
+            "tuple structs and variants must have exactly one field",
+        ));
+    }
+    Ok(&fields.unnamed[0].ty)
+}
+
+/// Whether the generated code needs to list the keys of the paragraph.
+///
+/// This is the case if any named field collects the remaining fields, or (when reading) if
+/// unknown fields are denied.
+fn uses_keys(input: &DeriveInput, reading: bool) -> Result<bool, syn::Error> {
+    let has_rest = |fields: &syn::Fields| -> Result<bool, syn::Error> {
+        for f in fields.iter() {
+            if extract_field_attributes(&f.attrs)?.rest {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    };
+    let mut ret = reading && extract_container_attributes(&input.attrs)?.deny_unknown_fields;
+    match &input.data {
+        syn::Data::Struct(s) => ret |= has_rest(&s.fields)?,
+        syn::Data::Enum(e) => {
+            // Updating a paragraph that holds a different variant removes its fields.
+            ret |= !reading;
+            for v in &e.variants {
+                ret |= has_rest(&v.fields)?;
+                ret |= reading && extract_container_attributes(&v.attrs)?.deny_unknown_fields;
+            }
+        }
+        syn::Data::Union(_) => {}
+    }
+    Ok(ret)
+}
+
+/// The bounds on the paragraph type of the generated impls.
+fn paragraph_bounds(uses_keys: bool) -> proc_macro2::TokenStream {
+    if uses_keys {
+        quote! {
+            deb822_lossless::convert::Deb822LikeParagraph
+                + deb822_lossless::convert::ReadableParagraph
+        }
+    } else {
+        quote! { deb822_lossless::convert::Deb822LikeParagraph }
     }
+}
+
+#[proc_macro_derive(FromDeb822, attributes(deb822))]
+pub fn derive_from_deb822(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_deb822(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
 
-    let gen = quote! {
-        impl<P: deb822_lossless::convert::Deb822LikeParagraph> deb822_lossless::ToDeb822Paragraph<P> for #name {
+fn from_deb822(input: &DeriveInput) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let name = &input.ident;
+
+    let mut bounds = vec![];
+    let body = match &input.data {
+        syn::Data::Struct(s) => match &s.fields {
+            syn::Fields::Named(fields) => {
+                let attrs = extract_container_attributes(&input.attrs)?;
+                let (from_fields, check) =
+                    from_named_fields(fields, attrs.deny_unknown_fields.then_some(&[]))?;
+                quote! {
+                    #check
+                    Ok(Self {
+                        #(#from_fields,)*
+                    })
+                }
+            }
+            syn::Fields::Unnamed(fields) => {
+                let ty = newtype_field(fields)?;
+                bounds.push(ty);
+                quote! {
+                    Ok(Self(<#ty as deb822_lossless::FromDeb822Paragraph<P>>::from_paragraph(para)?))
+                }
+            }
+            syn::Fields::Unit => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "FromDeb822 can not be derived for unit structs",
+                ))
+            }
+        },
+        syn::Data::Enum(e) => {
+            let enum_attrs = extract_container_attributes(&input.attrs)?;
+            let mut arms = vec![];
+            let mut expected = vec![];
+            let mut exhaustive = false;
+            for v in &e.variants {
+                if exhaustive {
+                    return Err(syn::Error::new(
+                        v.span(),
+                        "only the last variant can be without a tag_field",
+                    ));
+                }
+                let rule = Rule::for_variant(&enum_attrs, v)?;
+                expected.extend(rule.describe());
+                let ident = &v.ident;
+                let deny_unknown_fields = enum_attrs.deny_unknown_fields
+                    || extract_container_attributes(&v.attrs)?.deny_unknown_fields;
+                // The tag field is read by the enum rather than the variant
+                let tag_fields = rule
+                    .field()
+                    .map(|f| f.to_string())
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                let value = match &v.fields {
+                    syn::Fields::Named(fields) => {
+                        let (from_fields, check) = from_named_fields(
+                            fields,
+                            deny_unknown_fields.then_some(tag_fields.as_slice()),
+                        )?;
+                        quote! {
+                            {
+                                #check
+                                Self::#ident { #(#from_fields,)* }
+                            }
+                        }
+                    }
+                    syn::Fields::Unnamed(fields) => {
+                        let ty = newtype_field(fields)?;
+                        bounds.push(ty);
+                        quote! {
+                            Self::#ident(<#ty as deb822_lossless::FromDeb822Paragraph<P>>::from_paragraph(para)?)
+                        }
+                    }
+                    syn::Fields::Unit => {
+                        return Err(syn::Error::new(
+                            v.span(),
+                            "FromDeb822 can not be derived for unit variants",
+                        ))
+                    }
+                };
+                arms.push(match rule.condition() {
+                    Some(condition) => quote! {
+                        if #condition {
+                            return Ok(#value);
+                        }
+                    },
+                    None => {
+                        exhaustive = true;
+                        quote! { Ok(#value) }
+                    }
+                });
+            }
+            if !exhaustive {
+                let name = name.to_string();
+                arms.push(quote! {
+                    Err(deb822_lossless::convert::Error::NoMatchingVariant {
+                        name: #name.to_string(),
+                        expected: vec![#(#expected.to_string()),*],
+                    })
+                });
+            }
+            quote! { #(#arms)* }
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new(
+                input.span(),
+                "FromDeb822 can not be derived for unions",
+            ))
+        }
+    };
+
+    let paragraph = paragraph_bounds(uses_keys(input, true)?);
+    Ok(quote! {
+        impl<P: #paragraph> deb822_lossless::FromDeb822Paragraph<P> for #name
+        where #(#bounds: deb822_lossless::FromDeb822Paragraph<P>,)*
+        {
+            fn from_paragraph(para: &P) -> Result<Self, deb822_lossless::convert::Error> {
+                #body
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(ToDeb822, attributes(deb822))]
+pub fn derive_to_deb822(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_deb822(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn to_deb822(input: &DeriveInput) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let name = &input.ident;
+
+    let mut bounds = vec![];
+    let (to_body, update_body) = match &input.data {
+        syn::Data::Struct(s) => match &s.fields {
+            syn::Fields::Named(fields) => {
+                let (to_fields, update_fields) =
+                    to_named_fields(fields, |ident| quote! { self.#ident })?;
+                (
+                    quote! {
+                        let mut fields = Vec::<(String, String)>::new();
+                        #(#to_fields)*
+                        fields.into_iter().collect()
+                    },
+                    quote! { #(#update_fields)* },
+                )
+            }
+            syn::Fields::Unnamed(fields) => {
+                let ty = newtype_field(fields)?;
+                bounds.push(ty);
+                (
+                    quote! { <#ty as deb822_lossless::ToDeb822Paragraph<P>>::to_paragraph(&self.0) },
+                    quote! { <#ty as deb822_lossless::ToDeb822Paragraph<P>>::update_paragraph(&self.0, para) },
+                )
+            }
+            syn::Fields::Unit => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "ToDeb822 can not be derived for unit structs",
+                ))
+            }
+        },
+        syn::Data::Enum(e) => {
+            let enum_attrs = extract_container_attributes(&input.attrs)?;
+            let mut to_arms = vec![];
+            let mut update_arms = vec![];
+            // The variant the paragraph currently holds, checked in the same way as by
+            // FromDeb822.
+            let mut current_arms = vec![];
+            let mut current_fallback = quote! { None };
+            for (i, v) in e.variants.iter().enumerate() {
+                match Rule::for_variant(&enum_attrs, v)?.condition() {
+                    Some(condition) => {
+                        current_arms.push(quote! { if #condition { Some(#i) } else })
+                    }
+                    None => {
+                        current_fallback = quote! { Some(#i) };
+                        break;
+                    }
+                }
+            }
+            for (i, v) in e.variants.iter().enumerate() {
+                let ident = &v.ident;
+                let set_tag = match Rule::for_variant(&enum_attrs, v)? {
+                    Rule::Tag(field, tag) => quote! { para.set(#field, #tag); },
+                    Rule::Present(_) | Rule::Any => quote! {},
+                };
+                // If the paragraph holds a different variant, its fields are replaced.
+                let clear = quote! {
+                    if current.is_some_and(|c| c != #i) {
+                        for k in deb822_lossless::convert::ReadableParagraph::keys(para) {
+                            para.remove(&k);
+                        }
+                    }
+                };
+                match &v.fields {
+                    syn::Fields::Named(fields) => {
+                        let (to_fields, update_fields) =
+                            to_named_fields(fields, |ident| quote! { (*#ident) })?;
+                        let idents = fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
+                        to_arms.push(quote! {
+                            Self::#ident { #(#idents,)* } => {
+                                let mut fields = Vec::<(String, String)>::new();
+                                #(#to_fields)*
+                                let mut para: P = fields.into_iter().collect();
+                                #set_tag
+                                para
+                            }
+                        });
+                        update_arms.push(quote! {
+                            Self::#ident { #(#idents,)* } => {
+                                #clear
+                                #(#update_fields)*
+                                #set_tag
+                            }
+                        });
+                    }
+                    syn::Fields::Unnamed(fields) => {
+                        let ty = newtype_field(fields)?;
+                        bounds.push(ty);
+                        to_arms.push(quote! {
+                            Self::#ident(inner) => {
+                                let mut para = <#ty as deb822_lossless::ToDeb822Paragraph<P>>::to_paragraph(inner);
+                                #set_tag
+                                para
+                            }
+                        });
+                        update_arms.push(quote! {
+                            Self::#ident(inner) => {
+                                #clear
+                                <#ty as deb822_lossless::ToDeb822Paragraph<P>>::update_paragraph(inner, para);
+                                #set_tag
+                            }
+                        });
+                    }
+                    syn::Fields::Unit => {
+                        return Err(syn::Error::new(
+                            v.span(),
+                            "ToDeb822 can not be derived for unit variants",
+                        ))
+                    }
+                }
+            }
+            (
+                quote! { match self { #(#to_arms)* } },
+                quote! {
+                    let current: Option<usize> = #(#current_arms)* { #current_fallback };
+                    match self { #(#update_arms)* }
+                },
+            )
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new(
+                input.span(),
+                "ToDeb822 can not be derived for unions",
+            ))
+        }
+    };
+
+    let paragraph = paragraph_bounds(uses_keys(input, false)?);
+    Ok(quote! {
+        impl<P: #paragraph> deb822_lossless::ToDeb822Paragraph<P> for #name
+        where #(#bounds: deb822_lossless::ToDeb822Paragraph<P>,)*
+        {
             fn to_paragraph(&self) -> P {
-                let mut fields = Vec::<(String, String)>::new();
-                #(#to_fields)*
-                fields.into_iter().collect()
+                #to_body
             }
 
             fn update_paragraph(&self, para: &mut P) {
-                #(#update_fields)*
+                #update_body
             }
         }
-    };
-    gen.into()
+    })
 }
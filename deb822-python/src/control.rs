@@ -0,0 +1,315 @@
+//! Classes wrapping debian-control's lossless parser.
+use crate::lossless::{Deb822, Paragraph};
+use crate::share;
+use pyo3::exceptions::{PyIndexError, PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use std::str::FromStr;
+
+/// A Debian control file.
+#[pyclass(unsendable)]
+pub struct Control(debian_control::lossless::Control);
+
+#[pymethods]
+impl Control {
+    /// Parse a control file. Raises `ValueError` if the text contains syntax errors.
+    #[new]
+    #[pyo3(signature = (text=""))]
+    fn new(text: &str) -> PyResult<Self> {
+        debian_control::lossless::Control::from_str(text)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// The source package, or `None` if there is none.
+    #[getter]
+    fn source(&self) -> Option<Source> {
+        self.0.source().map(Source)
+    }
+
+    /// The binary packages.
+    #[getter]
+    fn binaries(&self) -> Vec<Binary> {
+        self.0.binaries().map(Binary).collect()
+    }
+
+    /// Add a source package paragraph.
+    fn add_source(&mut self, name: &str) -> Source {
+        Source(self.0.add_source(name))
+    }
+
+    /// Add a binary package paragraph.
+    fn add_binary(&mut self, name: &str) -> Binary {
+        Binary(self.0.add_binary(name))
+    }
+
+    /// Wrap and sort the fields of all paragraphs, in the style of `wrap-and-sort`.
+    ///
+    /// `indentation` is the number of spaces to indent continuation lines with; by default
+    /// they are aligned with the field value.
+    #[pyo3(signature = (indentation=None, immediate_empty_line=false, max_line_length_one_liner=None))]
+    fn wrap_and_sort(
+        &mut self,
+        indentation: Option<u32>,
+        immediate_empty_line: bool,
+        max_line_length_one_liner: Option<usize>,
+    ) {
+        let indentation = match indentation {
+            Some(spaces) => deb822_lossless::Indentation::Spaces(spaces),
+            None => deb822_lossless::Indentation::FieldNameLength,
+        };
+        self.0
+            .wrap_and_sort(indentation, immediate_empty_line, max_line_length_one_liner);
+    }
+
+    /// The underlying deb822 file.
+    fn as_deb822(&self) -> Deb822 {
+        Deb822(share(self.0.as_deb822()))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Control({:?})", self.0.to_string())
+    }
+}
+
+/// The source package paragraph of a control file.
+///
+/// Fields can be accessed by name, as with `Paragraph`; relation fields can be accessed as
+/// `Relations` objects with `relations()` and `set_relations()`.
+#[pyclass(unsendable)]
+pub struct Source(debian_control::lossless::Source);
+
+#[pymethods]
+impl Source {
+    /// The name of the source package.
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.0.name()
+    }
+
+    /// The underlying paragraph.
+    #[getter]
+    fn paragraph(&self) -> Paragraph {
+        Paragraph::shared(self.0.as_deb822())
+    }
+
+    /// Returns the value of a relation field such as `Build-Depends`, or `None` if the field
+    /// is not present.
+    ///
+    /// The returned object is a copy; pass it to `set_relations()` to change the field.
+    fn relations(&self, field: &str) -> PyResult<Option<Relations>> {
+        get_relations(self.0.as_deb822(), field)
+    }
+
+    /// Set a relation field. The field is removed if `relations` is `None` or empty.
+    #[pyo3(signature = (field, relations))]
+    fn set_relations(&mut self, field: &str, relations: Option<PyRef<Relations>>) {
+        set_relations(self.0.as_mut_deb822(), field, relations.as_deref());
+    }
+
+    fn __getitem__(&self, key: &str) -> PyResult<String> {
+        self.0
+            .as_deb822()
+            .get(key)
+            .ok_or_else(|| PyKeyError::new_err(key.to_string()))
+    }
+
+    fn __setitem__(&mut self, key: &str, value: &str) {
+        self.0.as_mut_deb822().set(key, value);
+    }
+
+    fn __delitem__(&mut self, key: &str) -> PyResult<()> {
+        let paragraph = self.0.as_mut_deb822();
+        if !paragraph.contains_key(key) {
+            return Err(PyKeyError::new_err(key.to_string()));
+        }
+        paragraph.remove(key);
+        Ok(())
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        self.0.as_deb822().contains_key(key)
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// A binary package paragraph of a control file.
+///
+/// Fields can be accessed by name, as with `Paragraph`; relation fields can be accessed as
+/// `Relations` objects with `relations()` and `set_relations()`.
+#[pyclass(unsendable)]
+pub struct Binary(debian_control::lossless::Binary);
+
+#[pymethods]
+impl Binary {
+    /// The name of the binary package.
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.0.name()
+    }
+
+    /// The underlying paragraph.
+    #[getter]
+    fn paragraph(&self) -> Paragraph {
+        Paragraph::shared(self.0.as_deb822())
+    }
+
+    /// Returns the value of a relation field such as `Depends`, or `None` if the field is not
+    /// present.
+    ///
+    /// The returned object is a copy; pass it to `set_relations()` to change the field.
+    fn relations(&self, field: &str) -> PyResult<Option<Relations>> {
+        get_relations(self.0.as_deb822(), field)
+    }
+
+    /// Set a relation field. The field is removed if `relations` is `None` or empty.
+    #[pyo3(signature = (field, relations))]
+    fn set_relations(&mut self, field: &str, relations: Option<PyRef<Relations>>) {
+        set_relations(self.0.as_mut_deb822(), field, relations.as_deref());
+    }
+
+    fn __getitem__(&self, key: &str) -> PyResult<String> {
+        self.0
+            .as_deb822()
+            .get(key)
+            .ok_or_else(|| PyKeyError::new_err(key.to_string()))
+    }
+
+    fn __setitem__(&mut self, key: &str, value: &str) {
+        self.0.as_mut_deb822().set(key, value);
+    }
+
+    fn __delitem__(&mut self, key: &str) -> PyResult<()> {
+        let paragraph = self.0.as_mut_deb822();
+        if !paragraph.contains_key(key) {
+            return Err(PyKeyError::new_err(key.to_string()));
+        }
+        paragraph.remove(key);
+        Ok(())
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        self.0.as_deb822().contains_key(key)
+    }
+
+    fn __str__(&self) -> String {
+        self.0.as_deb822().to_string()
+    }
+}
+
+fn get_relations(
+    paragraph: &deb822_lossless::Paragraph,
+    field: &str,
+) -> PyResult<Option<Relations>> {
+    paragraph
+        .get(field)
+        .map(|value| Relations::new(&value))
+        .transpose()
+}
+
+fn set_relations(
+    paragraph: &mut deb822_lossless::Paragraph,
+    field: &str,
+    relations: Option<&Relations>,
+) {
+    match relations {
+        Some(relations) if !relations.0.is_empty() => {
+            paragraph.set(field, &relations.0.to_string())
+        }
+        _ => paragraph.remove(field),
+    }
+}
+
+/// A list of package relations, as found in fields like `Depends`.
+///
+/// Each item is a string containing one or more alternatives, e.g. `"foo (>= 1.0) | bar"`.
+#[pyclass(unsendable)]
+pub struct Relations(debian_control::lossless::Relations);
+
+#[pymethods]
+impl Relations {
+    /// Parse a list of relations, which may contain substitution variables. Raises
+    /// `ValueError` if the text is not valid.
+    #[new]
+    #[pyo3(signature = (text=""))]
+    fn new(text: &str) -> PyResult<Self> {
+        let (relations, errors) = debian_control::lossless::Relations::parse_relaxed(text, true);
+        if !errors.is_empty() {
+            return Err(PyValueError::new_err(errors.join("\n")));
+        }
+        Ok(Self(relations))
+    }
+
+    /// Append an entry.
+    fn append(&mut self, entry: &str) -> PyResult<()> {
+        let entry = parse_entry(entry)?;
+        self.0.push(entry);
+        Ok(())
+    }
+
+    /// Insert an entry at the given index.
+    fn insert(&mut self, index: usize, entry: &str) -> PyResult<()> {
+        let entry = parse_entry(entry)?;
+        self.0.insert(index.min(self.0.len()), entry);
+        Ok(())
+    }
+
+    /// Returns the substitution variables, such as `${misc:Depends}`.
+    fn substvars(&self) -> Vec<String> {
+        self.0.substvars().collect()
+    }
+
+    /// Returns a copy with the entries sorted and consistently formatted.
+    fn wrap_and_sort(&self) -> PyResult<Self> {
+        Self::new(&self.0.to_string()).map(|r| Self(r.0.wrap_and_sort()))
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    fn __getitem__(&self, index: usize) -> PyResult<String> {
+        self.0
+            .get_entry(index)
+            .map(|e| e.to_string())
+            .ok_or_else(|| PyIndexError::new_err(index))
+    }
+
+    fn __delitem__(&mut self, index: usize) -> PyResult<()> {
+        if index >= self.0.len() {
+            return Err(PyIndexError::new_err(index));
+        }
+        self.0.remove_entry(index);
+        Ok(())
+    }
+
+    fn __iter__(&self, py: Python<'_>) -> PyResult<PyObject> {
+        Ok(self
+            .0
+            .entries()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+            .into_pyobject(py)?
+            .try_iter()?
+            .into_any()
+            .unbind())
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Relations({:?})", self.0.to_string())
+    }
+}
+
+fn parse_entry(entry: &str) -> PyResult<debian_control::lossless::relations::Entry> {
+    debian_control::lossless::relations::Entry::from_str(entry).map_err(PyValueError::new_err)
+}
@@ -0,0 +1,118 @@
+//! Classes wrapping debian-copyright's lossless parser.
+use crate::lossless::Paragraph;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// A license, as a `(name, text)` tuple. Either element may be `None`.
+type License = (Option<String>, Option<String>);
+
+fn license_tuple(license: debian_copyright::License) -> License {
+    (
+        license.name().map(|n| n.to_string()),
+        license.text().map(|t| t.to_string()),
+    )
+}
+
+/// A machine-readable debian/copyright file, as described by DEP-5.
+#[pyclass(unsendable)]
+pub struct Copyright(debian_copyright::lossless::Copyright);
+
+#[pymethods]
+impl Copyright {
+    /// Parse a copyright file. Raises `ValueError` if the text contains syntax errors or is
+    /// not machine-readable.
+    ///
+    /// Without `text`, creates a new file with just a header paragraph.
+    #[new]
+    #[pyo3(signature = (text=None))]
+    fn new(text: Option<&str>) -> PyResult<Self> {
+        match text {
+            Some(text) => debian_copyright::lossless::Copyright::from_str(text)
+                .map(Self)
+                .map_err(|e| PyValueError::new_err(e.to_string())),
+            None => Ok(Self(debian_copyright::lossless::Copyright::new())),
+        }
+    }
+
+    /// The header paragraph, or `None` if the file is empty.
+    #[getter]
+    fn header(&self) -> Option<Paragraph> {
+        self.0.header().map(|h| Paragraph::shared(h.as_deb822()))
+    }
+
+    /// Returns the `Files` paragraphs.
+    fn files_paragraphs(&self) -> Vec<FilesParagraph> {
+        self.0.iter_files().map(FilesParagraph).collect()
+    }
+
+    /// Returns the `Files` paragraph that applies to the given path, or `None`.
+    fn find_files(&self, path: PathBuf) -> Option<FilesParagraph> {
+        self.0.find_files(&path).map(FilesParagraph)
+    }
+
+    /// Returns the license that applies to the given path as a `(name, text)` tuple, or
+    /// `None`.
+    ///
+    /// If the `Files` paragraph only names the license, its text is looked up in the
+    /// standalone license paragraphs.
+    fn find_license_for_file(&self, path: PathBuf) -> Option<License> {
+        self.0.find_license_for_file(&path).map(license_tuple)
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Copyright({:?})", self.0.to_string())
+    }
+}
+
+/// A `Files` paragraph in a copyright file.
+#[pyclass(unsendable)]
+pub struct FilesParagraph(debian_copyright::lossless::FilesParagraph);
+
+#[pymethods]
+impl FilesParagraph {
+    /// The file patterns the paragraph applies to.
+    #[getter]
+    fn files(&self) -> Vec<String> {
+        self.0.files()
+    }
+
+    /// The copyright holders, one per line.
+    #[getter]
+    fn get_copyright(&self) -> Vec<String> {
+        self.0.copyright()
+    }
+
+    #[setter]
+    fn set_copyright(&mut self, holders: Vec<String>) {
+        let holders = holders.iter().map(|h| h.as_str()).collect::<Vec<_>>();
+        self.0.set_copyright(&holders);
+    }
+
+    /// The comment on the paragraph, if any.
+    #[getter]
+    fn get_comment(&self) -> Option<String> {
+        self.0.comment()
+    }
+
+    #[setter]
+    fn set_comment(&mut self, comment: &str) {
+        self.0.set_comment(comment);
+    }
+
+    /// The license of the paragraph as a `(name, text)` tuple, or `None`.
+    #[getter]
+    fn license(&self) -> Option<License> {
+        self.0.license().map(license_tuple)
+    }
+
+    /// Whether the paragraph applies to the given path.
+    fn matches(&self, path: PathBuf) -> bool {
+        self.0.matches(&path)
+    }
+}
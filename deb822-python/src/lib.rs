@@ -0,0 +1,45 @@
+//! Python bindings for deb822-lossless, debian-control and debian-copyright.
+//!
+//! The module exposes the lossless parsers as Python classes, so that Python code can make
+//! formatting-preserving edits to deb822 files:
+//!
+//! ```python
+//! from deb822_lossless import Control
+//!
+//! with open("debian/control") as f:
+//!     control = Control(f.read())
+//! control.source["Section"] = "devel"
+//! with open("debian/control", "w") as f:
+//!     f.write(str(control))
+//! ```
+//!
+//! Objects returned by the classes share the underlying syntax tree with the object they were
+//! obtained from: editing a paragraph returned by `Deb822.paragraphs()` edits the document.
+// The code generated by `#[pymethods]` for methods returning `PyResult` triggers this lint.
+#![allow(clippy::useless_conversion)]
+use pyo3::prelude::*;
+
+mod control;
+mod copyright;
+mod lossless;
+
+/// Returns a new handle to the same syntax tree node as `node`.
+fn share<N: rowan::ast::AstNode>(node: &N) -> N {
+    N::cast(node.syntax().clone()).expect("node kind is unchanged")
+}
+
+/// Formatting-preserving parser and editor for deb822 files.
+#[pymodule]
+#[pyo3(name = "deb822_lossless")]
+fn deb822_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<lossless::Deb822>()?;
+    m.add_class::<lossless::Paragraph>()?;
+    m.add_class::<lossless::Entry>()?;
+    m.add_class::<control::Control>()?;
+    m.add_class::<control::Source>()?;
+    m.add_class::<control::Binary>()?;
+    m.add_class::<control::Relations>()?;
+    m.add_class::<copyright::Copyright>()?;
+    m.add_class::<copyright::FilesParagraph>()?;
+    Ok(())
+}
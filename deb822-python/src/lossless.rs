@@ -0,0 +1,283 @@
+//! Classes wrapping the lossless deb822 parser.
+use crate::share;
+use pyo3::exceptions::{PyIndexError, PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use std::str::FromStr;
+
+/// A deb822 file, consisting of paragraphs.
+#[pyclass(unsendable)]
+pub struct Deb822(pub(crate) deb822_lossless::Deb822);
+
+#[pymethods]
+impl Deb822 {
+    /// Parse a deb822 file. Raises `ValueError` if the text contains syntax errors.
+    #[new]
+    #[pyo3(signature = (text=""))]
+    fn new(text: &str) -> PyResult<Self> {
+        deb822_lossless::Deb822::from_str(text)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Parse a deb822 file, returning the file along with a list of syntax error messages.
+    #[staticmethod]
+    fn from_str_relaxed(text: &str) -> (Self, Vec<String>) {
+        let (deb822, errors) = deb822_lossless::Deb822::from_str_relaxed(text);
+        (Self(deb822), errors.iter().map(|e| e.to_string()).collect())
+    }
+
+    /// Read a deb822 file from the given path.
+    #[staticmethod]
+    fn from_file(path: std::path::PathBuf) -> PyResult<Self> {
+        match deb822_lossless::Deb822::from_file(path) {
+            Ok(deb822) => Ok(Self(deb822)),
+            Err(deb822_lossless::Error::IoError(e)) => Err(e.into()),
+            Err(e) => Err(PyValueError::new_err(e.to_string())),
+        }
+    }
+
+    /// Returns the paragraphs in the file.
+    fn paragraphs(&self) -> Vec<Paragraph> {
+        self.0.paragraphs().map(Paragraph).collect()
+    }
+
+    /// Add a new empty paragraph to the end of the file.
+    fn add_paragraph(&mut self) -> Paragraph {
+        Paragraph(self.0.add_paragraph())
+    }
+
+    /// Insert a new empty paragraph at the given index.
+    fn insert_paragraph(&mut self, index: usize) -> PyResult<Paragraph> {
+        if index > self.__len__() {
+            return Err(PyIndexError::new_err(index));
+        }
+        Ok(Paragraph(self.0.insert_paragraph(index)))
+    }
+
+    /// Remove the paragraph at the given index.
+    fn remove_paragraph(&mut self, index: usize) -> PyResult<()> {
+        if index >= self.__len__() {
+            return Err(PyIndexError::new_err(index));
+        }
+        self.0.remove_paragraph(index);
+        Ok(())
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.paragraphs().count()
+    }
+
+    fn __getitem__(&self, index: usize) -> PyResult<Paragraph> {
+        self.0
+            .paragraphs()
+            .nth(index)
+            .map(Paragraph)
+            .ok_or_else(|| PyIndexError::new_err(index))
+    }
+
+    fn __iter__(&self, py: Python<'_>) -> PyResult<PyObject> {
+        Ok(self
+            .paragraphs()
+            .into_pyobject(py)?
+            .try_iter()?
+            .into_any()
+            .unbind())
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Deb822({:?})", self.0.to_string())
+    }
+}
+
+/// A paragraph in a deb822 file, mapping field names to values.
+///
+/// Field names are compared case-insensitively.
+#[pyclass(unsendable)]
+pub struct Paragraph(pub(crate) deb822_lossless::Paragraph);
+
+#[pymethods]
+impl Paragraph {
+    /// Parse a single paragraph. Raises `ValueError` if the text contains syntax errors.
+    #[new]
+    #[pyo3(signature = (text=None))]
+    fn new(text: Option<&str>) -> PyResult<Self> {
+        match text {
+            Some(text) => deb822_lossless::Paragraph::from_str(text)
+                .map(Self)
+                .map_err(|e| PyValueError::new_err(e.to_string())),
+            None => Ok(Self(deb822_lossless::Paragraph::new())),
+        }
+    }
+
+    /// Returns the value of a field, or `default` if it is not present.
+    #[pyo3(signature = (key, default=None))]
+    fn get(&self, key: &str, default: Option<String>) -> Option<String> {
+        self.0.get(key).or(default)
+    }
+
+    /// Returns the values of all occurrences of a field.
+    fn get_all(&self, key: &str) -> Vec<String> {
+        self.0.get_all(key).collect()
+    }
+
+    /// Returns the field names, in order.
+    fn keys(&self) -> Vec<String> {
+        self.0.keys().collect()
+    }
+
+    /// Returns the field values, in order.
+    fn values(&self) -> Vec<String> {
+        self.0.items().map(|(_, v)| v).collect()
+    }
+
+    /// Returns the `(name, value)` pairs of the fields, in order.
+    fn items(&self) -> Vec<(String, String)> {
+        self.0.items().collect()
+    }
+
+    /// Returns the fields, in order.
+    fn entries(&self) -> Vec<Entry> {
+        self.0.entries().map(Entry).collect()
+    }
+
+    /// Append a new field, even if a field with the same name already exists.
+    fn insert(&mut self, key: &str, value: &str) {
+        self.0.insert(key, value);
+    }
+
+    /// Insert a new field before the field `before`. Raises `KeyError` if there is no such
+    /// field.
+    fn insert_before(&mut self, before: &str, key: &str, value: &str) -> PyResult<()> {
+        if !self.0.insert_before(before, key, value) {
+            return Err(PyKeyError::new_err(before.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Insert a new field after the field `after`. Raises `KeyError` if there is no such
+    /// field.
+    fn insert_after(&mut self, after: &str, key: &str, value: &str) -> PyResult<()> {
+        if !self.0.insert_after(after, key, value) {
+            return Err(PyKeyError::new_err(after.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Set a field, placing it according to `field_order` if it does not exist yet.
+    fn set_with_field_order(&mut self, key: &str, value: &str, field_order: Vec<String>) {
+        let field_order = field_order.iter().map(|f| f.as_str()).collect::<Vec<_>>();
+        self.0.set_with_field_order(key, value, &field_order);
+    }
+
+    /// Rename a field, keeping its value and formatting. Raises `KeyError` if there is no such
+    /// field.
+    fn rename(&mut self, old_key: &str, new_key: &str) -> PyResult<()> {
+        if !self.0.rename(old_key, new_key) {
+            return Err(PyKeyError::new_err(old_key.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Returns the comment lines above the paragraph.
+    fn leading_comments(&self) -> Vec<String> {
+        self.0.leading_comments()
+    }
+
+    /// Replace the comment lines above the paragraph.
+    fn set_leading_comments(&mut self, comments: Vec<String>) {
+        let comments = comments.iter().map(|c| c.as_str()).collect::<Vec<_>>();
+        self.0.set_leading_comments(&comments);
+    }
+
+    fn __getitem__(&self, key: &str) -> PyResult<String> {
+        self.0
+            .get(key)
+            .ok_or_else(|| PyKeyError::new_err(key.to_string()))
+    }
+
+    fn __setitem__(&mut self, key: &str, value: &str) {
+        self.0.set(key, value);
+    }
+
+    fn __delitem__(&mut self, key: &str) -> PyResult<()> {
+        if !self.0.contains_key(key) {
+            return Err(PyKeyError::new_err(key.to_string()));
+        }
+        self.0.remove(key);
+        Ok(())
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.entries().count()
+    }
+
+    fn __iter__(&self, py: Python<'_>) -> PyResult<PyObject> {
+        Ok(self
+            .keys()
+            .into_pyobject(py)?
+            .try_iter()?
+            .into_any()
+            .unbind())
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Paragraph({:?})", self.0.to_string())
+    }
+}
+
+impl Paragraph {
+    /// Returns a new handle to the given paragraph, sharing its syntax tree.
+    pub(crate) fn shared(paragraph: &deb822_lossless::Paragraph) -> Self {
+        Self(share(paragraph))
+    }
+}
+
+/// A single field in a paragraph.
+#[pyclass(unsendable)]
+pub struct Entry(deb822_lossless::lossless::Entry);
+
+#[pymethods]
+impl Entry {
+    /// The name of the field.
+    #[getter]
+    fn key(&self) -> Option<String> {
+        self.0.key()
+    }
+
+    /// The value of the field, with continuation lines joined by newlines.
+    #[getter]
+    fn value(&self) -> String {
+        self.0.value()
+    }
+
+    /// The comment lines directly above the field.
+    fn leading_comments(&self) -> Vec<String> {
+        self.0.leading_comments()
+    }
+
+    /// The `(start, end)` byte offsets of the field in the file.
+    fn text_range(&self) -> (usize, usize) {
+        let range = self.0.text_range();
+        (range.start().into(), range.end().into())
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Entry({:?})", self.0.to_string())
+    }
+}
@@ -0,0 +1,292 @@
+import os
+import tempfile
+import unittest
+
+from deb822_lossless import (
+    Binary,
+    Control,
+    Copyright,
+    Deb822,
+    FilesParagraph,
+    Paragraph,
+    Relations,
+    Source,
+)
+
+
+class Deb822Tests(unittest.TestCase):
+    def test_roundtrip(self):
+        text = "# Comment\nFoo: bar\n\nBaz: blah\n continued\n"
+        self.assertEqual(text, str(Deb822(text)))
+
+    def test_paragraphs(self):
+        d = Deb822("Foo: bar\n\nBaz: blah\n")
+        self.assertEqual(2, len(d))
+        self.assertEqual(["Foo"], list(d[0]))
+        self.assertEqual(["Baz"], [p.keys()[0] for p in d][1:])
+        with self.assertRaises(IndexError):
+            d[2]
+
+    def test_syntax_error(self):
+        with self.assertRaises(ValueError):
+            Deb822("Foo bar\n")
+        d, errors = Deb822.from_str_relaxed("Foo bar\n\nBaz: blah\n")
+        self.assertEqual(1, len(errors))
+        self.assertEqual("blah", d[1]["Baz"])
+
+    def test_from_file(self):
+        with tempfile.TemporaryDirectory() as td:
+            path = os.path.join(td, "control")
+            with open(path, "w") as f:
+                f.write("Foo: bar\n")
+            self.assertEqual("bar", Deb822.from_file(path)[0]["Foo"])
+            with self.assertRaises(FileNotFoundError):
+                Deb822.from_file(os.path.join(td, "missing"))
+
+    def test_edit_paragraph(self):
+        d = Deb822("Foo: bar\n\nBaz: blah\n")
+        d[0]["Foo"] = "new"
+        d[1]["Extra"] = "value"
+        self.assertEqual("Foo: new\n\nBaz: blah\nExtra: value\n", str(d))
+
+    def test_add_remove_paragraph(self):
+        d = Deb822("Foo: bar\n")
+        d.add_paragraph()["Baz"] = "blah"
+        d.insert_paragraph(0)["First"] = "yes"
+        self.assertEqual(["First", "Foo", "Baz"], [p.keys()[0] for p in d])
+        d.remove_paragraph(1)
+        self.assertEqual(["First", "Baz"], [p.keys()[0] for p in d])
+        with self.assertRaises(IndexError):
+            d.remove_paragraph(5)
+
+
+class ParagraphTests(unittest.TestCase):
+    def test_mapping(self):
+        p = Paragraph("Foo: bar\nBaz: blah\n")
+        self.assertEqual("bar", p["foo"])
+        self.assertIn("Baz", p)
+        self.assertNotIn("Other", p)
+        self.assertEqual(2, len(p))
+        self.assertEqual(["Foo", "Baz"], list(p))
+        self.assertEqual([("Foo", "bar"), ("Baz", "blah")], p.items())
+        self.assertEqual(["bar", "blah"], p.values())
+        self.assertIsNone(p.get("Other"))
+        self.assertEqual("x", p.get("Other", "x"))
+        with self.assertRaises(KeyError):
+            p["Other"]
+
+    def test_set_and_delete(self):
+        p = Paragraph("Foo: bar\nBaz: blah\n")
+        p["Foo"] = "new"
+        del p["Baz"]
+        self.assertEqual("Foo: new\n", str(p))
+        with self.assertRaises(KeyError):
+            del p["Baz"]
+
+    def test_new(self):
+        p = Paragraph()
+        p["Foo"] = "bar"
+        self.assertEqual("Foo: bar\n", str(p))
+
+    def test_insert(self):
+        p = Paragraph("A: 1\nC: 3\n")
+        p.insert_before("C", "B", "2")
+        p.insert_after("C", "D", "4")
+        self.assertEqual(["A", "B", "C", "D"], p.keys())
+        with self.assertRaises(KeyError):
+            p.insert_before("Z", "E", "5")
+
+    def test_set_with_field_order(self):
+        p = Paragraph("A: 1\nC: 3\n")
+        p.set_with_field_order("B", "2", ["A", "B", "C"])
+        self.assertEqual("A: 1\nB: 2\nC: 3\n", str(p))
+
+    def test_rename(self):
+        p = Paragraph("Foo: bar\n")
+        p.rename("Foo", "Bar")
+        self.assertEqual("Bar: bar\n", str(p))
+        with self.assertRaises(KeyError):
+            p.rename("Foo", "Baz")
+
+    def test_duplicates(self):
+        p = Paragraph("Foo: a\nFoo: b\n")
+        self.assertEqual(["a", "b"], p.get_all("Foo"))
+
+    def test_comments(self):
+        d = Deb822("# A comment\nFoo: bar\n")
+        self.assertEqual(["# A comment"], d[0].leading_comments())
+        d[0].set_leading_comments(["Another"])
+        self.assertEqual("# Another\nFoo: bar\n", str(d))
+
+    def test_entries(self):
+        p = Paragraph("Foo: bar\nBaz: blah\n more\n")
+        entries = p.entries()
+        self.assertEqual(["Foo", "Baz"], [e.key for e in entries])
+        self.assertEqual("blah\nmore", entries[1].value)
+        self.assertEqual((9, 25), entries[1].text_range())
+        self.assertEqual("Foo: bar\n", str(entries[0]))
+
+
+CONTROL = """\
+Source: foo
+Build-Depends: debhelper-compat (= 13), python3
+Maintainer: Jane Packager <jane@example.com>
+
+Package: foo
+Architecture: all
+Depends: ${misc:Depends}, python3
+Description: A package
+ With a description.
+"""
+
+
+class ControlTests(unittest.TestCase):
+    def test_roundtrip(self):
+        self.assertEqual(CONTROL, str(Control(CONTROL)))
+
+    def test_source(self):
+        c = Control(CONTROL)
+        self.assertIsInstance(c.source, Source)
+        self.assertEqual("foo", c.source.name)
+        self.assertEqual("Jane Packager <jane@example.com>", c.source["Maintainer"])
+        c.source["Section"] = "python"
+        self.assertIn("Section: python\n", str(c))
+
+    def test_binaries(self):
+        c = Control(CONTROL)
+        binaries = c.binaries
+        self.assertEqual(1, len(binaries))
+        self.assertIsInstance(binaries[0], Binary)
+        self.assertEqual("foo", binaries[0].name)
+        self.assertIn("Architecture", binaries[0])
+        del binaries[0]["Architecture"]
+        self.assertNotIn("Architecture", str(c))
+
+    def test_add(self):
+        c = Control()
+        c.add_source("foo")
+        c.add_binary("python3-foo")["Architecture"] = "all"
+        self.assertEqual(
+            "Source: foo\n\nPackage: python3-foo\nArchitecture: all\n", str(c)
+        )
+
+    def test_relations(self):
+        c = Control(CONTROL)
+        binary = c.binaries[0]
+        depends = binary.relations("Depends")
+        self.assertEqual(["python3"], list(depends))
+        self.assertEqual(["${misc:Depends}"], depends.substvars())
+        depends.append("python3-bar (>= 1.0)")
+        binary.set_relations("Depends", depends)
+        self.assertEqual(
+            "${misc:Depends}, python3, python3-bar (>= 1.0)", binary["Depends"]
+        )
+        self.assertIsNone(binary.relations("Recommends"))
+        binary.set_relations("Depends", None)
+        self.assertNotIn("Depends", binary)
+
+    def test_paragraph(self):
+        c = Control(CONTROL)
+        paragraph = c.source.paragraph
+        self.assertIsInstance(paragraph, Paragraph)
+        paragraph["Priority"] = "optional"
+        self.assertEqual("optional", c.source["Priority"])
+
+    def test_as_deb822(self):
+        c = Control(CONTROL)
+        self.assertEqual(2, len(c.as_deb822()))
+
+    def test_wrap_and_sort(self):
+        c = Control("Source: foo\nBuild-Depends: zlib, debhelper-compat (= 13)\n")
+        c.wrap_and_sort()
+        self.assertEqual(
+            "Source: foo\nBuild-Depends: debhelper-compat (= 13), zlib\n", str(c)
+        )
+
+
+class RelationsTests(unittest.TestCase):
+    def test_parse(self):
+        r = Relations("foo (>= 1.0) | bar, baz")
+        self.assertEqual(2, len(r))
+        self.assertEqual("foo (>= 1.0) | bar", r[0])
+        with self.assertRaises(IndexError):
+            r[2]
+
+    def test_edit(self):
+        r = Relations("foo, bar")
+        r.insert(0, "blah")
+        del r[1]
+        self.assertEqual("blah, bar", str(r))
+
+    def test_invalid(self):
+        with self.assertRaises(ValueError):
+            Relations("foo (>= 1.0")
+
+    def test_wrap_and_sort(self):
+        self.assertEqual("bar, foo", str(Relations("foo, bar").wrap_and_sort()))
+
+
+COPYRIGHT = """\
+Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/
+Upstream-Name: example
+
+Files: *
+Copyright: 2019 John Doe
+License: GPL-3+
+
+Files: debian/*
+Copyright: 2019 Jane Packager
+License: GPL-3+
+
+License: GPL-3+
+ This program is free software.
+"""
+
+
+class CopyrightTests(unittest.TestCase):
+    def test_roundtrip(self):
+        self.assertEqual(COPYRIGHT, str(Copyright(COPYRIGHT)))
+
+    def test_not_machine_readable(self):
+        with self.assertRaises(ValueError):
+            Copyright("This is not machine readable.\n")
+
+    def test_new(self):
+        c = Copyright()
+        self.assertTrue(c.header["Format"].startswith("https://"))
+
+    def test_header(self):
+        c = Copyright(COPYRIGHT)
+        c.header["Upstream-Name"] = "other"
+        self.assertIn("Upstream-Name: other\n", str(c))
+
+    def test_files(self):
+        c = Copyright(COPYRIGHT)
+        files = c.files_paragraphs()
+        self.assertEqual([["*"], ["debian/*"]], [f.files for f in files])
+        self.assertIsInstance(files[0], FilesParagraph)
+        self.assertEqual(["2019 John Doe"], files[0].copyright)
+        self.assertEqual(("GPL-3+", None), files[0].license)
+        self.assertTrue(files[1].matches("debian/rules"))
+        self.assertEqual(["debian/*"], c.find_files("debian/rules").files)
+
+    def test_edit_files(self):
+        c = Copyright(COPYRIGHT)
+        files = c.files_paragraphs()[0]
+        files.copyright = ["2019 John Doe", "2020 Someone Else"]
+        files.comment = "Checked"
+        self.assertEqual("Checked", files.comment)
+        self.assertIn(
+            "Copyright: 2019 John Doe\n 2020 Someone Else\n", str(c)
+        )
+
+    def test_find_license_for_file(self):
+        c = Copyright(COPYRIGHT)
+        self.assertEqual(
+            ("GPL-3+", "This program is free software."),
+            c.find_license_for_file("src/main.c"),
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
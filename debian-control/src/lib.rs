@@ -50,6 +50,7 @@ pub use lossless::changes;
 pub use lossless::control;
 #[cfg(feature = "lossless")]
 pub mod pgp;
+pub mod query;
 pub mod relations;
 pub mod vcs;
 
@@ -204,20 +204,34 @@ impl Changes {
         Ok(Self(para))
     }
 
-    /// Read a Changes file from a file, allowing syntax errors.
-    pub fn from_file_relaxed<P: AsRef<std::path::Path>>(
-        path: P,
-    ) -> Result<(Self, Vec<String>), std::io::Error> {
-        let (mut deb822, mut errors) = deb822_lossless::Deb822::from_file_relaxed(path)?;
+    /// Take the first paragraph of a relaxed parse, reporting any further ones as errors.
+    fn from_deb822_relaxed(
+        mut deb822: deb822_lossless::Deb822,
+        mut errors: Vec<deb822_lossless::PositionedParseError>,
+    ) -> (Self, Vec<deb822_lossless::PositionedParseError>) {
         let mut paras = deb822.paragraphs();
         let para = match paras.next() {
             Some(para) => para,
             None => deb822.add_paragraph(),
         };
-        if paras.next().is_some() {
-            errors.push("multiple paragraphs found".to_string());
+        if let Some(extra) = paras.next() {
+            errors.push(deb822_lossless::PositionedParseError::new(
+                &deb822.to_string(),
+                deb822_lossless::ParseErrorKind::MultipleParagraphs,
+                "multiple paragraphs found".to_string(),
+                extra.text_range(),
+                None,
+            ));
         }
-        Ok((Self(para), errors))
+        (Self(para), errors)
+    }
+
+    /// Read a Changes file from a file, allowing syntax errors.
+    pub fn from_file_relaxed<P: AsRef<std::path::Path>>(
+        path: P,
+    ) -> Result<(Self, Vec<deb822_lossless::PositionedParseError>), std::io::Error> {
+        let (deb822, errors) = deb822_lossless::Deb822::from_file_relaxed(path)?;
+        Ok(Self::from_deb822_relaxed(deb822, errors))
     }
 
     /// Read a Changes file from a reader.
@@ -237,17 +251,9 @@ impl Changes {
     /// Read a Changes file from a reader, allowing syntax errors.
     pub fn read_relaxed<R: std::io::Read>(
         mut r: R,
-    ) -> Result<(Self, Vec<String>), deb822_lossless::Error> {
-        let (mut deb822, mut errors) = deb822_lossless::Deb822::read_relaxed(&mut r)?;
-        let mut paras = deb822.paragraphs();
-        let para = match paras.next() {
-            Some(para) => para,
-            None => deb822.add_paragraph(),
-        };
-        if paras.next().is_some() {
-            errors.push("multiple paragraphs found".to_string());
-        }
-        Ok((Self(para), errors))
+    ) -> Result<(Self, Vec<deb822_lossless::PositionedParseError>), deb822_lossless::Error> {
+        let (deb822, errors) = deb822_lossless::Deb822::read_relaxed(&mut r)?;
+        Ok(Self::from_deb822_relaxed(deb822, errors))
     }
 }
 
@@ -280,6 +286,19 @@ mod tests {
         assert_eq!(changes.format(), Some("1.8".to_string()));
     }
 
+    #[test]
+    fn test_read_relaxed_multiple_paragraphs() {
+        let text = "Format: 1.8\n\nSource: foo\n";
+        let (changes, errors) = super::Changes::read_relaxed(text.as_bytes()).unwrap();
+        assert_eq!(changes.format(), Some("1.8".to_string()));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].kind,
+            deb822_lossless::ParseErrorKind::MultipleParagraphs
+        );
+        assert_eq!((errors[0].line, errors[0].column), (2, 0));
+    }
+
     #[test]
     fn test_parse() {
         let changes = r#"Format: 1.8
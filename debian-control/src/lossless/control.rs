@@ -62,6 +62,64 @@ fn format_field(name: &str, value: &str) -> String {
     }
 }
 
+/// The canonical order of fields in a source package paragraph.
+///
+/// This is used to place new fields when they are added to a [`Source`].
+pub const SOURCE_FIELD_ORDER: &[&str] = &[
+    "Source",
+    "Section",
+    "Priority",
+    "Maintainer",
+    "Uploaders",
+    "Build-Depends",
+    "Build-Depends-Indep",
+    "Build-Depends-Arch",
+    "Build-Conflicts",
+    "Build-Conflicts-Indep",
+    "Build-Conflicts-Arch",
+    "Standards-Version",
+    "Vcs-Browser",
+    "Vcs-Git",
+    "Vcs-Svn",
+    "Vcs-Bzr",
+    "Vcs-Hg",
+    "Vcs-Mtn",
+    "Vcs-Cvs",
+    "Vcs-Arch",
+    "Vcs-Svk",
+    "Vcs-Darcs",
+    "Homepage",
+    "Architecture",
+    "Rules-Requires-Root",
+    "Testsuite",
+    "Testsuite-Triggers",
+];
+
+/// The canonical order of fields in a binary package paragraph.
+///
+/// This is used to place new fields when they are added to a [`Binary`].
+pub const BINARY_FIELD_ORDER: &[&str] = &[
+    "Package",
+    "Architecture",
+    "Section",
+    "Priority",
+    "Multi-Arch",
+    "Essential",
+    "Build-Profiles",
+    "Built-Using",
+    "Pre-Depends",
+    "Depends",
+    "Recommends",
+    "Suggests",
+    "Enhances",
+    "Breaks",
+    "Conflicts",
+    "Provides",
+    "Replaces",
+    "Homepage",
+    "Description",
+];
+
 /// A Debian control file
 pub struct Control(deb822_lossless::Deb822);
 
@@ -147,7 +205,7 @@ impl Control {
     /// Read a control file from a file, allowing syntax errors
     pub fn from_file_relaxed<P: AsRef<std::path::Path>>(
         path: P,
-    ) -> Result<(Self, Vec<String>), std::io::Error> {
+    ) -> Result<(Self, Vec<deb822_lossless::PositionedParseError>), std::io::Error> {
         let (control, errors) = deb822_lossless::Deb822::from_file_relaxed(path)?;
         Ok((Control(control), errors))
     }
@@ -160,7 +218,7 @@ impl Control {
     /// Read a control file from a reader, allowing syntax errors
     pub fn read_relaxed<R: std::io::Read>(
         mut r: R,
-    ) -> Result<(Self, Vec<String>), deb822_lossless::Error> {
+    ) -> Result<(Self, Vec<deb822_lossless::PositionedParseError>), deb822_lossless::Error> {
         let (control, errors) = deb822_lossless::Deb822::read_relaxed(&mut r)?;
         Ok((Self(control), errors))
     }
@@ -239,6 +297,35 @@ impl std::str::FromStr for Control {
     }
 }
 
+/// Control files can be edited in place with [`deb822_lossless::editor::FileEditor`].
+///
+/// # Example
+/// ```rust
+/// use debian_control::lossless::control::Control;
+/// use deb822_lossless::editor::FileEditor;
+/// let td = tempfile::tempdir().unwrap();
+/// let path = td.path().join("control");
+/// std::fs::write(&path, "Source: foo\n\nPackage: foo\n").unwrap();
+/// let mut control = FileEditor::<Control>::open(&path).unwrap();
+/// control.source().unwrap().set_section(Some("libs"));
+/// control.commit().unwrap();
+/// assert_eq!(
+///     std::fs::read_to_string(&path).unwrap(),
+///     "Source: foo\nSection: libs\n\nPackage: foo\n"
+/// );
+/// ```
+impl deb822_lossless::editor::Editable for Control {
+    type Error = deb822_lossless::ParseError;
+
+    fn parse(text: &str) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// A source package paragraph
 pub struct Source(deb822_lossless::Paragraph);
 
@@ -261,6 +348,11 @@ impl std::fmt::Display for Source {
 }
 
 impl Source {
+    /// Set a field, placing it according to [`SOURCE_FIELD_ORDER`] if it is new.
+    fn set(&mut self, key: &str, value: &str) {
+        self.0.set_with_field_order(key, value, SOURCE_FIELD_ORDER);
+    }
+
     /// The name of the source package.
     pub fn name(&self) -> Option<String> {
         self.0.get("Source")
@@ -294,7 +386,7 @@ impl Source {
 
     /// Set the name of the source package.
     pub fn set_name(&mut self, name: &str) {
-        self.0.set("Source", name);
+        self.set("Source", name);
     }
 
     /// The default section of the packages built from this source package.
@@ -305,7 +397,7 @@ impl Source {
     /// Set the section of the source package
     pub fn set_section(&mut self, section: Option<&str>) {
         if let Some(section) = section {
-            self.0.set("Section", section);
+            self.set("Section", section);
         } else {
             self.0.remove("Section");
         }
@@ -319,7 +411,7 @@ impl Source {
     /// Set the priority of the source package
     pub fn set_priority(&mut self, priority: Option<Priority>) {
         if let Some(priority) = priority {
-            self.0.set("Priority", priority.to_string().as_str());
+            self.set("Priority", priority.to_string().as_str());
         } else {
             self.0.remove("Priority");
         }
@@ -332,7 +424,7 @@ impl Source {
 
     /// Set the maintainer of the package
     pub fn set_maintainer(&mut self, maintainer: &str) {
-        self.0.set("Maintainer", maintainer);
+        self.set("Maintainer", maintainer);
     }
 
     /// The build dependencies of the package.
@@ -342,7 +434,7 @@ impl Source {
 
     /// Set the Build-Depends field
     pub fn set_build_depends(&mut self, relations: &Relations) {
-        self.0.set("Build-Depends", relations.to_string().as_str());
+        self.set("Build-Depends", relations.to_string().as_str());
     }
 
     /// Return the Build-Depends-Indep field
@@ -383,7 +475,7 @@ impl Source {
 
     /// Set the Standards-Version field
     pub fn set_standards_version(&mut self, version: &str) {
-        self.0.set("Standards-Version", version);
+        self.set("Standards-Version", version);
     }
 
     /// Return the upstrea mHomepage
@@ -393,7 +485,7 @@ impl Source {
 
     /// Set the Homepage field
     pub fn set_homepage(&mut self, homepage: &url::Url) {
-        self.0.set("Homepage", homepage.to_string().as_str());
+        self.set("Homepage", homepage.to_string().as_str());
     }
 
     /// Return the Vcs-Git field
@@ -403,7 +495,7 @@ impl Source {
 
     /// Set the Vcs-Git field
     pub fn set_vcs_git(&mut self, url: &str) {
-        self.0.set("Vcs-Git", url);
+        self.set("Vcs-Git", url);
     }
 
     /// Return the Vcs-Browser field
@@ -413,7 +505,7 @@ impl Source {
 
     /// Set the Vcs-Svn field
     pub fn set_vcs_svn(&mut self, url: &str) {
-        self.0.set("Vcs-Svn", url);
+        self.set("Vcs-Svn", url);
     }
 
     /// Return the Vcs-Bzr field
@@ -423,7 +515,7 @@ impl Source {
 
     /// Set the Vcs-Bzr field
     pub fn set_vcs_bzr(&mut self, url: &str) {
-        self.0.set("Vcs-Bzr", url);
+        self.set("Vcs-Bzr", url);
     }
 
     /// Return the Vcs-Arch field
@@ -433,7 +525,7 @@ impl Source {
 
     /// Set the Vcs-Arch field
     pub fn set_vcs_arch(&mut self, url: &str) {
-        self.0.set("Vcs-Arch", url);
+        self.set("Vcs-Arch", url);
     }
 
     /// Return the Vcs-Svk field
@@ -443,7 +535,7 @@ impl Source {
 
     /// Set the Vcs-Svk field
     pub fn set_vcs_svk(&mut self, url: &str) {
-        self.0.set("Vcs-Svk", url);
+        self.set("Vcs-Svk", url);
     }
 
     /// Return the Vcs-Darcs field
@@ -453,7 +545,7 @@ impl Source {
 
     /// Set the Vcs-Darcs field
     pub fn set_vcs_darcs(&mut self, url: &str) {
-        self.0.set("Vcs-Darcs", url);
+        self.set("Vcs-Darcs", url);
     }
 
     /// Return the Vcs-Mtn field
@@ -463,7 +555,7 @@ impl Source {
 
     /// Set the Vcs-Mtn field
     pub fn set_vcs_mtn(&mut self, url: &str) {
-        self.0.set("Vcs-Mtn", url);
+        self.set("Vcs-Mtn", url);
     }
 
     /// Return the Vcs-Cvs field
@@ -473,7 +565,7 @@ impl Source {
 
     /// Set the Vcs-Cvs field
     pub fn set_vcs_cvs(&mut self, url: &str) {
-        self.0.set("Vcs-Cvs", url);
+        self.set("Vcs-Cvs", url);
     }
 
     /// Return the Vcs-Hg field
@@ -483,7 +575,7 @@ impl Source {
 
     /// Set the Vcs-Hg field
     pub fn set_vcs_hg(&mut self, url: &str) {
-        self.0.set("Vcs-Hg", url);
+        self.set("Vcs-Hg", url);
     }
 
     /// Return the Vcs-Browser field
@@ -504,7 +596,7 @@ impl Source {
     /// Set the Vcs-Browser field
     pub fn set_vcs_browser(&mut self, url: Option<&str>) {
         if let Some(url) = url {
-            self.0.set("Vcs-Browser", url);
+            self.set("Vcs-Browser", url);
         } else {
             self.0.remove("Vcs-Browser");
         }
@@ -519,7 +611,7 @@ impl Source {
 
     /// Set the uploaders field
     pub fn set_uploaders(&mut self, uploaders: &[&str]) {
-        self.0.set(
+        self.set(
             "Uploaders",
             uploaders
                 .iter()
@@ -538,7 +630,7 @@ impl Source {
     /// Set the architecture field
     pub fn set_architecture(&mut self, arch: Option<&str>) {
         if let Some(arch) = arch {
-            self.0.set("Architecture", arch);
+            self.set("Architecture", arch);
         } else {
             self.0.remove("Architecture");
         }
@@ -557,7 +649,7 @@ impl Source {
 
     /// Set the Rules-Requires-Root field
     pub fn set_rules_requires_root(&mut self, requires_root: bool) {
-        self.0.set(
+        self.set(
             "Rules-Requires-Root",
             if requires_root { "yes" } else { "no" },
         );
@@ -570,7 +662,7 @@ impl Source {
 
     /// Set the Testsuite field
     pub fn set_testsuite(&mut self, testsuite: &str) {
-        self.0.set("Testsuite", testsuite);
+        self.set("Testsuite", testsuite);
     }
 }
 
@@ -632,6 +724,11 @@ impl Default for Binary {
 }
 
 impl Binary {
+    /// Set a field, placing it according to [`BINARY_FIELD_ORDER`] if it is new.
+    fn set(&mut self, key: &str, value: &str) {
+        self.0.set_with_field_order(key, value, BINARY_FIELD_ORDER);
+    }
+
     /// Create a new binary package control file
     pub fn new() -> Self {
         Binary(deb822_lossless::Paragraph::new())
@@ -670,7 +767,7 @@ impl Binary {
 
     /// Set the name of the package
     pub fn set_name(&mut self, name: &str) {
-        self.0.set("Package", name);
+        self.set("Package", name);
     }
 
     /// The section of the package.
@@ -681,7 +778,7 @@ impl Binary {
     /// Set the section
     pub fn set_section(&mut self, section: Option<&str>) {
         if let Some(section) = section {
-            self.0.set("Section", section);
+            self.set("Section", section);
         } else {
             self.0.remove("Section");
         }
@@ -695,7 +792,7 @@ impl Binary {
     /// Set the priority of the package
     pub fn set_priority(&mut self, priority: Option<Priority>) {
         if let Some(priority) = priority {
-            self.0.set("Priority", priority.to_string().as_str());
+            self.set("Priority", priority.to_string().as_str());
         } else {
             self.0.remove("Priority");
         }
@@ -709,7 +806,7 @@ impl Binary {
     /// Set the architecture of the package
     pub fn set_architecture(&mut self, arch: Option<&str>) {
         if let Some(arch) = arch {
-            self.0.set("Architecture", arch);
+            self.set("Architecture", arch);
         } else {
             self.0.remove("Architecture");
         }
@@ -723,7 +820,7 @@ impl Binary {
     /// Set the Depends field
     pub fn set_depends(&mut self, depends: Option<&Relations>) {
         if let Some(depends) = depends {
-            self.0.set("Depends", depends.to_string().as_str());
+            self.set("Depends", depends.to_string().as_str());
         } else {
             self.0.remove("Depends");
         }
@@ -737,7 +834,7 @@ impl Binary {
     /// Set the Recommends field
     pub fn set_recommends(&mut self, recommends: Option<&Relations>) {
         if let Some(recommends) = recommends {
-            self.0.set("Recommends", recommends.to_string().as_str());
+            self.set("Recommends", recommends.to_string().as_str());
         } else {
             self.0.remove("Recommends");
         }
@@ -751,7 +848,7 @@ impl Binary {
     /// Set the Suggests field
     pub fn set_suggests(&mut self, suggests: Option<&Relations>) {
         if let Some(suggests) = suggests {
-            self.0.set("Suggests", suggests.to_string().as_str());
+            self.set("Suggests", suggests.to_string().as_str());
         } else {
             self.0.remove("Suggests");
         }
@@ -765,7 +862,7 @@ impl Binary {
     /// Set the Enhances field
     pub fn set_enhances(&mut self, enhances: Option<&Relations>) {
         if let Some(enhances) = enhances {
-            self.0.set("Enhances", enhances.to_string().as_str());
+            self.set("Enhances", enhances.to_string().as_str());
         } else {
             self.0.remove("Enhances");
         }
@@ -779,7 +876,7 @@ impl Binary {
     /// Set the Pre-Depends field
     pub fn set_pre_depends(&mut self, pre_depends: Option<&Relations>) {
         if let Some(pre_depends) = pre_depends {
-            self.0.set("Pre-Depends", pre_depends.to_string().as_str());
+            self.set("Pre-Depends", pre_depends.to_string().as_str());
         } else {
             self.0.remove("Pre-Depends");
         }
@@ -793,7 +890,7 @@ impl Binary {
     /// Set the Breaks field
     pub fn set_breaks(&mut self, breaks: Option<&Relations>) {
         if let Some(breaks) = breaks {
-            self.0.set("Breaks", breaks.to_string().as_str());
+            self.set("Breaks", breaks.to_string().as_str());
         } else {
             self.0.remove("Breaks");
         }
@@ -807,7 +904,7 @@ impl Binary {
     /// Set the Conflicts field
     pub fn set_conflicts(&mut self, conflicts: Option<&Relations>) {
         if let Some(conflicts) = conflicts {
-            self.0.set("Conflicts", conflicts.to_string().as_str());
+            self.set("Conflicts", conflicts.to_string().as_str());
         } else {
             self.0.remove("Conflicts");
         }
@@ -821,7 +918,7 @@ impl Binary {
     /// Set the Replaces field
     pub fn set_replaces(&mut self, replaces: Option<&Relations>) {
         if let Some(replaces) = replaces {
-            self.0.set("Replaces", replaces.to_string().as_str());
+            self.set("Replaces", replaces.to_string().as_str());
         } else {
             self.0.remove("Replaces");
         }
@@ -835,7 +932,7 @@ impl Binary {
     /// Set the Provides field
     pub fn set_provides(&mut self, provides: Option<&Relations>) {
         if let Some(provides) = provides {
-            self.0.set("Provides", provides.to_string().as_str());
+            self.set("Provides", provides.to_string().as_str());
         } else {
             self.0.remove("Provides");
         }
@@ -849,7 +946,7 @@ impl Binary {
     /// Set the Built-Using field
     pub fn set_built_using(&mut self, built_using: Option<&Relations>) {
         if let Some(built_using) = built_using {
-            self.0.set("Built-Using", built_using.to_string().as_str());
+            self.set("Built-Using", built_using.to_string().as_str());
         } else {
             self.0.remove("Built-Using");
         }
@@ -863,7 +960,7 @@ impl Binary {
     /// Set the Multi-Arch field
     pub fn set_multi_arch(&mut self, multi_arch: Option<MultiArch>) {
         if let Some(multi_arch) = multi_arch {
-            self.0.set("Multi-Arch", multi_arch.to_string().as_str());
+            self.set("Multi-Arch", multi_arch.to_string().as_str());
         } else {
             self.0.remove("Multi-Arch");
         }
@@ -877,7 +974,7 @@ impl Binary {
     /// Set whether the package is essential
     pub fn set_essential(&mut self, essential: bool) {
         if essential {
-            self.0.set("Essential", "yes");
+            self.set("Essential", "yes");
         } else {
             self.0.remove("Essential");
         }
@@ -891,7 +988,7 @@ impl Binary {
     /// Set the binary package description
     pub fn set_description(&mut self, description: Option<&str>) {
         if let Some(description) = description {
-            self.0.set("Description", description);
+            self.set("Description", description);
         } else {
             self.0.remove("Description");
         }
@@ -904,7 +1001,7 @@ impl Binary {
 
     /// Set the upstream homepage
     pub fn set_homepage(&mut self, url: &url::Url) {
-        self.0.set("Homepage", url.as_str());
+        self.set("Homepage", url.as_str());
     }
 }
 
@@ -1000,6 +1097,49 @@ Description: this is the short description
         binary.set_depends(Some(&relations));
     }
 
+    #[test]
+    fn test_field_order() {
+        let control: Control = r#"Source: foo
+Maintainer: Foo Bar <foo@example.com>
+Homepage: https://example.com/
+
+Package: foo
+Description: A package
+"#
+        .parse()
+        .unwrap();
+        let mut source = control.source().unwrap();
+        source.set_rules_requires_root(false);
+        source.set_vcs_git("https://example.com/foo.git");
+        source.set_section(Some("net"));
+        let mut binary = control.binaries().next().unwrap();
+        binary.set_architecture(Some("all"));
+        binary.set_depends(Some(&"bar".parse().unwrap()));
+        assert_eq!(
+            control.to_string(),
+            r#"Source: foo
+Section: net
+Maintainer: Foo Bar <foo@example.com>
+Vcs-Git: https://example.com/foo.git
+Homepage: https://example.com/
+Rules-Requires-Root: no
+
+Package: foo
+Architecture: all
+Depends: bar
+Description: A package
+"#
+        );
+    }
+
+    #[test]
+    fn test_wrong_case_field() {
+        let control: Control = "Source: foo\nBuild-depends: bar\n".parse().unwrap();
+        let source = control.source().unwrap();
+        let bd = source.build_depends().unwrap();
+        assert_eq!(bd.entries().count(), 1);
+    }
+
     #[test]
     fn test_wrap_and_sort() {
         let mut control: Control = r#"Package: blah
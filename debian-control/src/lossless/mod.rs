@@ -9,5 +9,6 @@ pub mod buildinfo;
 pub mod changes;
 pub mod control;
 pub mod relations;
+pub mod wrap_and_sort;
 pub use control::*;
 pub use relations::*;
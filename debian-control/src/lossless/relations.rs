@@ -99,7 +99,7 @@ fn parse(text: &str, allow_substvar: bool) -> Parse {
                     Some(IDENT) | Some(COLON) => {
                         self.bump();
                     }
-                    Some(R_CURLY) => {
+                    Some(R_CURLY) | None => {
                         break;
                     }
                     e => {
@@ -248,6 +248,10 @@ fn parse(text: &str, allow_substvar: bool) -> Parse {
                             self.bump();
                             break;
                         }
+                        None => {
+                            self.error("Expected architecture name or '!' or ']'".to_string());
+                            break;
+                        }
                         _ => {
                             self.error("Expected architecture name or '!' or ']'".to_string());
                         }
@@ -388,7 +392,6 @@ fn parse(text: &str, allow_substvar: bool) -> Parse {
 /// It is also immutable, like a GreenNode,
 /// but it contains parent pointers, offsets, and
 /// has identity semantics.
-
 type SyntaxNode = rowan::SyntaxNode<Lang>;
 #[allow(unused)]
 type SyntaxToken = rowan::SyntaxToken<Lang>;
@@ -702,6 +705,7 @@ impl Default for Entry {
     }
 }
 
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for Entry {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         let mut rels_a = self.relations();
@@ -1219,6 +1223,8 @@ impl Relation {
 
     /// Return the name of the package in the relation.
     ///
+    /// The name is empty if the relation was parsed with syntax errors and has no name.
+    ///
     /// # Example
     /// ```
     /// use debian_control::lossless::relations::Relation;
@@ -1232,9 +1238,7 @@ impl Relation {
                 SyntaxElement::Token(token) if token.kind() == IDENT => Some(token),
                 _ => None,
             })
-            .unwrap()
-            .text()
-            .to_string()
+            .map_or_else(String::new, |token| token.text().to_string())
     }
 
     /// Return the archqual
@@ -1295,6 +1299,8 @@ impl Relation {
     }
 
     /// Return the version constraint and the version it is constrained to.
+    ///
+    /// Returns `None` if there is no version constraint, or if it is malformed.
     pub fn version(&self) -> Option<(VersionConstraint, Version)> {
         let vc = self.0.children().find(|n| n.kind() == VERSION);
         let vc = vc.as_ref()?;
@@ -1306,8 +1312,8 @@ impl Relation {
         });
 
         if let (Some(constraint), Some(version)) = (constraint, version) {
-            let vc: VersionConstraint = constraint.to_string().parse().unwrap();
-            return Some((vc, (version.text().to_string()).parse().unwrap()));
+            let vc: VersionConstraint = constraint.to_string().parse().ok()?;
+            Some((vc, version.text().parse().ok()?))
         } else {
             None
         }
@@ -1732,6 +1738,7 @@ impl RelationBuilder {
     }
 }
 
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for Relation {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         // Compare by name first, then by version
@@ -2549,4 +2556,140 @@ mod tests {
         relation.set_architectures(vec!["amd64", "i386"].into_iter());
         assert_eq!(relation.to_string(), "samba [amd64 i386]");
     }
+
+    #[test]
+    fn test_unterminated() {
+        for input in ["foo [amd64", "${foo:Depends", "$"] {
+            let (parsed, errors) = Relations::parse_relaxed(input, true);
+            assert_eq!(parsed.to_string(), input);
+            assert!(!errors.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_malformed_version() {
+        let (parsed, _) = Relations::parse_relaxed("foo (bar)", false);
+        let relation = parsed.entries().next().unwrap().relations().next().unwrap();
+        assert_eq!(relation.version(), None);
+
+        let (parsed, _) = Relations::parse_relaxed("foo | (", false);
+        let relation = parsed.entries().next().unwrap().relations().nth(1).unwrap();
+        assert_eq!(relation.name(), "");
+    }
+
+    mod proptests {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// A generated relation, along with its text.
+        #[derive(Debug, Clone)]
+        struct Generated {
+            text: String,
+            name: String,
+            archqual: Option<String>,
+            version: Option<(String, String)>,
+            architectures: Option<Vec<String>>,
+        }
+
+        fn relation() -> impl Strategy<Value = Generated> {
+            (
+                "[a-z0-9][a-z0-9+.-]{1,12}",
+                proptest::option::of("any|native|amd64"),
+                proptest::option::of((
+                    prop_oneof![Just(">="), Just("<="), Just("="), Just(">>"), Just("<<")],
+                    "[0-9]{1,3}(\\.[0-9]{1,3}){0,2}(-[0-9])?",
+                )),
+                proptest::option::of(prop::collection::vec(("!?", "[a-z0-9-]{2,8}"), 1..4)),
+                prop::collection::vec("!?(nocheck|nodoc|cross|stage1)", 0..3),
+            )
+                .prop_map(|(name, archqual, version, architectures, profiles)| {
+                    let mut text = name.clone();
+                    if let Some(archqual) = &archqual {
+                        text.push_str(&format!(":{}", archqual));
+                    }
+                    if let Some((op, version)) = &version {
+                        text.push_str(&format!(" ({} {})", op, version));
+                    }
+                    if let Some(architectures) = &architectures {
+                        let architectures = architectures
+                            .iter()
+                            .map(|(not, arch)| format!("{}{}", not, arch))
+                            .collect::<Vec<_>>();
+                        text.push_str(&format!(" [{}]", architectures.join(" ")));
+                    }
+                    for profile in &profiles {
+                        text.push_str(&format!(" <{}>", profile));
+                    }
+                    Generated {
+                        text,
+                        name,
+                        archqual,
+                        version: version.map(|(op, v)| (op.to_string(), v)),
+                        architectures: architectures
+                            .map(|a| a.into_iter().map(|(_, arch)| arch).collect()),
+                    }
+                })
+        }
+
+        /// A relations field: entries of alternatives.
+        fn relations() -> impl Strategy<Value = Vec<Vec<Generated>>> {
+            prop::collection::vec(prop::collection::vec(relation(), 1..3), 0..5)
+        }
+
+        fn render(entries: &[Vec<Generated>]) -> String {
+            entries
+                .iter()
+                .map(|e| {
+                    e.iter()
+                        .map(|r| r.text.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" | ")
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        proptest! {
+            #[test]
+            fn roundtrip(entries in relations()) {
+                let text = render(&entries);
+                let (parsed, errors) = Relations::parse_relaxed(&text, false);
+                prop_assert!(errors.is_empty(), "{:?}", errors);
+                prop_assert_eq!(parsed.to_string(), text);
+                prop_assert_eq!(parsed.len(), entries.len());
+                for (entry, expected) in parsed.entries().zip(&entries) {
+                    let relations = entry.relations().collect::<Vec<_>>();
+                    prop_assert_eq!(relations.len(), expected.len());
+                    for (relation, expected) in relations.iter().zip(expected) {
+                        // Whitespace after a relation may be part of its node.
+                        let text = relation.to_string();
+                        prop_assert_eq!(text.trim_end(), expected.text.as_str());
+                        prop_assert_eq!(relation.name(), expected.name.as_str());
+                        prop_assert_eq!(relation.archqual(), expected.archqual.clone());
+                        prop_assert_eq!(
+                            relation.version(),
+                            expected.version.as_ref().map(|(op, v)| (
+                                op.parse::<VersionConstraint>().unwrap(),
+                                v.parse::<Version>().unwrap()
+                            ))
+                        );
+                        prop_assert_eq!(
+                            relation.architectures().map(|a| a.collect::<Vec<_>>()),
+                            expected.architectures.clone()
+                        );
+                    }
+                }
+            }
+
+            #[test]
+            fn never_loses_input(
+                text in prop_oneof!["([a-z0-9]{1,4}|[ ,|:()<>=!\\[\\]${}\n-]){0,40}", any::<String>()]
+            ) {
+                for allow_substvar in [false, true] {
+                    let (parsed, _) = Relations::parse_relaxed(&text, allow_substvar);
+                    prop_assert_eq!(parsed.to_string(), text.as_str());
+                }
+            }
+        }
+    }
 }
@@ -206,7 +206,7 @@ impl std::str::FromStr for Source {
             .parse::<deb822_lossless::lossy::Paragraph>()
             .map_err(|e| e.to_string())?;
 
-        FromDeb822Paragraph::from_paragraph(&para)
+        FromDeb822Paragraph::from_paragraph(&para).map_err(|e| e.to_string())
     }
 }
 
@@ -329,7 +329,7 @@ impl std::str::FromStr for Package {
             .parse::<deb822_lossless::lossy::Paragraph>()
             .map_err(|e| e.to_string())?;
 
-        FromDeb822Paragraph::from_paragraph(&para)
+        FromDeb822Paragraph::from_paragraph(&para).map_err(|e| e.to_string())
     }
 }
 
@@ -133,7 +133,7 @@ impl std::str::FromStr for Buildinfo {
         let para: deb822_lossless::lossless::Paragraph = s
             .parse()
             .map_err(|e: deb822_lossless::lossless::ParseError| e.to_string())?;
-        Self::from_paragraph(&para)
+        Self::from_paragraph(&para).map_err(|e| e.to_string())
     }
 }
 
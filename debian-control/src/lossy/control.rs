@@ -181,13 +181,13 @@ impl std::str::FromStr for Control {
 
         for para in deb822.paragraphs() {
             if para.get("Package").is_some() {
-                let binary: Binary = Binary::from_paragraph(&para)?;
+                let binary: Binary = Binary::from_paragraph(&para).map_err(|e| e.to_string())?;
                 binaries.push(binary);
             } else if para.get("Source").is_some() {
                 if source.is_some() {
                     return Err("more than one source paragraph".to_string());
                 }
-                source = Some(Source::from_paragraph(&para)?);
+                source = Some(Source::from_paragraph(&para).map_err(|e| e.to_string())?);
             } else {
                 return Err("paragraph without Source or Package field".to_string());
             }
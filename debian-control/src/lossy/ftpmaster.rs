@@ -3,7 +3,7 @@
 use deb822_lossless::FromDeb822Paragraph;
 use deb822_lossless::{FromDeb822, ToDeb822};
 
-fn serialize_list(list: &Vec<String>) -> String {
+fn serialize_list(list: &[String]) -> String {
     list.join("\n")
 }
 
@@ -0,0 +1,861 @@
+//! Querying paragraphs, compatible with grep-dctrl.
+//!
+//! A [`Query`] can be built programmatically, or parsed from grep-dctrl(1) style command-line
+//! arguments with [`GrepDctrl::parse_args`], so that existing scripts can be ported one-to-one.
+//!
+//! # Example
+//!
+//! ```rust
+//! use debian_control::query::GrepDctrl;
+//! use deb822_lossless::lossy::Deb822;
+//!
+//! let packages: Deb822 = r#"Package: curl
+//! Version: 8.5.0-2
+//! Depends: libc6, libcurl4 (= 8.5.0-2), libssl3
+//!
+//! Package: hello
+//! Version: 2.10-3
+//! Depends: libc6
+//! "#.parse().unwrap();
+//!
+//! let grep = GrepDctrl::parse_args(["-F", "Depends", "libssl", "-s", "Package"]).unwrap();
+//! assert_eq!(grep.output(packages.iter()), "Package: curl\n\n");
+//!
+//! let grep = GrepDctrl::parse_args(["-F", "Version", "--lt", "3", "-n", "-s", "Package"]).unwrap();
+//! assert_eq!(grep.output(packages.iter()), "hello\n");
+//! ```
+use deb822_lossless::convert::ReadableParagraph;
+use regex::{Regex, RegexBuilder};
+
+/// A comparison between the version in a field and a given version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionOp {
+    /// `--lt`: The field version is lower.
+    Lt,
+
+    /// `--le`: The field version is lower or equal.
+    Le,
+
+    /// `--eq`: The versions are equal.
+    Eq,
+
+    /// `--ge`: The field version is greater or equal.
+    Ge,
+
+    /// `--gt`: The field version is greater.
+    Gt,
+}
+
+impl VersionOp {
+    fn compare(self, a: &debversion::Version, b: &debversion::Version) -> bool {
+        match self {
+            VersionOp::Lt => a < b,
+            VersionOp::Le => a <= b,
+            VersionOp::Eq => a == b,
+            VersionOp::Ge => a >= b,
+            VersionOp::Gt => a > b,
+        }
+    }
+}
+
+/// How a field value is matched.
+#[derive(Debug, Clone)]
+pub enum Matcher {
+    /// The value contains the string (the default in grep-dctrl).
+    Substring(String),
+
+    /// The value is equal to the string (`-X`).
+    Exact(String),
+
+    /// The value matches the regular expression (`-e` or `-r`).
+    Regex(Regex),
+
+    /// The value is a version that compares to the given version (`--lt`, `--eq`, ...).
+    ///
+    /// Values that are not valid versions never match.
+    Version(VersionOp, debversion::Version),
+}
+
+/// A simple filter, matching a pattern against one or more fields.
+#[derive(Debug, Clone)]
+pub struct Predicate {
+    /// The fields to match against. If empty, all fields are searched.
+    pub fields: Vec<String>,
+
+    /// How to match the field values.
+    pub matcher: Matcher,
+
+    /// Whether to ignore case for substring and exact matches.
+    ///
+    /// Regular expressions should be built case-insensitive instead.
+    pub ignore_case: bool,
+}
+
+impl Predicate {
+    fn matches_value(&self, value: &str) -> bool {
+        match &self.matcher {
+            Matcher::Substring(s) if self.ignore_case => {
+                value.to_lowercase().contains(&s.to_lowercase())
+            }
+            Matcher::Substring(s) => value.contains(s.as_str()),
+            Matcher::Exact(s) if self.ignore_case => value.eq_ignore_ascii_case(s),
+            Matcher::Exact(s) => value == s,
+            Matcher::Regex(re) => re.is_match(value),
+            Matcher::Version(op, version) => value
+                .trim()
+                .parse::<debversion::Version>()
+                .map(|v| op.compare(&v, version))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Check whether any of the fields matches.
+    pub fn matches(&self, paragraph: &impl ReadableParagraph) -> bool {
+        if self.fields.is_empty() {
+            paragraph
+                .items()
+                .iter()
+                .any(|(_, value)| self.matches_value(value))
+        } else {
+            self.fields.iter().any(|field| {
+                ReadableParagraph::get(paragraph, field)
+                    .is_some_and(|value| self.matches_value(&value))
+            })
+        }
+    }
+}
+
+/// A query on a paragraph.
+#[derive(Debug, Clone)]
+pub enum Query {
+    /// A simple filter.
+    Predicate(Predicate),
+
+    /// Matches if the query does not match.
+    Not(Box<Query>),
+
+    /// Matches if all of the queries match. An empty list matches every paragraph.
+    And(Vec<Query>),
+
+    /// Matches if any of the queries match.
+    Or(Vec<Query>),
+}
+
+impl Query {
+    /// Match fields containing a string.
+    pub fn substring(fields: &[&str], pattern: &str) -> Self {
+        Self::predicate(fields, Matcher::Substring(pattern.to_string()))
+    }
+
+    /// Match fields equal to a string.
+    pub fn exact(fields: &[&str], pattern: &str) -> Self {
+        Self::predicate(fields, Matcher::Exact(pattern.to_string()))
+    }
+
+    /// Match fields against a regular expression.
+    pub fn regex(fields: &[&str], pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self::predicate(
+            fields,
+            Matcher::Regex(Regex::new(pattern)?),
+        ))
+    }
+
+    /// Compare the version in a field.
+    pub fn version(fields: &[&str], op: VersionOp, version: debversion::Version) -> Self {
+        Self::predicate(fields, Matcher::Version(op, version))
+    }
+
+    fn predicate(fields: &[&str], matcher: Matcher) -> Self {
+        Query::Predicate(Predicate {
+            fields: fields.iter().map(|f| f.to_string()).collect(),
+            matcher,
+            ignore_case: false,
+        })
+    }
+
+    /// Matches if both this query and `other` match.
+    pub fn and(self, other: Query) -> Self {
+        match self {
+            Query::And(mut queries) => {
+                queries.push(other);
+                Query::And(queries)
+            }
+            q => Query::And(vec![q, other]),
+        }
+    }
+
+    /// Matches if either this query or `other` matches.
+    pub fn or(self, other: Query) -> Self {
+        match self {
+            Query::Or(mut queries) => {
+                queries.push(other);
+                Query::Or(queries)
+            }
+            q => Query::Or(vec![q, other]),
+        }
+    }
+
+    /// Matches if this query does not match.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Query::Not(Box::new(self))
+    }
+
+    /// Check whether the paragraph matches this query.
+    pub fn matches(&self, paragraph: &impl ReadableParagraph) -> bool {
+        match self {
+            Query::Predicate(p) => p.matches(paragraph),
+            Query::Not(q) => !q.matches(paragraph),
+            Query::And(queries) => queries.iter().all(|q| q.matches(paragraph)),
+            Query::Or(queries) => queries.iter().any(|q| q.matches(paragraph)),
+        }
+    }
+
+    /// Returns the paragraphs that match this query.
+    pub fn filter<'a, P, I>(&'a self, paragraphs: I) -> impl Iterator<Item = P> + 'a
+    where
+        P: ReadableParagraph,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: 'a,
+    {
+        paragraphs.into_iter().filter(move |p| self.matches(p))
+    }
+}
+
+/// Error parsing grep-dctrl arguments.
+#[derive(Debug)]
+pub enum ParseError {
+    /// An option that is not supported.
+    UnknownOption(String),
+
+    /// An option is missing its argument.
+    MissingArgument(String),
+
+    /// A pattern was expected, e.g. after `-a` or at the end of the arguments.
+    MissingPattern,
+
+    /// A parenthesis was not closed, or closed without being opened.
+    UnbalancedParenthesis,
+
+    /// A regular expression could not be compiled.
+    InvalidRegex(regex::Error),
+
+    /// The version to compare against is not valid.
+    InvalidVersion(debversion::ParseError),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseError::UnknownOption(o) => write!(f, "unknown option: {}", o),
+            ParseError::MissingArgument(o) => write!(f, "option {} requires an argument", o),
+            ParseError::MissingPattern => f.write_str("missing pattern"),
+            ParseError::UnbalancedParenthesis => f.write_str("unbalanced parenthesis"),
+            ParseError::InvalidRegex(e) => write!(f, "invalid regular expression: {}", e),
+            ParseError::InvalidVersion(e) => write!(f, "invalid version: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The kind of pattern for the simple filter that is being parsed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum PatternKind {
+    #[default]
+    Substring,
+    Exact,
+    ExtendedRegex,
+    BasicRegex,
+    Version(VersionOp),
+}
+
+/// Modifiers for the simple filter that is being parsed.
+#[derive(Debug, Default)]
+struct PendingPredicate {
+    fields: Vec<String>,
+    kind: PatternKind,
+    ignore_case: bool,
+    /// Whether any modifiers were given.
+    started: bool,
+}
+
+impl PendingPredicate {
+    fn build(self, pattern: &str) -> Result<Query, ParseError> {
+        let regex = |pattern: &str| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(self.ignore_case)
+                .build()
+                .map_err(ParseError::InvalidRegex)
+        };
+        let matcher = match self.kind {
+            PatternKind::Substring => Matcher::Substring(pattern.to_string()),
+            PatternKind::Exact => Matcher::Exact(pattern.to_string()),
+            PatternKind::ExtendedRegex => Matcher::Regex(regex(pattern)?),
+            PatternKind::BasicRegex => Matcher::Regex(regex(&basic_to_extended(pattern))?),
+            PatternKind::Version(op) => {
+                Matcher::Version(op, pattern.parse().map_err(ParseError::InvalidVersion)?)
+            }
+        };
+        Ok(Query::Predicate(Predicate {
+            fields: self.fields,
+            matcher,
+            ignore_case: self.ignore_case,
+        }))
+    }
+}
+
+/// Convert a POSIX basic regular expression to the syntax of the regex crate.
+///
+/// In basic regular expressions, `(){}|+?` are literals unless escaped with a backslash.
+fn basic_to_extended(pattern: &str) -> String {
+    let mut ret = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c @ ('(' | ')' | '{' | '}' | '|' | '+' | '?')) => ret.push(c),
+                Some(c) => {
+                    ret.push('\\');
+                    ret.push(c);
+                }
+                None => ret.push_str("\\\\"),
+            },
+            '(' | ')' | '{' | '}' | '|' | '+' | '?' => {
+                ret.push('\\');
+                ret.push(c);
+            }
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+/// A token of the filter expression.
+#[derive(Debug)]
+enum Token {
+    Query(Query),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+/// A grep-dctrl invocation: a query plus output options.
+#[derive(Debug, Clone)]
+pub struct GrepDctrl {
+    /// The filter.
+    pub query: Query,
+
+    /// Output only paragraphs that don't match (`-v`).
+    pub invert: bool,
+
+    /// The fields to output (`-s`). If empty, whole paragraphs are output.
+    pub show_fields: Vec<String>,
+
+    /// Output only field values, without the field names (`-n`).
+    pub no_field_names: bool,
+
+    /// Output only the number of matching paragraphs (`-c`).
+    pub count: bool,
+
+    /// Don't output anything (`-q`).
+    pub quiet: bool,
+
+    /// The files to read, as given after the filter.
+    pub files: Vec<String>,
+}
+
+impl GrepDctrl {
+    /// Create an invocation for the given query, that outputs whole paragraphs.
+    pub fn new(query: Query) -> Self {
+        Self {
+            query,
+            invert: false,
+            show_fields: vec![],
+            no_field_names: false,
+            count: false,
+            quiet: false,
+            files: vec![],
+        }
+    }
+
+    /// Parse grep-dctrl(1) command-line arguments, excluding the program name.
+    ///
+    /// The supported options are:
+    /// * `-F FIELD[,FIELD...]`, `-P`: restrict the next pattern to the given fields
+    /// * `-e`, `-r`, `-X`, `-i`, `--lt`, `--le`, `--eq`, `--ge`, `--gt`: how to match the next
+    ///   pattern
+    /// * `-a`, `-o`, `-!` (or `--not`), `(` and `)`: combine simple filters
+    /// * `-s FIELD[,FIELD...]`, `-n`, `-v`, `-c`, `-q`: output options
+    ///
+    /// Simple filters that are started by an option without an explicit `-a` or `-o` are
+    /// combined with `-a`. Any arguments after a complete filter are files.
+    pub fn parse_args<I, S>(args: I) -> Result<Self, ParseError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut grep = Self::new(Query::And(vec![]));
+        let mut tokens = vec![];
+        let mut pending = PendingPredicate::default();
+        let mut args = args
+            .into_iter()
+            .map(|a| a.as_ref().to_string())
+            .collect::<Vec<_>>()
+            .into_iter();
+        let mut only_positional = false;
+
+        // Whether the next positional argument is a pattern, rather than a file.
+        let expects_pattern = |tokens: &[Token], pending: &PendingPredicate| {
+            pending.started || !matches!(tokens.last(), Some(Token::Query(_) | Token::Close))
+        };
+
+        while let Some(arg) = args.next() {
+            // Parentheses can't follow an option that starts a simple filter, so they are taken
+            // as the pattern in that case.
+            let parenthesis = !pending.started && (arg == "(" || arg == ")");
+            if only_positional || arg == "-" || (!arg.starts_with('-') && !parenthesis) {
+                if expects_pattern(&tokens, &pending) {
+                    tokens.push(Token::Query(std::mem::take(&mut pending).build(&arg)?));
+                } else {
+                    grep.files.push(arg);
+                }
+                continue;
+            }
+
+            // Split the argument into option name and any attached value.
+            let (option, mut attached) = if let Some(long) = arg.strip_prefix("--") {
+                match long.split_once('=') {
+                    Some((name, value)) => (format!("--{}", name), Some(value.to_string())),
+                    None => (arg.clone(), None),
+                }
+            } else if arg.len() > 2 && (arg.starts_with("-F") || arg.starts_with("-s")) {
+                (arg[..2].to_string(), Some(arg[2..].to_string()))
+            } else if arg.len() > 2 && arg.starts_with('-') && arg.is_char_boundary(2) {
+                // A cluster of flags, like "-ni". The remainder after -F or -s is its value.
+                let mut rest = vec![];
+                for (i, c) in arg[1..].char_indices() {
+                    if matches!(c, 'F' | 's') {
+                        rest.push(format!("-{}", &arg[i + 1..]));
+                        break;
+                    }
+                    rest.push(format!("-{}", c));
+                }
+                let remaining = args.collect::<Vec<_>>();
+                args = rest
+                    .into_iter()
+                    .chain(remaining)
+                    .collect::<Vec<_>>()
+                    .into_iter();
+                continue;
+            } else {
+                (arg.clone(), None)
+            };
+            let mut value = |option: &str| {
+                attached
+                    .take()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| ParseError::MissingArgument(option.to_string()))
+            };
+
+            let modifier = |pending: &mut PendingPredicate, tokens: &mut Vec<Token>| {
+                if !pending.started && matches!(tokens.last(), Some(Token::Query(_) | Token::Close))
+                {
+                    tokens.push(Token::And);
+                }
+                pending.started = true;
+            };
+
+            match option.as_str() {
+                "--" => only_positional = true,
+                "-F" | "--field" => {
+                    let fields = value(&option)?;
+                    modifier(&mut pending, &mut tokens);
+                    pending
+                        .fields
+                        .extend(fields.split(',').map(|f| f.trim().to_string()));
+                }
+                "-P" => {
+                    modifier(&mut pending, &mut tokens);
+                    pending.fields.push("Package".to_string());
+                }
+                "-e" | "--eregex" => {
+                    modifier(&mut pending, &mut tokens);
+                    pending.kind = PatternKind::ExtendedRegex;
+                }
+                "-r" | "--regex" => {
+                    modifier(&mut pending, &mut tokens);
+                    pending.kind = PatternKind::BasicRegex;
+                }
+                "-X" | "--exact-match" => {
+                    modifier(&mut pending, &mut tokens);
+                    pending.kind = PatternKind::Exact;
+                }
+                "-i" | "--ignore-case" => {
+                    modifier(&mut pending, &mut tokens);
+                    pending.ignore_case = true;
+                }
+                "--lt" | "--le" | "--eq" | "--ge" | "--gt" => {
+                    modifier(&mut pending, &mut tokens);
+                    pending.kind = PatternKind::Version(match option.as_str() {
+                        "--lt" => VersionOp::Lt,
+                        "--le" => VersionOp::Le,
+                        "--eq" => VersionOp::Eq,
+                        "--ge" => VersionOp::Ge,
+                        _ => VersionOp::Gt,
+                    });
+                }
+                "-a" | "--and" => tokens.push(Token::And),
+                "-o" | "--or" => tokens.push(Token::Or),
+                "-!" | "--not" | "--!" => {
+                    if matches!(tokens.last(), Some(Token::Query(_) | Token::Close)) {
+                        tokens.push(Token::And);
+                    }
+                    tokens.push(Token::Not)
+                }
+                "(" => {
+                    if matches!(tokens.last(), Some(Token::Query(_) | Token::Close)) {
+                        tokens.push(Token::And);
+                    }
+                    tokens.push(Token::Open)
+                }
+                ")" => tokens.push(Token::Close),
+                "-s" | "--show-field" => {
+                    let fields = value(&option)?;
+                    grep.show_fields
+                        .extend(fields.split(',').map(|f| f.trim().to_string()));
+                }
+                "-n" | "--no-field-names" => grep.no_field_names = true,
+                "-v" | "--invert-match" => grep.invert = true,
+                "-c" | "--count" => grep.count = true,
+                "-q" | "--quiet" | "--silent" => grep.quiet = true,
+                _ => return Err(ParseError::UnknownOption(option)),
+            }
+        }
+
+        if pending.started {
+            return Err(ParseError::MissingPattern);
+        }
+        if !tokens.is_empty() {
+            let mut tokens = tokens.into_iter().peekable();
+            grep.query = parse_or(&mut tokens)?;
+            match tokens.next() {
+                None => {}
+                Some(Token::Close) => return Err(ParseError::UnbalancedParenthesis),
+                Some(_) => return Err(ParseError::MissingPattern),
+            }
+        }
+        Ok(grep)
+    }
+
+    /// Check whether the paragraph should be output, taking `-v` into account.
+    pub fn is_match(&self, paragraph: &impl ReadableParagraph) -> bool {
+        self.query.matches(paragraph) != self.invert
+    }
+
+    /// Format a single paragraph, restricted to the fields to show.
+    pub fn format_paragraph<P: ReadableParagraph + std::fmt::Display>(
+        &self,
+        paragraph: &P,
+    ) -> String {
+        if self.show_fields.is_empty() {
+            return paragraph.to_string();
+        }
+        let mut ret = String::new();
+        for (name, value) in paragraph.items() {
+            if !self
+                .show_fields
+                .iter()
+                .any(|f| f.eq_ignore_ascii_case(&name))
+            {
+                continue;
+            }
+            let mut lines = value.split('\n');
+            if self.no_field_names {
+                ret.push_str(lines.next().unwrap_or_default());
+            } else {
+                ret.push_str(&name);
+                ret.push(':');
+                match lines.next() {
+                    Some("") | None => {}
+                    Some(first) => {
+                        ret.push(' ');
+                        ret.push_str(first);
+                    }
+                }
+            }
+            ret.push('\n');
+            for line in lines {
+                ret.push(' ');
+                ret.push_str(line);
+                ret.push('\n');
+            }
+        }
+        ret
+    }
+
+    /// Produce the output grep-dctrl would print for the given paragraphs.
+    ///
+    /// Paragraphs are separated by empty lines, except when only the values of a single
+    /// field are shown.
+    pub fn output<P: ReadableParagraph + std::fmt::Display>(
+        &self,
+        paragraphs: impl IntoIterator<Item = P>,
+    ) -> String {
+        let matches = paragraphs.into_iter().filter(|p| self.is_match(p));
+        if self.quiet {
+            return String::new();
+        }
+        if self.count {
+            return format!("{}\n", matches.count());
+        }
+        let separate = !(self.no_field_names && self.show_fields.len() == 1);
+        let mut ret = String::new();
+        for p in matches {
+            let text = self.format_paragraph(&p);
+            if text.is_empty() {
+                continue;
+            }
+            ret.push_str(&text);
+            if separate {
+                ret.push('\n');
+            }
+        }
+        ret
+    }
+}
+
+type Tokens = std::iter::Peekable<std::vec::IntoIter<Token>>;
+
+fn parse_or(tokens: &mut Tokens) -> Result<Query, ParseError> {
+    let mut query = parse_and(tokens)?;
+    while matches!(tokens.peek(), Some(Token::Or)) {
+        tokens.next();
+        query = query.or(parse_and(tokens)?);
+    }
+    Ok(query)
+}
+
+fn parse_and(tokens: &mut Tokens) -> Result<Query, ParseError> {
+    let mut query = parse_not(tokens)?;
+    while matches!(tokens.peek(), Some(Token::And)) {
+        tokens.next();
+        query = query.and(parse_not(tokens)?);
+    }
+    Ok(query)
+}
+
+fn parse_not(tokens: &mut Tokens) -> Result<Query, ParseError> {
+    match tokens.next() {
+        Some(Token::Not) => Ok(parse_not(tokens)?.not()),
+        Some(Token::Open) => {
+            let query = parse_or(tokens)?;
+            match tokens.next() {
+                Some(Token::Close) => Ok(query),
+                _ => Err(ParseError::UnbalancedParenthesis),
+            }
+        }
+        Some(Token::Query(query)) => Ok(query),
+        Some(Token::Close) => Err(ParseError::UnbalancedParenthesis),
+        Some(Token::And | Token::Or) | None => Err(ParseError::MissingPattern),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deb822_lossless::lossy::Paragraph;
+
+    const PACKAGES: &str = r#"Package: curl
+Version: 8.5.0-2
+Depends: libc6 (>= 2.34), libcurl4 (= 8.5.0-2), libssl3
+Description: command line tool for transferring data with URL syntax
+ curl is a command line tool for transferring data with URL syntax.
+
+Package: hello
+Version: 2.10-3
+Depends: libc6 (>= 2.34)
+Description: example package based on GNU hello
+
+Package: libssl3
+Source: openssl
+Version: 3.1.4-2
+Depends: libc6 (>= 2.34)
+"#;
+
+    fn packages() -> Vec<Paragraph> {
+        PACKAGES
+            .parse::<deb822_lossless::lossy::Deb822>()
+            .unwrap()
+            .into()
+    }
+
+    fn grep(args: &[&str]) -> Vec<String> {
+        let grep = GrepDctrl::parse_args(args).unwrap();
+        packages()
+            .iter()
+            .filter(|p| grep.is_match(p))
+            .map(|p| p.get("Package").unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_matchers() {
+        assert_eq!(grep(&["-F", "Depends", "libssl"]), vec!["curl"]);
+        assert_eq!(grep(&["libssl"]), vec!["curl", "libssl3"]);
+        assert_eq!(
+            grep(&["-X", "-F", "Package", "libssl"]),
+            Vec::<String>::new()
+        );
+        assert_eq!(grep(&["-XP", "hello"]), vec!["hello"]);
+        assert_eq!(grep(&["-FPackage", "-i", "HELLO"]), vec!["hello"]);
+        assert_eq!(grep(&["-P", "-e", "^(curl|hello)$"]), vec!["curl", "hello"]);
+        assert_eq!(
+            grep(&["-P", "-r", "^\\(curl\\|hello\\)$"]),
+            vec!["curl", "hello"]
+        );
+        assert_eq!(grep(&["-P", "-r", "l+"]), Vec::<String>::new());
+        assert_eq!(grep(&["-F", "Source,Description", "GNU"]), vec!["hello"]);
+        assert_eq!(grep(&["-F", "Version", "--lt", "3"]), vec!["hello"]);
+        assert_eq!(
+            grep(&["-F", "Version", "--ge", "3.1.4-2"]),
+            vec!["curl", "libssl3"]
+        );
+        assert_eq!(grep(&["--field=Version", "--eq", "2.10-3"]), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_boolean() {
+        assert_eq!(
+            grep(&["-F", "Depends", "libc6", "-a", "-!", "-F", "Depends", "libssl"]),
+            vec!["hello", "libssl3"]
+        );
+        assert_eq!(
+            grep(&["-P", "hello", "-o", "-F", "Source", "openssl"]),
+            vec!["hello", "libssl3"]
+        );
+        // Without a connective, simple filters are combined with -a.
+        assert_eq!(
+            grep(&["-P", "l", "-F", "Version", "--gt", "3"]),
+            vec!["curl", "libssl3"]
+        );
+        assert_eq!(
+            grep(&["-F", "Depends", "libc6", "-a", "(", "-P", "hello", "-o", "-P", "curl", ")"]),
+            vec!["curl", "hello"]
+        );
+        assert_eq!(grep(&["-v", "-P", "curl"]), vec!["hello", "libssl3"]);
+        assert_eq!(grep(&[]).len(), 3);
+    }
+
+    #[test]
+    fn test_files() {
+        let grep = GrepDctrl::parse_args(["-P", "hello", "Packages", "-"]).unwrap();
+        assert_eq!(grep.files, vec!["Packages", "-"]);
+        let grep =
+            GrepDctrl::parse_args(["-P", "-s", "Version", "--", "-hello", "Packages"]).unwrap();
+        assert_eq!(grep.files, vec!["Packages"]);
+        assert_eq!(grep.show_fields, vec!["Version"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            GrepDctrl::parse_args(["-F"]),
+            Err(ParseError::MissingArgument(_))
+        ));
+        assert!(matches!(
+            GrepDctrl::parse_args(["-P"]),
+            Err(ParseError::MissingPattern)
+        ));
+        assert!(matches!(
+            GrepDctrl::parse_args(["-P", "foo", "-a"]),
+            Err(ParseError::MissingPattern)
+        ));
+        assert!(matches!(
+            GrepDctrl::parse_args(["(", "-P", "foo"]),
+            Err(ParseError::UnbalancedParenthesis)
+        ));
+        assert!(matches!(
+            GrepDctrl::parse_args(["-e", "("]),
+            Err(ParseError::InvalidRegex(_))
+        ));
+        assert!(matches!(
+            GrepDctrl::parse_args(["--lt", "1.0 beta"]),
+            Err(ParseError::InvalidVersion(_))
+        ));
+        assert!(matches!(
+            GrepDctrl::parse_args(["--frobnicate"]),
+            Err(ParseError::UnknownOption(_))
+        ));
+    }
+
+    #[test]
+    fn test_output() {
+        let grep = GrepDctrl::parse_args(["-F", "Depends", "libc6", "-s", "Package,Version"]);
+        assert_eq!(
+            grep.unwrap().output(packages()),
+            "Package: curl\nVersion: 8.5.0-2\n\nPackage: hello\nVersion: 2.10-3\n\n\
+             Package: libssl3\nVersion: 3.1.4-2\n\n"
+        );
+        let grep = GrepDctrl::parse_args(["-n", "-s", "Package", "-P", "l"]).unwrap();
+        assert_eq!(grep.output(packages()), "curl\nhello\nlibssl3\n");
+        let grep = GrepDctrl::parse_args(["-c", "-F", "Depends", "libssl"]).unwrap();
+        assert_eq!(grep.output(packages()), "1\n");
+        let grep = GrepDctrl::parse_args(["-s", "Description", "-P", "curl"]).unwrap();
+        assert_eq!(
+            grep.output(packages()),
+            "Description: command line tool for transferring data with URL syntax\n \
+             curl is a command line tool for transferring data with URL syntax.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_lossless() {
+        let deb822: deb822_lossless::Deb822 =
+            "Package: foo\n# comment\nVersion: 1.0\n\nPackage: bar\nVersion: 2.0\n"
+                .parse()
+                .unwrap();
+        let query = Query::version(&["Version"], VersionOp::Lt, "2.0".parse().unwrap());
+        let matches = query.filter(deb822.paragraphs()).collect::<Vec<_>>();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            GrepDctrl::new(query).output(deb822.paragraphs()),
+            "Package: foo\n# comment\nVersion: 1.0\n\n"
+        );
+    }
+
+    #[test]
+    fn test_borrowed() {
+        let deb822 = deb822_lossless::borrowed::BorrowedDeb822::parse(PACKAGES).unwrap();
+        let query = Query::exact(&["Source"], "openssl");
+        let matches = query.filter(deb822.iter()).collect::<Vec<_>>();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].get("Package"), Some("libssl3"));
+        let grep = GrepDctrl::parse_args(["-F", "Depends", "libcurl4", "-s", "Description"]);
+        assert_eq!(
+            grep.unwrap().output(deb822.iter()),
+            "Description: command line tool for transferring data with URL syntax\n \
+             curl is a command line tool for transferring data with URL syntax.\n\n"
+        );
+        let grep = GrepDctrl::parse_args(["-P", "hello"]).unwrap();
+        assert_eq!(
+            grep.output(deb822.iter()),
+            "Package: hello\nVersion: 2.10-3\nDepends: libc6 (>= 2.34)\n\
+             Description: example package based on GNU hello\n\n"
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let query = Query::exact(&["Package"], "hello")
+            .or(Query::substring(&[], "openssl"))
+            .and(Query::regex(&["Version"], r"^\d+\.\d+-").unwrap().not());
+        let matches = query
+            .filter(packages())
+            .map(|p| p.get("Package").unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(matches, vec!["libssl3"]);
+    }
+}
@@ -0,0 +1,25 @@
+#!/bin/sh
+# Regenerate the expected outputs in expected/ by running devscripts' wrap-and-sort on each file
+# in input/ with each set of options listed in options.
+#
+# Files named *.control are formatted as debian/control, other files keep their name.
+set -e
+cd "$(dirname "$0")"
+rm -rf expected
+grep -v '^#' options | while read -r name args; do
+    mkdir -p "expected/$name"
+    for input in input/*; do
+        file=$(basename "$input")
+        case "$file" in
+            *.control) target=control ;;
+            *) target=$file ;;
+        esac
+        tmp=$(mktemp -d)
+        mkdir "$tmp/debian"
+        cp "$input" "$tmp/debian/$target"
+        # shellcheck disable=SC2086
+        wrap-and-sort $args --debian-directory "$tmp/debian" --file "$tmp/debian/$target"
+        cp "$tmp/debian/$target" "expected/$name/$file"
+        rm -rf "$tmp"
+    done
+done
@@ -108,7 +108,9 @@ impl Copyright {
     }
 
     /// Read copyright file from a string, allowing syntax errors
-    pub fn from_str_relaxed(s: &str) -> Result<(Self, Vec<String>), Error> {
+    pub fn from_str_relaxed(
+        s: &str,
+    ) -> Result<(Self, Vec<deb822_lossless::PositionedParseError>), Error> {
         if !s.starts_with("Format:") {
             return Err(Error::NotMachineReadable);
         }
@@ -118,7 +120,9 @@ impl Copyright {
     }
 
     /// Read copyright file from a file, allowing syntax errors
-    pub fn from_file_relaxed<P: AsRef<Path>>(path: P) -> Result<(Self, Vec<String>), Error> {
+    pub fn from_file_relaxed<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<(Self, Vec<deb822_lossless::PositionedParseError>), Error> {
         let text = std::fs::read_to_string(path)?;
         Self::from_str_relaxed(&text)
     }
@@ -200,6 +204,39 @@ impl std::fmt::Display for Copyright {
     }
 }
 
+/// Copyright files can be edited in place with [`deb822_lossless::editor::FileEditor`].
+///
+/// # Example
+/// ```rust
+/// use debian_copyright::lossless::Copyright;
+/// use deb822_lossless::editor::FileEditor;
+/// let td = tempfile::tempdir().unwrap();
+/// let path = td.path().join("copyright");
+/// std::fs::write(
+///     &path,
+///     "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/\n",
+/// )
+/// .unwrap();
+/// let mut copyright = FileEditor::<Copyright>::open(&path).unwrap();
+/// copyright.header().unwrap().set_upstream_name("foo");
+/// copyright.commit().unwrap();
+/// assert_eq!(
+///     std::fs::read_to_string(&path).unwrap(),
+///     "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/\nUpstream-Name: foo\n"
+/// );
+/// ```
+impl deb822_lossless::editor::Editable for Copyright {
+    type Error = Error;
+
+    fn parse(text: &str) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// A header paragraph
 pub struct Header(Paragraph);
 
@@ -118,18 +118,18 @@ impl std::str::FromStr for Copyright {
             return Err("No paragraphs".to_string());
         };
 
-        let header: Header = Header::from_paragraph(&first_para)?;
+        let header: Header = Header::from_paragraph(&first_para).map_err(|e| e.to_string())?;
 
         let mut files_paras = vec![];
         let mut license_paras = vec![];
 
-        while let Some(para) = paragraphs.next() {
-            if para.get("Files").is_some() {
-                files_paras.push(FilesParagraph::from_paragraph(&para)?);
-            } else if para.get("License").is_some() {
-                license_paras.push(LicenseParagraph::from_paragraph(&para)?);
-            } else {
-                return Err("Paragraph is neither License nor Files".to_string());
+        for para in paragraphs {
+            match Paragraph::from_paragraph(&para).map_err(|e| e.to_string())? {
+                Paragraph::Files(files) => files_paras.push(files),
+                Paragraph::License(license) => license_paras.push(license),
+                Paragraph::Header(_) => {
+                    return Err("Paragraph is neither License nor Files".to_string());
+                }
             }
         }
 
@@ -141,6 +141,22 @@ impl std::str::FromStr for Copyright {
     }
 }
 
+/// A paragraph in a copyright file.
+#[derive(FromDeb822, ToDeb822, Clone, PartialEq, Eq, Debug)]
+pub enum Paragraph {
+    /// A paragraph describing a set of files.
+    #[deb822(tag_field = "Files")]
+    Files(FilesParagraph),
+
+    /// A paragraph describing a license.
+    #[deb822(tag_field = "License")]
+    License(LicenseParagraph),
+
+    /// The header paragraph.
+    #[deb822(tag_field = "Format")]
+    Header(Header),
+}
+
 /// A paragraph describing a license.
 #[derive(FromDeb822, ToDeb822, Clone, PartialEq, Eq, Debug)]
 pub struct LicenseParagraph {
@@ -221,7 +237,7 @@ impl Copyright {
     /// # Arguments
     /// * `path` - The path to the file to find the license for.
     pub fn find_files(&self, path: &std::path::Path) -> Option<&FilesParagraph> {
-        self.files.iter().filter(|f| f.matches(path)).last()
+        self.files.iter().rfind(|f| f.matches(path))
     }
 
     /// Returns the license for the given file.
@@ -358,4 +374,34 @@ the Free Software Foundation, either version 3 of the License, or
         let gpl = copyright.find_license_for_file(std::path::Path::new("debian/foo.c"));
         assert_eq!(gpl.unwrap().name().unwrap(), "GPL-3+");
     }
+
+    #[test]
+    fn test_paragraph() {
+        use deb822_lossless::FromDeb822Paragraph;
+
+        let para: deb822_lossless::lossy::Paragraph =
+            "Files: *\nCopyright: 2019 John Doe\nLicense: GPL-3+\n"
+                .parse::<deb822_lossless::lossy::Deb822>()
+                .unwrap()
+                .into_iter()
+                .next()
+                .unwrap();
+        match super::Paragraph::from_paragraph(&para).unwrap() {
+            super::Paragraph::Files(files) => assert_eq!(vec!["*"], files.files),
+            other => panic!("unexpected paragraph: {:?}", other),
+        }
+
+        let para: deb822_lossless::lossy::Paragraph = "Comment: nothing\n"
+            .parse::<deb822_lossless::lossy::Deb822>()
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
+        assert_eq!(
+            super::Paragraph::from_paragraph(&para)
+                .unwrap_err()
+                .to_string(),
+            "paragraph matches no variant of Paragraph (expected one of: Files, License, Format)"
+        );
+    }
 }
@@ -241,6 +241,82 @@ impl std::str::FromStr for PatchHeader {
     }
 }
 
+impl deb822_lossless::editor::Editable for PatchHeader {
+    type Error = deb822_lossless::ParseError;
+
+    fn parse(text: &str) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// A patch file, consisting of a patch header followed by the patch itself.
+///
+/// The header ends at the first line that is empty or starts with `---`.
+///
+/// # Example
+/// ```rust
+/// use deb822_lossless::editor::FileEditor;
+/// use dep3::lossless::PatchFile;
+/// use dep3::Forwarded;
+/// let td = tempfile::tempdir().unwrap();
+/// let path = td.path().join("fix-a-bug.patch");
+/// std::fs::write(&path, "Description: Fix a bug\n\n--- a/foo\n+++ b/foo\n").unwrap();
+/// let mut patch = FileEditor::<PatchFile>::open(&path).unwrap();
+/// patch.header.set_forwarded(Forwarded::NotNeeded);
+/// patch.commit().unwrap();
+/// assert_eq!(
+///     std::fs::read_to_string(&path).unwrap(),
+///     "Description: Fix a bug\nForwarded: not-needed\n\n--- a/foo\n+++ b/foo\n"
+/// );
+/// ```
+pub struct PatchFile {
+    /// The patch header.
+    pub header: PatchHeader,
+
+    /// The rest of the file, starting with the line that ends the header.
+    pub body: String,
+}
+
+impl std::fmt::Display for PatchFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.header, self.body)
+    }
+}
+
+impl std::str::FromStr for PatchFile {
+    type Err = deb822_lossless::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut end = 0;
+        for line in s.split_inclusive('\n') {
+            if line.trim_end_matches(['\r', '\n']).is_empty() || line.starts_with("---") {
+                break;
+            }
+            end += line.len();
+        }
+        Ok(PatchFile {
+            header: s[..end].parse()?,
+            body: s[end..].to_string(),
+        })
+    }
+}
+
+impl deb822_lossless::editor::Editable for PatchFile {
+    type Error = deb822_lossless::ParseError;
+
+    fn parse(text: &str) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::PatchHeader;
@@ -427,7 +503,7 @@ Bug-Debian: http://bugs.debian.org/123
 Bug-Ubuntu: http://bugs.launchpad.net/123
 "#;
 
-        let header = PatchHeader::from_str(&text).unwrap();
+        let header = PatchHeader::from_str(text).unwrap();
 
         assert_eq!(
             header.vendor_bugs("Debian").collect::<Vec<_>>(),
@@ -438,4 +514,18 @@ Bug-Ubuntu: http://bugs.launchpad.net/123
             vec!["http://bugs.launchpad.net/123".to_string()]
         );
     }
+
+    #[test]
+    fn test_patch_file() {
+        let text = "Description: Fix a bug\nAuthor: Joe <joe@example.com>\n---\n foo | 1 +\n";
+        let mut patch = super::PatchFile::from_str(text).unwrap();
+        assert_eq!(patch.header.description(), Some("Fix a bug".to_string()));
+        assert_eq!(patch.body, "---\n foo | 1 +\n");
+        assert_eq!(patch.to_string(), text);
+        patch.header.set_forwarded(super::Forwarded::No);
+        assert_eq!(
+            patch.to_string(),
+            "Description: Fix a bug\nAuthor: Joe <joe@example.com>\nForwarded: no\n---\n foo | 1 +\n"
+        );
+    }
 }
@@ -99,7 +99,7 @@ impl std::str::FromStr for PatchHeader {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let paragraph = Paragraph::from_str(s).map_err(|e| e.to_string())?;
-        let mut header = PatchHeader::from_paragraph(&paragraph)?;
+        let mut header = PatchHeader::from_paragraph(&paragraph).map_err(|e| e.to_string())?;
         if header.author.is_none() {
             header.author = paragraph.get("From").map(|v| v.to_string());
         }
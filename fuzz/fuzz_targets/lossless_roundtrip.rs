@@ -0,0 +1,32 @@
+//! Checks that the lossless parser never panics and never loses input, with any of the
+//! dialects.
+#![no_main]
+use deb822_lossless::dialect::Dialect;
+use deb822_lossless::Deb822;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    let (deb822, errors) = Deb822::from_str_relaxed(text);
+    assert_eq!(deb822.to_string(), text);
+    for error in &errors {
+        assert!(usize::from(error.range.end()) <= text.len());
+    }
+    // Walk the tree, to check that every node has a valid kind.
+    for paragraph in deb822.paragraphs() {
+        for entry in paragraph.entries() {
+            let _ = entry.key();
+            let _ = entry.value();
+        }
+    }
+
+    for dialect in [
+        Dialect::Deb822,
+        Dialect::DpkgStatus,
+        Dialect::Apt,
+        Dialect::Rfc822,
+        Dialect::RDescription,
+    ] {
+        let (deb822, _) = Deb822::from_str_relaxed_with_options(text, &dialect.options());
+        assert_eq!(deb822.to_string(), text, "{:?}", dialect);
+    }
+});
@@ -0,0 +1,42 @@
+//! Checks that the lossy and lossless parsers agree on the field values of valid input.
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    let (lossless, errors) = deb822_lossless::Deb822::from_str_relaxed(text);
+    let Ok(lossy) = text.parse::<deb822_lossless::lossy::Deb822>() else {
+        return;
+    };
+    if !errors.is_empty() {
+        return;
+    }
+
+    // The lossy parser keeps the leading newline of values that start on a continuation
+    // line, as documented in deb822_lossless::lossy.
+    let lossless = lossless
+        .paragraphs()
+        .map(|p| {
+            p.entries()
+                .map(|e| {
+                    let value = e.value();
+                    let lossy_value =
+                        if e.multiline_value().first_line.is_empty() && !value.is_empty() {
+                            format!("\n{}", value)
+                        } else {
+                            value
+                        };
+                    (e.key().unwrap(), lossy_value)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let lossy = lossy
+        .iter()
+        .map(|p| {
+            p.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(lossy, lossless);
+});
@@ -0,0 +1,20 @@
+//! Checks that the relations parser never panics and never loses input.
+#![no_main]
+use debian_control::lossless::relations::Relations;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    for allow_substvar in [false, true] {
+        let (relations, _) = Relations::parse_relaxed(text, allow_substvar);
+        assert_eq!(relations.to_string(), text);
+        for entry in relations.entries() {
+            for relation in entry.relations() {
+                let _ = relation.name();
+                let _ = relation.archqual();
+                let _ = relation.version();
+                let _ = relation.architectures().map(|a| a.count());
+                let _ = relation.profiles().count();
+            }
+        }
+    }
+});
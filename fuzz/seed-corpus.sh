@@ -0,0 +1,48 @@
+#!/bin/sh
+# Populate the seed corpora of the fuzz targets from bench/Sources.
+#
+# Usage: fuzz/seed-corpus.sh [PARAGRAPHS]
+#
+# Each of the first PARAGRAPHS (default: 32) paragraphs becomes a seed for the deb822
+# targets, and their relation fields become seeds for the relations target.
+set -e
+
+top=$(dirname "$0")/..
+count=${1:-32}
+corpus=$top/fuzz/corpus
+
+mkdir -p "$corpus/lossless_roundtrip" "$corpus/lossy_lossless_agree" "$corpus/relations_roundtrip"
+
+awk -v count="$count" -v corpus="$corpus" '
+BEGIN { RS = ""; ORS = "" }
+NR > count { exit }
+{
+    name = sprintf("sources-%04d", NR)
+    print $0 "\n" > (corpus "/lossless_roundtrip/" name)
+    print $0 "\n" > (corpus "/lossy_lossless_agree/" name)
+    close(corpus "/lossless_roundtrip/" name)
+    close(corpus "/lossy_lossless_agree/" name)
+
+    # Relation fields, with their continuation lines joined.
+    n = split($0, lines, "\n")
+    field = ""
+    value = ""
+    for (i = 1; i <= n + 1; i++) {
+        if (i <= n && lines[i] ~ /^[ \t]/) {
+            value = value " " substr(lines[i], 2)
+            continue
+        }
+        if (field != "") {
+            file = sprintf("%s/relations_roundtrip/%s-%s", corpus, name, tolower(field))
+            print value > file
+            close(file)
+        }
+        field = ""
+        if (i <= n && match(lines[i], /^(Build-Depends|Build-Depends-Indep|Build-Depends-Arch|Build-Conflicts|Build-Conflicts-Indep):/)) {
+            field = substr(lines[i], 1, RLENGTH - 1)
+            value = substr(lines[i], RLENGTH + 1)
+            sub(/^[ \t]+/, "", value)
+        }
+    }
+}
+' "$top/bench/Sources"
@@ -0,0 +1,539 @@
+//! Zero-copy parser for deb822 format.
+//!
+//! This parser is similar to the [`crate::lossy`] parser, but rather than allocating a
+//! `String` for every field name and value, it returns slices of the input text. Only values
+//! that span multiple lines are allocated, since their continuation lines need to be stripped
+//! of indentation.
+//!
+//! # Example
+//!
+//! ```rust
+//! use deb822_lossless::borrowed::BorrowedDeb822;
+//!
+//! let input = "Package: foo\nDescription: short\n long\n\nPackage: bar\n";
+//! let deb822 = BorrowedDeb822::parse(input).unwrap();
+//! assert_eq!(deb822.len(), 2);
+//! let foo = deb822.iter().next().unwrap();
+//! assert_eq!(foo.get("Package"), Some("foo"));
+//! assert_eq!(foo.get("Description"), Some("short\nlong"));
+//! ```
+use crate::dialect::{blank_lines, merge_values, Comments, DuplicateFields, ParseOptions};
+use crate::lex::SyntaxKind;
+use crate::lossy::Error;
+use std::borrow::Cow;
+
+/// A field in a deb822 paragraph, borrowing from the input text.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BorrowedField<'a> {
+    /// The name of the field.
+    pub name: &'a str,
+
+    /// The value of the field.
+    pub value: Cow<'a, str>,
+}
+
+/// A deb822 paragraph, borrowing from the input text.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BorrowedParagraph<'a> {
+    /// Fields in the paragraph.
+    pub fields: Vec<BorrowedField<'a>>,
+}
+
+impl<'a> BorrowedParagraph<'a> {
+    /// Get the value of a field by name.
+    ///
+    /// Field names are compared case-insensitively.
+    /// Returns `None` if the field does not exist.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|field| crate::common::key_eq(field.name, name))
+            .map(|field| field.value.as_ref())
+    }
+
+    /// Check if the paragraph contains the given field.
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Check if the paragraph is empty.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Return the number of fields in the paragraph.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Iterate over the fields in the paragraph.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &str)> {
+        self.fields
+            .iter()
+            .map(|field| (field.name, field.value.as_ref()))
+    }
+
+    /// Iterate over the names of the fields in the paragraph.
+    pub fn keys(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.fields.iter().map(|field| field.name)
+    }
+}
+
+impl std::fmt::Display for BorrowedField<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let lines = self.value.lines().collect::<Vec<_>>();
+        if lines.len() > 1 {
+            write!(f, "{}:", self.name)?;
+            for line in lines {
+                writeln!(f, " {}", line)?;
+            }
+            Ok(())
+        } else {
+            writeln!(f, "{}: {}", self.name, self.value)
+        }
+    }
+}
+
+impl std::fmt::Display for BorrowedParagraph<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for field in &self.fields {
+            field.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<BorrowedParagraph<'_>> for crate::lossy::Paragraph {
+    fn from(paragraph: BorrowedParagraph<'_>) -> Self {
+        paragraph
+            .fields
+            .into_iter()
+            .map(|field| (field.name.to_string(), field.value.into_owned()))
+            .collect()
+    }
+}
+
+/// A deb822 document, borrowing from the input text.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BorrowedDeb822<'a>(Vec<BorrowedParagraph<'a>>);
+
+impl<'a> BorrowedDeb822<'a> {
+    /// Parse a deb822 document.
+    pub fn parse(text: &'a str) -> Result<Self, Error> {
+        parse(text).map_err(|(e, _line)| e)
+    }
+
+    /// Parse a deb822 document, using the rules of a particular dialect.
+    pub fn parse_with_options(text: &'a str, options: &ParseOptions) -> Result<Self, Error> {
+        parse_with_options(text, options).map_err(|(e, _line)| e)
+    }
+
+    /// Number of paragraphs in the document.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check if the document is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the paragraphs in the document.
+    pub fn iter(&self) -> impl Iterator<Item = &BorrowedParagraph<'a>> {
+        self.0.iter()
+    }
+}
+
+impl<'a> TryFrom<&'a str> for BorrowedDeb822<'a> {
+    type Error = Error;
+
+    fn try_from(text: &'a str) -> Result<Self, Self::Error> {
+        Self::parse(text)
+    }
+}
+
+impl<'a> IntoIterator for BorrowedDeb822<'a> {
+    type Item = BorrowedParagraph<'a>;
+    type IntoIter = std::vec::IntoIter<BorrowedParagraph<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl From<BorrowedDeb822<'_>> for Vec<crate::lossy::Paragraph> {
+    fn from(doc: BorrowedDeb822<'_>) -> Self {
+        doc.0.into_iter().map(|p| p.into()).collect()
+    }
+}
+
+/// Parse a deb822 document, returning the (0-based) line on which any error occurred.
+pub(crate) fn parse(s: &str) -> Result<BorrowedDeb822<'_>, (Error, usize)> {
+    parse_with_options(s, &ParseOptions::default())
+}
+
+/// Parse a deb822 document using the rules of a particular dialect, returning the (0-based)
+/// line on which any error occurred.
+pub(crate) fn parse_with_options<'a>(
+    s: &'a str,
+    options: &ParseOptions,
+) -> Result<BorrowedDeb822<'a>, (Error, usize)> {
+    let (leading, trailing) = blank_lines(s);
+    if leading.is_some() && !options.allow_leading_blank_lines {
+        return Err((Error::UnexpectedBlankLine, 0));
+    }
+    if let Some(range) = trailing.filter(|_| !options.allow_trailing_blank_lines) {
+        return Err((
+            Error::UnexpectedBlankLine,
+            s[..range.start].matches('\n').count(),
+        ));
+    }
+
+    // Annotate every token with the line it appears on, rejecting any tokens the dialect
+    // does not allow as they are reached.
+    let mut tokens = crate::lex::lex_with_options(s, options)
+        .scan(0, |line, (k, t)| {
+            let token_line = *line;
+            if k == SyntaxKind::NEWLINE {
+                *line += 1;
+            }
+            Some((k, t, token_line))
+        })
+        .map(|(k, t, line)| match k {
+            SyntaxKind::COMMENT if options.comments == Comments::Forbidden => {
+                Err((Error::UnexpectedToken(k, t.to_string()), line))
+            }
+            SyntaxKind::NEWLINE if !options.allow_crlf && t.contains('\r') => {
+                Err((Error::UnexpectedToken(k, t.to_string()), line))
+            }
+            _ => Ok((k, t, line)),
+        })
+        .peekable();
+
+    let mut paragraphs = Vec::new();
+    let mut current_paragraph = Vec::new();
+
+    while let Some((k, t, line)) = tokens.next().transpose()? {
+        match k {
+            SyntaxKind::EMPTY_LINE
+            | SyntaxKind::PARAGRAPH
+            | SyntaxKind::ROOT
+            | SyntaxKind::ENTRY => unreachable!(),
+            SyntaxKind::INDENT | SyntaxKind::COLON | SyntaxKind::ERROR => {
+                return Err((Error::UnexpectedToken(k, t.to_string()), line));
+            }
+            SyntaxKind::WHITESPACE => {
+                // ignore whitespace
+            }
+            SyntaxKind::KEY => {
+                match tokens.next().transpose()? {
+                    Some((SyntaxKind::COLON, _, _)) => {}
+                    Some((k, t, line)) => {
+                        return Err((Error::UnexpectedToken(k, t.to_string()), line));
+                    }
+                    None => {
+                        return Err((Error::UnexpectedEof, line));
+                    }
+                }
+
+                while peek_kind(&mut tokens)? == Some(SyntaxKind::WHITESPACE) {
+                    tokens.next();
+                }
+
+                let mut value = Cow::Borrowed("");
+                while let Some((k, t, line)) = tokens.next().transpose()? {
+                    match k {
+                        SyntaxKind::VALUE => {
+                            value = Cow::Borrowed(t);
+                        }
+                        SyntaxKind::NEWLINE => {
+                            break;
+                        }
+                        _ => return Err((Error::UnexpectedToken(k, t.to_string()), line)),
+                    }
+                }
+
+                // while the next line starts with INDENT, it's a continuation of the value
+                loop {
+                    match peek_kind(&mut tokens)? {
+                        Some(SyntaxKind::INDENT) => {}
+                        Some(SyntaxKind::COMMENT) if options.comments == Comments::Anywhere => {
+                            // Comment lines may appear between continuation lines.
+                            skip_line(&mut tokens)?;
+                            continue;
+                        }
+                        _ => break,
+                    }
+                    tokens.next();
+                    // Lines consisting only of whitespace are not part of the value, so the
+                    // newline is only added once the line turns out to have any content.
+                    let mut blank = true;
+                    loop {
+                        match peek_kind(&mut tokens)? {
+                            Some(SyntaxKind::VALUE) => {
+                                if let Some(Ok((_, t, _))) = tokens.next() {
+                                    let value = value.to_mut();
+                                    if blank {
+                                        value.push('\n');
+                                        blank = false;
+                                    }
+                                    value.push_str(t);
+                                }
+                            }
+                            Some(SyntaxKind::NEWLINE) => {
+                                tokens.next();
+                                break;
+                            }
+                            Some(SyntaxKind::KEY) | None => {
+                                break;
+                            }
+                            Some(_) => {
+                                let (k, t, line) = tokens.next().unwrap()?;
+                                return Err((Error::UnexpectedToken(k, t.to_string()), line));
+                            }
+                        }
+                    }
+                }
+
+                let existing = current_paragraph
+                    .iter()
+                    .position(|f: &BorrowedField| crate::common::key_eq(f.name, t));
+                match (existing, options.duplicate_fields) {
+                    (None, _) | (Some(_), DuplicateFields::Allow) => {
+                        current_paragraph.push(BorrowedField { name: t, value });
+                    }
+                    (Some(_), DuplicateFields::Error) => {
+                        return Err((Error::DuplicateField(t.to_string()), line));
+                    }
+                    (Some(_), DuplicateFields::KeepFirst) => {}
+                    (Some(i), DuplicateFields::KeepLast) => {
+                        current_paragraph.remove(i);
+                        current_paragraph.push(BorrowedField { name: t, value });
+                    }
+                    (Some(i), DuplicateFields::MergeAsList) => {
+                        let field = &mut current_paragraph[i];
+                        field.value = Cow::Owned(merge_values(&field.value, &value));
+                    }
+                }
+            }
+            SyntaxKind::VALUE => {
+                return Err((Error::UnexpectedToken(k, t.to_string()), line));
+            }
+            SyntaxKind::COMMENT => {
+                skip_line(&mut tokens)?;
+            }
+            SyntaxKind::NEWLINE => {
+                if !current_paragraph.is_empty() {
+                    paragraphs.push(BorrowedParagraph {
+                        fields: current_paragraph,
+                    });
+                    current_paragraph = Vec::new();
+                }
+            }
+        }
+    }
+    if !current_paragraph.is_empty() {
+        paragraphs.push(BorrowedParagraph {
+            fields: current_paragraph,
+        });
+    }
+    Ok(BorrowedDeb822(paragraphs))
+}
+
+type Token<'a> = (SyntaxKind, &'a str, usize);
+
+/// Returns the kind of the next token, or the error for it if the dialect does not allow it.
+fn peek_kind<'a>(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = Result<Token<'a>, (Error, usize)>>>,
+) -> Result<Option<SyntaxKind>, (Error, usize)> {
+    match tokens.peek() {
+        Some(Ok((k, _, _))) => Ok(Some(*k)),
+        Some(Err(_)) => tokens.next().unwrap().map(|_| None),
+        None => Ok(None),
+    }
+}
+
+/// Skip the remaining tokens on the current line, including the newline.
+fn skip_line<'a>(
+    tokens: &mut impl Iterator<Item = Result<Token<'a>, (Error, usize)>>,
+) -> Result<(), (Error, usize)> {
+    for token in tokens {
+        if token?.0 == SyntaxKind::NEWLINE {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = r#"Package: hello
+Version: 2.10
+Description: A program that says hello
+ Some more text
+
+# A comment
+
+Package: world
+"#;
+        let deb822 = BorrowedDeb822::parse(input).unwrap();
+        assert_eq!(deb822.len(), 2);
+        assert!(!deb822.is_empty());
+
+        let para = deb822.iter().next().unwrap();
+        assert_eq!(para.len(), 3);
+        assert_eq!(para.get("Package"), Some("hello"));
+        assert_eq!(
+            para.get("Description"),
+            Some("A program that says hello\nSome more text")
+        );
+        assert_eq!(para.get("Nonexistent"), None);
+        assert!(para.contains_key("Version"));
+        assert_eq!(
+            para.keys().collect::<Vec<_>>(),
+            vec!["Package", "Version", "Description"]
+        );
+
+        // Single-line values point into the input, multi-line values are owned.
+        assert!(matches!(para.fields[0].value, Cow::Borrowed(_)));
+        assert!(std::ptr::eq(para.fields[0].name, &input[..7]));
+        assert!(matches!(para.fields[2].value, Cow::Owned(_)));
+    }
+
+    #[test]
+    fn test_error() {
+        assert!(matches!(
+            BorrowedDeb822::parse("Package: foo\n bar\n\n baz\n"),
+            Err(Error::UnexpectedToken(SyntaxKind::INDENT, _))
+        ));
+        assert!(matches!(
+            parse("Package: foo\n\nBar\n"),
+            Err((Error::UnexpectedToken(SyntaxKind::NEWLINE, _), 2))
+        ));
+    }
+
+    #[test]
+    fn test_continuation_at_eof() {
+        let deb822 = BorrowedDeb822::parse("Package: foo\n bar").unwrap();
+        assert_eq!(
+            deb822.iter().next().unwrap().get("Package"),
+            Some("foo\nbar")
+        );
+    }
+
+    #[test]
+    fn test_whitespace_only_lines() {
+        for (text, expected) in [
+            ("Package: foo\n \n bar\n", "foo\nbar"),
+            ("Package: foo\n \t \n", "foo"),
+            ("Package:\n \n bar\n", "\nbar"),
+        ] {
+            let deb822 = BorrowedDeb822::parse(text).unwrap();
+            let paragraph = deb822.iter().next().unwrap();
+            assert_eq!(paragraph.get("Package"), Some(expected), "{:?}", text);
+
+            let lossless: crate::lossless::Deb822 = text.parse().unwrap();
+            let lossless = lossless.paragraphs().next().unwrap();
+            assert_eq!(
+                lossless.get("Package").as_deref(),
+                Some(expected.trim_start()),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_lossy() {
+        let text =
+            std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/bench/Sources")).unwrap();
+        let borrowed = BorrowedDeb822::parse(&text).unwrap();
+        let lossy: crate::lossy::Deb822 = text.parse().unwrap();
+        assert_eq!(Vec::from(borrowed), Vec::from(lossy));
+    }
+
+    #[test]
+    fn test_parse_with_options() {
+        use crate::dialect::Dialect;
+
+        let text = "Package: foo\n# Comment\nSigned-By:\n a\n# Comment\n b\n";
+        assert!(matches!(
+            BorrowedDeb822::parse_with_options(text, &Dialect::DpkgStatus.options()),
+            Err(Error::UnexpectedToken(SyntaxKind::COMMENT, _))
+        ));
+        let deb822 = BorrowedDeb822::parse_with_options(text, &Dialect::Apt.options()).unwrap();
+        assert_eq!(
+            deb822.iter().next().unwrap().get("Signed-By"),
+            Some("\na\nb")
+        );
+
+        assert!(matches!(
+            parse_with_options("A: b\r\n", &Dialect::Deb822.options()),
+            Err((Error::UnexpectedToken(SyntaxKind::NEWLINE, _), 0))
+        ));
+        assert!(matches!(
+            parse_with_options("A: b\n c\r\n", &Dialect::Deb822.options()),
+            Err((Error::UnexpectedToken(SyntaxKind::NEWLINE, _), 1))
+        ));
+        assert!(matches!(
+            parse_with_options("A: b\n\n# c\nD: e\n", &Dialect::DpkgStatus.options()),
+            Err((Error::UnexpectedToken(SyntaxKind::COMMENT, _), 2))
+        ));
+        assert!(matches!(
+            parse_with_options("A: b\nC: d\na: e\n", &Dialect::Deb822.options()),
+            Err((Error::DuplicateField(name), 2)) if name == "a"
+        ));
+        assert!(matches!(
+            parse_with_options("A: b\n\n\n", &Dialect::Deb822.options()),
+            Err((Error::UnexpectedBlankLine, 1))
+        ));
+        assert!(parse_with_options("A: b\r\nA: c\r\n\r\n", &Default::default()).is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_fields() {
+        let text = "Package: foo\nDepends: a\nSection: libs\ndepends: b,\n c\n";
+        let get = |duplicate_fields| {
+            let options = ParseOptions {
+                duplicate_fields,
+                ..Default::default()
+            };
+            let deb822 = BorrowedDeb822::parse_with_options(text, &options).unwrap();
+            let paragraph = deb822.into_iter().next().unwrap();
+            paragraph
+                .fields
+                .into_iter()
+                .map(|f| (f.name, f.value.into_owned()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(get(DuplicateFields::Allow).len(), 4);
+        assert_eq!(
+            get(DuplicateFields::KeepFirst),
+            vec![
+                ("Package", "foo".to_string()),
+                ("Depends", "a".to_string()),
+                ("Section", "libs".to_string())
+            ]
+        );
+        assert_eq!(
+            get(DuplicateFields::KeepLast),
+            vec![
+                ("Package", "foo".to_string()),
+                ("Section", "libs".to_string()),
+                ("depends", "b,\nc".to_string())
+            ]
+        );
+        assert_eq!(
+            get(DuplicateFields::MergeAsList),
+            vec![
+                ("Package", "foo".to_string()),
+                ("Depends", "a, b,\nc".to_string()),
+                ("Section", "libs".to_string())
+            ]
+        );
+    }
+}
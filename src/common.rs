@@ -23,3 +23,16 @@ pub(crate) fn is_valid_key_char(c: char) -> bool {
 pub(crate) fn is_valid_initial_key_char(c: char) -> bool {
     c != '-' && is_valid_key_char(c)
 }
+
+#[inline]
+pub(crate) fn key_eq(a: &str, b: &str) -> bool {
+    // deb822(5) says that field names are not case-sensitive.
+    a.eq_ignore_ascii_case(b)
+}
+
+/// Returns whether `key` can be used as a field name.
+pub(crate) fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c != '#' && is_valid_initial_key_char(c))
+        && chars.all(is_valid_key_char)
+}
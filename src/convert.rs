@@ -1,4 +1,102 @@
 //! Conversion between Deb822-like paragraphs and Rust objects.
+use rowan::TextRange;
+use std::borrow::Cow;
+
+/// An error converting a paragraph to an object.
+#[derive(Debug)]
+pub enum Error {
+    /// A required field is missing.
+    MissingField {
+        /// The name of the field.
+        field: String,
+    },
+
+    /// The value of a field could not be parsed.
+    InvalidValue {
+        /// The name of the field.
+        field: String,
+        /// The range of the value in the source text, if known.
+        range: Option<TextRange>,
+        /// The error returned by the parser for the value.
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// The paragraph contains a field that is not allowed.
+    UnknownField {
+        /// The name of the field.
+        field: String,
+        /// The range of the value in the source text, if known.
+        range: Option<TextRange>,
+    },
+
+    /// The paragraph does not match any variant of an enum.
+    NoMatchingVariant {
+        /// The name of the enum.
+        name: String,
+        /// Descriptions of the paragraphs the variants match, e.g. `Type: deb`.
+        expected: Vec<String>,
+    },
+}
+
+impl Error {
+    /// Create an [`Error::InvalidValue`] from the error returned by the parser for the value.
+    pub fn invalid_value(
+        field: &str,
+        range: Option<TextRange>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Error::InvalidValue {
+            field: field.to_string(),
+            range,
+            source: source.into(),
+        }
+    }
+
+    /// Returns the name of the field the error is about, if any.
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Error::MissingField { field }
+            | Error::InvalidValue { field, .. }
+            | Error::UnknownField { field, .. } => Some(field),
+            Error::NoMatchingVariant { .. } => None,
+        }
+    }
+
+    /// Returns the range of the offending value in the source text, if known.
+    pub fn range(&self) -> Option<TextRange> {
+        match self {
+            Error::InvalidValue { range, .. } | Error::UnknownField { range, .. } => *range,
+            Error::MissingField { .. } | Error::NoMatchingVariant { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::MissingField { field } => write!(f, "missing field: {}", field),
+            Error::InvalidValue { field, source, .. } => {
+                write!(f, "parsing field {}: {}", field, source)
+            }
+            Error::UnknownField { field, .. } => write!(f, "unknown field: {}", field),
+            Error::NoMatchingVariant { name, expected } => write!(
+                f,
+                "paragraph matches no variant of {} (expected one of: {})",
+                name,
+                expected.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidValue { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 /// Abstract trait for accessing and modifying key-value pairs in a paragraph.
 pub trait Deb822LikeParagraph: FromIterator<(String, String)> {
@@ -10,6 +108,106 @@ pub trait Deb822LikeParagraph: FromIterator<(String, String)> {
 
     /// Remove a key-value pair.
     fn remove(&mut self, key: &str);
+
+    /// Get the range of the value for the given key in the source text.
+    ///
+    /// Returns `None` if the paragraph does not keep track of positions.
+    fn value_range(&self, _key: &str) -> Option<TextRange> {
+        None
+    }
+}
+
+/// Read-only access to the fields of a paragraph.
+///
+/// Unlike [`Deb822LikeParagraph`], this is also implemented by paragraphs that can not be
+/// modified, such as [`crate::borrowed::BorrowedParagraph`], and can return values without
+/// copying them. The derived conversions also require it for types that need to list the keys
+/// of a paragraph: those with a `rest` field or `deny_unknown_fields`, and enums.
+pub trait ReadableParagraph {
+    /// Get the value for the given key.
+    fn get(&self, key: &str) -> Option<Cow<'_, str>>;
+
+    /// Get the keys, in order.
+    fn keys(&self) -> Vec<String>;
+
+    /// Check whether the paragraph contains the given key.
+    fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Get the fields as name/value pairs, in order.
+    ///
+    /// Unlike combining [`ReadableParagraph::keys`] with [`ReadableParagraph::get`], this
+    /// returns every field, including repeated ones.
+    fn items(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)>;
+}
+
+impl<P: ReadableParagraph + ?Sized> ReadableParagraph for &P {
+    fn get(&self, key: &str) -> Option<Cow<'_, str>> {
+        (**self).get(key)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        (**self).keys()
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        (**self).contains_key(key)
+    }
+
+    fn items(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)> {
+        (**self).items()
+    }
+}
+
+impl ReadableParagraph for crate::lossy::Paragraph {
+    fn get(&self, key: &str) -> Option<Cow<'_, str>> {
+        crate::lossy::Paragraph::get(self, key).map(Cow::Borrowed)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.iter().map(|(k, _)| k.to_string()).collect()
+    }
+
+    fn items(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)> {
+        self.iter()
+            .map(|(k, v)| (Cow::Borrowed(k), Cow::Borrowed(v)))
+            .collect()
+    }
+}
+
+impl ReadableParagraph for crate::lossless::Paragraph {
+    fn get(&self, key: &str) -> Option<Cow<'_, str>> {
+        crate::lossless::Paragraph::get(self, key).map(Cow::Owned)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        crate::lossless::Paragraph::keys(self).collect()
+    }
+
+    fn items(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)> {
+        crate::lossless::Paragraph::items(self)
+            .map(|(k, v)| (Cow::Owned(k), Cow::Owned(v)))
+            .collect()
+    }
+}
+
+impl ReadableParagraph for crate::borrowed::BorrowedParagraph<'_> {
+    fn get(&self, key: &str) -> Option<Cow<'_, str>> {
+        crate::borrowed::BorrowedParagraph::get(self, key).map(Cow::Borrowed)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        crate::borrowed::BorrowedParagraph::keys(self)
+            .map(|k| k.to_string())
+            .collect()
+    }
+
+    fn items(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)> {
+        self.iter()
+            .map(|(k, v)| (Cow::Borrowed(k), Cow::Borrowed(v)))
+            .collect()
+    }
 }
 
 impl Deb822LikeParagraph for crate::lossy::Paragraph {
@@ -38,12 +236,16 @@ impl Deb822LikeParagraph for crate::lossless::Paragraph {
     fn remove(&mut self, key: &str) {
         crate::lossless::Paragraph::remove(self, key);
     }
+
+    fn value_range(&self, key: &str) -> Option<TextRange> {
+        self.get_entry(key)?.value_range()
+    }
 }
 
 /// Convert a paragraph to this object.
 pub trait FromDeb822Paragraph<P: Deb822LikeParagraph> {
     /// Convert a paragraph to this object.
-    fn from_paragraph(paragraph: &P) -> Result<Self, String>
+    fn from_paragraph(paragraph: &P) -> Result<Self, Error>
     where
         Self: Sized;
 }
@@ -57,11 +259,84 @@ pub trait ToDeb822Paragraph<P: Deb822LikeParagraph> {
     fn update_paragraph(&self, paragraph: &mut P);
 }
 
+/// A paragraph converts to itself, so that newtypes can wrap a paragraph.
+impl FromDeb822Paragraph<crate::lossy::Paragraph> for crate::lossy::Paragraph {
+    fn from_paragraph(paragraph: &crate::lossy::Paragraph) -> Result<Self, Error> {
+        Ok(paragraph.clone())
+    }
+}
+
+impl ToDeb822Paragraph<crate::lossy::Paragraph> for crate::lossy::Paragraph {
+    fn to_paragraph(&self) -> crate::lossy::Paragraph {
+        self.clone()
+    }
+
+    fn update_paragraph(&self, paragraph: &mut crate::lossy::Paragraph) {
+        *paragraph = self.clone();
+    }
+}
+
+/// A paragraph converts to a copy of itself, so that newtypes can wrap a paragraph.
+impl FromDeb822Paragraph<crate::lossless::Paragraph> for crate::lossless::Paragraph {
+    fn from_paragraph(paragraph: &crate::lossless::Paragraph) -> Result<Self, Error> {
+        Ok(paragraph.detached())
+    }
+}
+
+impl ToDeb822Paragraph<crate::lossless::Paragraph> for crate::lossless::Paragraph {
+    fn to_paragraph(&self) -> crate::lossless::Paragraph {
+        self.detached()
+    }
+
+    /// Set the fields of `paragraph` to those of this paragraph, keeping the formatting of
+    /// fields that already exist.
+    fn update_paragraph(&self, paragraph: &mut crate::lossless::Paragraph) {
+        for key in paragraph.keys().collect::<Vec<_>>() {
+            if !self.contains_key(&key) {
+                paragraph.remove(&key);
+            }
+        }
+        for (key, value) in self.items() {
+            paragraph.set(&key, &value);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "derive")]
     use super::*;
 
+    #[test]
+    fn test_readable_paragraph() {
+        use super::ReadableParagraph;
+
+        fn fields(p: &impl ReadableParagraph) -> Vec<(String, String)> {
+            p.keys()
+                .into_iter()
+                .map(|k| {
+                    let v = p.get(&k).unwrap().into_owned();
+                    (k, v)
+                })
+                .collect()
+        }
+
+        let text = "Package: foo\nDescription: short\n long\n";
+        let expected = vec![
+            ("Package".to_string(), "foo".to_string()),
+            ("Description".to_string(), "short\nlong".to_string()),
+        ];
+        let lossy: crate::lossy::Paragraph = text.parse().unwrap();
+        let lossless: crate::lossless::Paragraph = text.parse().unwrap();
+        let borrowed = crate::borrowed::BorrowedDeb822::parse(text).unwrap();
+        let borrowed = borrowed.iter().next().unwrap();
+        assert_eq!(fields(&lossy), expected);
+        assert_eq!(fields(&lossless), expected);
+        assert_eq!(fields(borrowed), expected);
+        assert!(ReadableParagraph::contains_key(borrowed, "package"));
+        assert!(!ReadableParagraph::contains_key(&lossy, "Version"));
+    }
+
     #[cfg(feature = "derive")]
     mod derive {
         use super::*;
@@ -193,5 +468,425 @@ mod tests {
             assert_eq!(para.get("baz"), Some("blah"));
             assert_eq!(para.to_string(), "baz: blah\n");
         }
+
+        #[derive(FromDeb822, ToDeb822, Debug, PartialEq)]
+        struct Source {
+            #[deb822(field = "Source")]
+            name: String,
+        }
+
+        #[derive(FromDeb822, ToDeb822, Debug, PartialEq)]
+        struct Binary {
+            #[deb822(field = "Package")]
+            name: String,
+            #[deb822(field = "Architecture")]
+            architecture: Option<String>,
+        }
+
+        #[test]
+        fn test_enum_present_field() {
+            #[derive(FromDeb822, ToDeb822, Debug, PartialEq)]
+            enum Stanza {
+                #[deb822(tag_field = "Source")]
+                Source(Source),
+                #[deb822(tag_field = "Package")]
+                Binary(Binary),
+            }
+
+            let para: crate::lossy::Paragraph =
+                "Package: foo\nArchitecture: all\n".parse().unwrap();
+            let stanza = Stanza::from_paragraph(&para).unwrap();
+            assert_eq!(
+                stanza,
+                Stanza::Binary(Binary {
+                    name: "foo".to_string(),
+                    architecture: Some("all".to_string()),
+                })
+            );
+            let para: crate::lossy::Paragraph = stanza.to_paragraph();
+            assert_eq!(para.to_string(), "Package: foo\nArchitecture: all\n");
+
+            let para: crate::lossy::Paragraph = "Source: foo\n".parse().unwrap();
+            assert_eq!(
+                Stanza::from_paragraph(&para).unwrap(),
+                Stanza::Source(Source {
+                    name: "foo".to_string()
+                })
+            );
+
+            let para: crate::lossy::Paragraph = "Other: foo\n".parse().unwrap();
+            assert_eq!(
+                Stanza::from_paragraph(&para).unwrap_err().to_string(),
+                "paragraph matches no variant of Stanza (expected one of: Source, Package)"
+            );
+        }
+
+        #[test]
+        fn test_enum_tag() {
+            #[derive(FromDeb822, ToDeb822, Debug, PartialEq)]
+            #[deb822(tag_field = "Type")]
+            enum Repository {
+                #[deb822(tag = "deb")]
+                Binary {
+                    #[deb822(field = "URIs")]
+                    uris: String,
+                },
+                #[deb822(tag = "deb-src")]
+                Source {
+                    #[deb822(field = "URIs")]
+                    uris: String,
+                },
+                Other(crate::lossy::Paragraph),
+            }
+
+            let para: crate::lossy::Paragraph = "Type: deb-src\nURIs: http://example.com/\n"
+                .parse()
+                .unwrap();
+            let repository = Repository::from_paragraph(&para).unwrap();
+            assert_eq!(
+                repository,
+                Repository::Source {
+                    uris: "http://example.com/".to_string()
+                }
+            );
+            let para: crate::lossy::Paragraph = repository.to_paragraph();
+            assert_eq!(
+                para.to_string(),
+                "URIs: http://example.com/\nType: deb-src\n"
+            );
+
+            let para: crate::lossy::Paragraph = "Type: Other\nFoo: bar\n".parse().unwrap();
+            assert_eq!(
+                Repository::from_paragraph(&para).unwrap(),
+                Repository::Other(para.clone())
+            );
+
+            let para: crate::lossy::Paragraph = "Type: rpm\n".parse().unwrap();
+            assert!(Repository::from_paragraph(&para).is_err());
+        }
+
+        #[test]
+        fn test_enum_fallback() {
+            #[derive(FromDeb822, ToDeb822, Debug, PartialEq)]
+            enum Stanza {
+                #[deb822(tag_field = "Source")]
+                Source(Source),
+                Other(crate::lossy::Paragraph),
+            }
+
+            let para: crate::lossy::Paragraph = "Package: foo\n".parse().unwrap();
+            assert_eq!(
+                Stanza::from_paragraph(&para).unwrap(),
+                Stanza::Other(para.clone())
+            );
+        }
+
+        #[test]
+        fn test_enum_update() {
+            #[derive(FromDeb822, ToDeb822, Debug, PartialEq)]
+            enum Stanza {
+                #[deb822(tag_field = "Source")]
+                Source(Source),
+                #[deb822(tag_field = "Package")]
+                Binary(Binary),
+            }
+
+            let mut para: crate::lossless::Paragraph =
+                "Package: foo\n# comment\nArchitecture: all\n"
+                    .parse()
+                    .unwrap();
+            let mut stanza = Stanza::from_paragraph(&para).unwrap();
+            if let Stanza::Binary(binary) = &mut stanza {
+                binary.architecture = Some("any".to_string());
+            }
+            stanza.update_paragraph(&mut para);
+            assert_eq!(
+                para.to_string(),
+                "Package: foo\n# comment\nArchitecture: any\n"
+            );
+
+            // Switching to another variant replaces the fields of the old one.
+            let stanza = Stanza::Source(Source {
+                name: "bar".to_string(),
+            });
+            stanza.update_paragraph(&mut para);
+            assert_eq!(Stanza::from_paragraph(&para).unwrap(), stanza);
+            assert_eq!(para.get("Package"), None);
+            assert_eq!(para.get("Architecture"), None);
+        }
+
+        #[test]
+        fn test_enum_tag_update() {
+            #[derive(FromDeb822, ToDeb822, Debug, PartialEq)]
+            #[deb822(tag_field = "Type")]
+            enum Entry {
+                #[deb822(tag = "file")]
+                File {
+                    #[deb822(field = "Path")]
+                    path: String,
+                },
+                #[deb822(tag = "link")]
+                Link {
+                    #[deb822(field = "Target")]
+                    target: String,
+                },
+            }
+
+            let mut para: crate::lossy::Paragraph = "Type: file\nPath: /etc/foo\n".parse().unwrap();
+            let entry = Entry::Link {
+                target: "/etc/bar".to_string(),
+            };
+            entry.update_paragraph(&mut para);
+            assert_eq!(para.to_string(), "Target: /etc/bar\nType: link\n");
+            assert_eq!(Entry::from_paragraph(&para).unwrap(), entry);
+        }
+
+        #[test]
+        fn test_newtype() {
+            #[derive(FromDeb822, ToDeb822, Debug, PartialEq)]
+            struct Wrapper(Binary);
+
+            let para: crate::lossy::Paragraph = "Package: foo\n".parse().unwrap();
+            let wrapper = Wrapper::from_paragraph(&para).unwrap();
+            assert_eq!(
+                wrapper,
+                Wrapper(Binary {
+                    name: "foo".to_string(),
+                    architecture: None,
+                })
+            );
+            let para: crate::lossy::Paragraph = wrapper.to_paragraph();
+            assert_eq!(para.to_string(), "Package: foo\n");
+        }
+
+        #[test]
+        fn test_newtype_paragraph() {
+            #[derive(FromDeb822, ToDeb822)]
+            struct Raw(crate::lossless::Paragraph);
+
+            let mut para: crate::lossless::Paragraph =
+                "Foo: bar\n# comment\nBaz: blah\n".parse().unwrap();
+            let raw = Raw::from_paragraph(&para).unwrap();
+            assert_eq!(raw.0.to_string(), para.to_string());
+
+            let mut raw = raw;
+            raw.0.set("Baz", "new");
+            raw.0.remove("Foo");
+            raw.update_paragraph(&mut para);
+            assert_eq!(para.to_string(), "# comment\nBaz: new\n");
+        }
+
+        #[test]
+        fn test_default() {
+            fn yes() -> String {
+                "yes".to_string()
+            }
+
+            #[derive(FromDeb822, Debug, PartialEq)]
+            struct Foo {
+                #[deb822(field = "Name")]
+                name: String,
+                #[deb822(field = "Count", default)]
+                count: u32,
+                #[deb822(field = "Enabled", default = yes)]
+                enabled: String,
+                #[deb822(field = "Extra", default)]
+                extra: Option<String>,
+            }
+
+            let para: crate::lossy::Paragraph = "Name: foo\n".parse().unwrap();
+            assert_eq!(
+                Foo::from_paragraph(&para).unwrap(),
+                Foo {
+                    name: "foo".to_string(),
+                    count: 0,
+                    enabled: "yes".to_string(),
+                    extra: None,
+                }
+            );
+
+            let para: crate::lossy::Paragraph = "Name: foo\nCount: 3\nEnabled: no\nExtra: x\n"
+                .parse()
+                .unwrap();
+            assert_eq!(
+                Foo::from_paragraph(&para).unwrap(),
+                Foo {
+                    name: "foo".to_string(),
+                    count: 3,
+                    enabled: "no".to_string(),
+                    extra: Some("x".to_string()),
+                }
+            );
+
+            let para: crate::lossy::Paragraph = "Name: foo\nCount: x\n".parse().unwrap();
+            assert!(Foo::from_paragraph(&para).is_err());
+        }
+
+        #[test]
+        fn test_skip() {
+            #[derive(FromDeb822, ToDeb822, Debug, PartialEq)]
+            struct Foo {
+                #[deb822(field = "Name")]
+                name: String,
+                #[deb822(skip)]
+                cache: Vec<String>,
+            }
+
+            let para: crate::lossy::Paragraph = "Name: foo\ncache: bar\n".parse().unwrap();
+            let mut foo = Foo::from_paragraph(&para).unwrap();
+            assert!(foo.cache.is_empty());
+
+            foo.cache.push("blah".to_string());
+            let para: crate::lossy::Paragraph = foo.to_paragraph();
+            assert_eq!(para.to_string(), "Name: foo\n");
+        }
+
+        #[test]
+        fn test_skip_serializing_if() {
+            fn is_zero(v: &u32) -> bool {
+                *v == 0
+            }
+
+            #[derive(FromDeb822, ToDeb822)]
+            struct Foo {
+                #[deb822(field = "Name")]
+                name: String,
+                #[deb822(field = "Count", default, skip_serializing_if = is_zero)]
+                count: u32,
+            }
+
+            let mut para: crate::lossless::Paragraph = "Name: foo\nCount: 2\n".parse().unwrap();
+            let mut foo = Foo::from_paragraph(&para).unwrap();
+            let to: crate::lossy::Paragraph = foo.to_paragraph();
+            assert_eq!(to.to_string(), "Name: foo\nCount: 2\n");
+
+            foo.count = 0;
+            let to: crate::lossy::Paragraph = foo.to_paragraph();
+            assert_eq!(to.to_string(), "Name: foo\n");
+            foo.update_paragraph(&mut para);
+            assert_eq!(para.to_string(), "Name: foo\n");
+        }
+
+        #[test]
+        fn test_rest() {
+            #[derive(FromDeb822, ToDeb822, Debug, PartialEq)]
+            struct Foo {
+                #[deb822(field = "Name")]
+                name: String,
+                #[deb822(field = "Version")]
+                version: Option<String>,
+                #[deb822(rest)]
+                rest: Vec<(String, String)>,
+            }
+
+            let mut para: crate::lossless::Paragraph =
+                "Name: foo\nX-Custom: bar\nversion: 1.0\nOther: blah\n"
+                    .parse()
+                    .unwrap();
+            let mut foo = Foo::from_paragraph(&para).unwrap();
+            assert_eq!(
+                foo.rest,
+                vec![
+                    ("X-Custom".to_string(), "bar".to_string()),
+                    ("Other".to_string(), "blah".to_string()),
+                ]
+            );
+
+            let to: crate::lossy::Paragraph = foo.to_paragraph();
+            assert_eq!(
+                to.to_string(),
+                "Name: foo\nVersion: 1.0\nX-Custom: bar\nOther: blah\n"
+            );
+
+            foo.rest.retain(|(k, _)| k != "Other");
+            foo.rest.push(("X-New".to_string(), "new".to_string()));
+            foo.update_paragraph(&mut para);
+            assert_eq!(
+                para.to_string(),
+                "Name: foo\nX-Custom: bar\nversion: 1.0\nX-New: new\n"
+            );
+        }
+
+        #[test]
+        fn test_errors() {
+            #[derive(FromDeb822, Debug)]
+            struct Foo {
+                #[deb822(field = "Name")]
+                name: String,
+                #[deb822(field = "Count")]
+                count: Option<u32>,
+            }
+
+            let para: crate::lossy::Paragraph = "Name: foo\nCount: 1\n".parse().unwrap();
+            let foo = Foo::from_paragraph(&para).unwrap();
+            assert_eq!((foo.name.as_str(), foo.count), ("foo", Some(1)));
+
+            let para: crate::lossy::Paragraph = "Count: 1\n".parse().unwrap();
+            let err = Foo::from_paragraph(&para).unwrap_err();
+            assert!(matches!(&err, Error::MissingField { field } if field == "Name"));
+            assert_eq!(err.to_string(), "missing field: Name");
+
+            let deb822: crate::Deb822 = "Other: x\n\nName: foo\nCount: x\n".parse().unwrap();
+            let para = deb822.paragraphs().nth(1).unwrap();
+            let err = Foo::from_paragraph(&para).unwrap_err();
+            match &err {
+                Error::InvalidValue { field, source, .. } => {
+                    assert_eq!(field, "Count");
+                    assert!(source.is::<std::num::ParseIntError>());
+                }
+                _ => panic!("unexpected error: {:?}", err),
+            }
+            assert_eq!(err.field(), Some("Count"));
+            assert_eq!(err.range(), Some(TextRange::new(27.into(), 28.into())));
+            assert_eq!(
+                err.to_string(),
+                "parsing field Count: invalid digit found in string"
+            );
+
+            // Lossy paragraphs do not keep track of positions.
+            let para: crate::lossy::Paragraph = "Name: foo\nCount: x\n".parse().unwrap();
+            assert_eq!(Foo::from_paragraph(&para).unwrap_err().range(), None);
+        }
+
+        #[test]
+        fn test_deny_unknown_fields() {
+            #[derive(FromDeb822, Debug)]
+            #[deb822(deny_unknown_fields)]
+            struct Foo {
+                #[deb822(field = "Name")]
+                name: String,
+            }
+
+            #[derive(FromDeb822, Debug)]
+            enum Stanza {
+                #[deb822(tag_field = "Package", deny_unknown_fields)]
+                Binary {
+                    #[deb822(field = "Architecture")]
+                    architecture: String,
+                },
+            }
+
+            let para: crate::lossless::Paragraph = "name: foo\n".parse().unwrap();
+            assert_eq!(Foo::from_paragraph(&para).unwrap().name, "foo");
+
+            let para: crate::lossless::Paragraph = "Name: foo\nX-Other: bar\n".parse().unwrap();
+            let err = Foo::from_paragraph(&para).unwrap_err();
+            assert!(matches!(&err, Error::UnknownField { field, .. } if field == "X-Other"));
+            assert_eq!(err.range(), Some(TextRange::new(19.into(), 22.into())));
+
+            let para: crate::lossy::Paragraph =
+                "Package: foo\nArchitecture: all\n".parse().unwrap();
+            assert!(matches!(
+                Stanza::from_paragraph(&para),
+                Ok(Stanza::Binary { architecture }) if architecture == "all"
+            ));
+            let para: crate::lossy::Paragraph = "Package: foo\nArchitecture: all\nOther: x\n"
+                .parse()
+                .unwrap();
+            assert_eq!(
+                Stanza::from_paragraph(&para).unwrap_err().to_string(),
+                "unknown field: Other"
+            );
+        }
     }
 }
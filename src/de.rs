@@ -0,0 +1,576 @@
+//! Deserialize deb822 text into Rust types with serde.
+//!
+//! A struct or map is read from a single paragraph, a sequence from all paragraphs in the input.
+//! Field values are strings; they are parsed as numbers or booleans ("yes" or "no") as required by
+//! the target type. Sequences in a field are comma-separated by default; see [`crate::lists`] for
+//! other encodings.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, Debug, PartialEq)]
+//! struct Source {
+//!     #[serde(rename = "Source")]
+//!     name: String,
+//!     #[serde(rename = "Build-Depends")]
+//!     build_depends: Vec<String>,
+//!     #[serde(rename = "Homepage")]
+//!     homepage: Option<String>,
+//! }
+//!
+//! let source: Source = deb822_lossless::from_str("Source: foo\nBuild-Depends: bar, baz\n").unwrap();
+//! assert_eq!(source, Source {
+//!     name: "foo".to_string(),
+//!     build_depends: vec!["bar".to_string(), "baz".to_string()],
+//!     homepage: None,
+//! });
+//! ```
+use crate::lossy::{Field, Paragraph};
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+
+/// Error deserializing deb822 text.
+#[derive(Debug)]
+pub enum Error {
+    /// The input is not valid deb822.
+    Parse(crate::lossy::Error),
+
+    /// A single paragraph was expected, but the input contains several.
+    MultipleParagraphs(usize),
+
+    /// The value of a field could not be deserialized.
+    InvalidValue {
+        /// The name of the field.
+        field: String,
+
+        /// Description of the error.
+        message: String,
+    },
+
+    /// Any other error, e.g. a missing field.
+    Message(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "parse error: {}", e),
+            Error::MultipleParagraphs(n) => {
+                write!(f, "expected a single paragraph, found {}", n)
+            }
+            Error::InvalidValue { field, message } => {
+                write!(f, "invalid value for field {}: {}", field, message)
+            }
+            Error::Message(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+/// Deserialize an instance of `T` from deb822 text.
+pub fn from_str<T: de::DeserializeOwned>(s: &str) -> Result<T, Error> {
+    T::deserialize(Deserializer::from_str(s)?)
+}
+
+/// Deserialize an instance of `T` from a paragraph.
+pub fn from_paragraph<T: de::DeserializeOwned>(paragraph: &Paragraph) -> Result<T, Error> {
+    T::deserialize(ParagraphDeserializer(paragraph))
+}
+
+/// A deserializer for deb822 text.
+pub struct Deserializer {
+    paragraphs: Vec<Paragraph>,
+}
+
+impl Deserializer {
+    /// Parse the text, for deserializing it later.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, Error> {
+        let deb822: crate::lossy::Deb822 = s.parse().map_err(Error::Parse)?;
+        Ok(Self {
+            paragraphs: deb822.into(),
+        })
+    }
+
+    fn single_paragraph(&self) -> Result<Paragraph, Error> {
+        match self.paragraphs.as_slice() {
+            [] => Ok(Paragraph { fields: vec![] }),
+            [paragraph] => Ok(paragraph.clone()),
+            paragraphs => Err(Error::MultipleParagraphs(paragraphs.len())),
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.paragraphs.len() == 1 {
+            self.deserialize_map(visitor)
+        } else {
+            self.deserialize_seq(visitor)
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.paragraphs.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Paragraphs(self.paragraphs.iter()))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        ParagraphDeserializer(&self.single_paragraph()?).deserialize_map(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        ParagraphDeserializer(&self.single_paragraph()?).deserialize_struct(name, fields, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple tuple_struct enum identifier ignored_any
+    }
+}
+
+/// Access to the paragraphs of a document, as a sequence.
+struct Paragraphs<'a>(std::slice::Iter<'a, Paragraph>);
+
+impl<'de> SeqAccess<'de> for Paragraphs<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0
+            .next()
+            .map(|p| seed.deserialize(ParagraphDeserializer(p)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+/// Deserializer for a single paragraph, as a map from field names to values.
+struct ParagraphDeserializer<'a>(&'a Paragraph);
+
+impl<'de> de::Deserializer<'de> for ParagraphDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(Fields {
+            fields: self.0.fields.iter(),
+            known: &[],
+            value: None,
+        })
+    }
+
+    /// Field names are matched case-insensitively against the names of the struct fields.
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_map(Fields {
+            fields: self.0.fields.iter(),
+            known: fields,
+            value: None,
+        })
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Access to the fields of a paragraph, as a map.
+struct Fields<'a> {
+    fields: std::slice::Iter<'a, Field>,
+    /// The expected field names, with their canonical spelling.
+    known: &'static [&'static str],
+    value: Option<&'a Field>,
+}
+
+impl<'de> MapAccess<'de> for Fields<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.fields.next() {
+            Some(field) => {
+                self.value = Some(field);
+                let name = self
+                    .known
+                    .iter()
+                    .find(|known| crate::common::key_eq(known, &field.name))
+                    .copied()
+                    .unwrap_or(field.name.as_str());
+                seed.deserialize(name.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let field = self
+            .value
+            .take()
+            .ok_or_else(|| Error::Message("value requested before key".to_string()))?;
+        seed.deserialize(ValueDeserializer(&field.value))
+            .map_err(|e| match e {
+                Error::Message(message) => Error::InvalidValue {
+                    field: field.name.clone(),
+                    message,
+                },
+                e => e,
+            })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+}
+
+/// Deserializer for a field value.
+pub(crate) struct ValueDeserializer<'a>(pub(crate) &'a str);
+
+impl ValueDeserializer<'_> {
+    fn parse<T: std::str::FromStr>(&self, expected: &str) -> Result<T, Error> {
+        self.0
+            .trim()
+            .parse()
+            .map_err(|_| Error::Message(format!("expected {}, got {:?}", expected, self.0)))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident $ty:literal,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.parse($ty)?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.trim() {
+            "yes" | "true" => visitor.visit_bool(true),
+            "no" | "false" => visitor.visit_bool(false),
+            _ => Err(Error::Message(format!(
+                "expected \"yes\" or \"no\", got {:?}",
+                self.0
+            ))),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8 "an integer",
+        deserialize_i16 => visit_i16 "an integer",
+        deserialize_i32 => visit_i32 "an integer",
+        deserialize_i64 => visit_i64 "an integer",
+        deserialize_i128 => visit_i128 "an integer",
+        deserialize_u8 => visit_u8 "an integer",
+        deserialize_u16 => visit_u16 "an integer",
+        deserialize_u32 => visit_u32 "an integer",
+        deserialize_u64 => visit_u64 "an integer",
+        deserialize_u128 => visit_u128 "an integer",
+        deserialize_f32 => visit_f32 "a number",
+        deserialize_f64 => visit_f64 "a number",
+        deserialize_char => visit_char "a single character",
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Sequences are comma-separated.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Items(crate::lists::split_comma(self.0).into_iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(UnitVariant(self.0.trim()))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit_struct tuple_struct map struct identifier
+    }
+}
+
+/// The items of a list in a field value.
+pub(crate) struct Items<'a, I: Iterator<Item = &'a str>>(pub(crate) I);
+
+impl<'de, 'a, I: Iterator<Item = &'a str>> SeqAccess<'de> for Items<'a, I> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0
+            .next()
+            .map(|item| seed.deserialize(ValueDeserializer(item)))
+            .transpose()
+    }
+}
+
+/// A unit enum variant, identified by the field value.
+struct UnitVariant<'a>(&'a str);
+
+impl<'de, 'a> EnumAccess<'de> for UnitVariant<'a> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let variant = seed.deserialize(self.0.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for UnitVariant<'_> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, _seed: T) -> Result<T::Value, Error> {
+        Err(de::Error::invalid_type(
+            de::Unexpected::UnitVariant,
+            &"newtype variant",
+        ))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value, Error> {
+        Err(de::Error::invalid_type(
+            de::Unexpected::UnitVariant,
+            &"tuple variant",
+        ))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Error> {
+        Err(de::Error::invalid_type(
+            de::Unexpected::UnitVariant,
+            &"struct variant",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum Priority {
+        #[serde(rename = "required")]
+        Required,
+        #[serde(rename = "optional")]
+        Optional,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    struct Package {
+        package: String,
+        version: Option<String>,
+        priority: Option<Priority>,
+        essential: Option<bool>,
+        #[serde(rename = "Installed-Size")]
+        installed_size: Option<u64>,
+        #[serde(default)]
+        depends: Vec<String>,
+        #[serde(rename = "Multi-Arch")]
+        multi_arch: Option<String>,
+        description: Option<String>,
+    }
+
+    const PACKAGES: &str = r#"Package: base-files
+Essential: yes
+Priority: required
+Installed-Size: 391
+Version: 13
+Description: Debian base system miscellaneous files
+ This package contains the basic filesystem hierarchy.
+
+Package: hello
+Depends: libc6 (>= 2.34),  libfoo
+Multi-Arch: foreign
+"#;
+
+    #[test]
+    fn test_seq() {
+        let packages: Vec<Package> = from_str(PACKAGES).unwrap();
+        assert_eq!(
+            packages,
+            vec![
+                Package {
+                    package: "base-files".to_string(),
+                    version: Some("13".to_string()),
+                    priority: Some(Priority::Required),
+                    essential: Some(true),
+                    installed_size: Some(391),
+                    depends: vec![],
+                    multi_arch: None,
+                    description: Some(
+                        "Debian base system miscellaneous files\n\
+                         This package contains the basic filesystem hierarchy."
+                            .to_string()
+                    ),
+                },
+                Package {
+                    package: "hello".to_string(),
+                    version: None,
+                    priority: None,
+                    essential: None,
+                    installed_size: None,
+                    depends: vec!["libc6 (>= 2.34)".to_string(), "libfoo".to_string()],
+                    multi_arch: Some("foreign".to_string()),
+                    description: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single() {
+        let package: Package = from_str("Package: foo\nVersion: 1.0\n").unwrap();
+        assert_eq!(package.package, "foo");
+        assert_eq!(package.version.as_deref(), Some("1.0"));
+
+        assert!(matches!(
+            from_str::<Package>(PACKAGES),
+            Err(Error::MultipleParagraphs(2))
+        ));
+        assert!(matches!(from_str::<Option<Package>>(""), Ok(None)));
+        assert!(matches!(
+            from_str::<Package>("Version: 1.0\n"),
+            Err(Error::Message(m)) if m == "missing field `Package`"
+        ));
+        assert!(matches!(
+            from_str::<Package>("Package: foo\n: bar\n"),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_field_case() {
+        let package: Package = from_str("package: foo\nmulti-arch: same\n").unwrap();
+        assert_eq!(package.package, "foo");
+        assert_eq!(package.multi_arch.as_deref(), Some("same"));
+    }
+
+    #[test]
+    fn test_invalid_value() {
+        let err = from_str::<Package>("Package: foo\nEssential: maybe\n").unwrap_err();
+        assert!(matches!(err, Error::InvalidValue { ref field, .. } if field == "Essential"));
+        assert_eq!(
+            err.to_string(),
+            "invalid value for field Essential: expected \"yes\" or \"no\", got \"maybe\""
+        );
+        assert!(matches!(
+            from_str::<Package>("Package: foo\nPriority: extra\n"),
+            Err(Error::InvalidValue { field, .. }) if field == "Priority"
+        ));
+    }
+
+    #[test]
+    fn test_map() {
+        let map: std::collections::BTreeMap<String, String> =
+            from_str("Package: foo\nVersion: 1.0\n").unwrap();
+        assert_eq!(map.get("Version").map(|s| s.as_str()), Some("1.0"));
+
+        let paragraph: Paragraph = "Package: foo\nVersion: 1.0\n".parse().unwrap();
+        let map: std::collections::HashMap<String, String> = from_paragraph(&paragraph).unwrap();
+        assert_eq!(map.len(), 2);
+    }
+}
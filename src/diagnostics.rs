@@ -0,0 +1,348 @@
+//! Human-readable rendering of errors against the source text.
+//!
+//! Diagnostics are rendered in the style of compiler errors, showing the offending line with a
+//! caret underline and an optional hint:
+//!
+//! ```text
+//! error: expected ':', got None
+//!  --> debian/control:2:4
+//!   |
+//! 2 | Bar
+//!   |    ^
+//!   = hint: field names must be followed by ':'; continuation lines must start with a space or tab
+//! ```
+//!
+//! Crates building on this one can create [`Diagnostic`]s for their own semantic errors, e.g.
+//! using the range returned by [`crate::lossless::Entry::text_range`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use deb822_lossless::{Deb822, diagnostics::render_parse_errors};
+//!
+//! let text = "Source: foo\nBar\n";
+//! let (_deb822, errors) = Deb822::from_str_relaxed(text);
+//! let rendered = render_parse_errors(text, Some("debian/control"), &errors);
+//! assert!(rendered.contains(" --> debian/control:2:4\n"));
+//! ```
+use crate::lossless::{Deb822, ParseErrorKind, PositionedParseError};
+use rowan::TextRange;
+
+/// The severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// An error.
+    Error,
+
+    /// A warning.
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// A message attached to a range of the source text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    /// The severity of the diagnostic.
+    pub severity: Severity,
+
+    /// The message to display.
+    pub message: String,
+
+    /// The byte range in the source text that the diagnostic applies to.
+    pub range: TextRange,
+
+    /// An optional hint on how to fix the problem.
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    /// Create a new error diagnostic.
+    pub fn error(message: impl Into<String>, range: TextRange) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            range,
+            hint: None,
+        }
+    }
+
+    /// Create a new warning diagnostic.
+    pub fn warning(message: impl Into<String>, range: TextRange) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            range,
+            hint: None,
+        }
+    }
+
+    /// Attach a hint to this diagnostic.
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Render this diagnostic against the source text it applies to.
+    ///
+    /// # Arguments
+    /// * `source` - The text that the diagnostic's range refers to
+    /// * `path` - The name of the file to mention in the location line, if any
+    ///
+    /// A range that extends past the end of `source` or into the middle of a character is
+    /// clamped to it.
+    pub fn render(&self, source: &str, path: Option<&str>) -> String {
+        let offset = clamp_offset(source, usize::from(self.range.start()));
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..]
+            .find(['\n', '\r'])
+            .map_or(source.len(), |i| offset + i);
+        let line = &source[line_start..line_end];
+        let line_no = source[..line_start].matches('\n').count() + 1;
+        let column = source[line_start..offset].chars().count() + 1;
+
+        // Underline up to the end of the range, or the end of the line if the range spans
+        // multiple lines. Always show at least one caret.
+        let end = std::cmp::min(clamp_offset(source, usize::from(self.range.end())), line_end);
+        let carets = std::cmp::max(
+            source[offset..std::cmp::max(offset, end)].chars().count(),
+            1,
+        );
+        // Keep tabs in the padding so that the carets line up with the text above them.
+        let padding = source[line_start..offset]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        let gutter = " ".repeat(line_no.to_string().len());
+        let mut ret = format!("{}: {}\n", self.severity, self.message);
+        match path {
+            Some(path) => {
+                ret.push_str(&format!("{}--> {}:{}:{}\n", gutter, path, line_no, column))
+            }
+            None => ret.push_str(&format!("{}--> {}:{}\n", gutter, line_no, column)),
+        }
+        ret.push_str(&format!("{} |\n", gutter));
+        ret.push_str(&format!("{} | {}\n", line_no, line));
+        ret.push_str(&format!("{} | {}{}\n", gutter, padding, "^".repeat(carets)));
+        if let Some(hint) = &self.hint {
+            ret.push_str(&format!("{} = hint: {}\n", gutter, hint));
+        }
+        ret
+    }
+}
+
+/// Clamp `offset` to the length of `source`, moving it back to the start of the character it
+/// falls in.
+fn clamp_offset(source: &str, offset: usize) -> usize {
+    let mut offset = std::cmp::min(offset, source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+impl From<&PositionedParseError> for Diagnostic {
+    fn from(err: &PositionedParseError) -> Self {
+        let diagnostic = Diagnostic::error(err.message.clone(), err.range);
+        match hint_for(err.kind) {
+            Some(hint) => diagnostic.with_hint(hint),
+            None => diagnostic,
+        }
+    }
+}
+
+impl From<PositionedParseError> for Diagnostic {
+    fn from(err: PositionedParseError) -> Self {
+        Diagnostic::from(&err)
+    }
+}
+
+fn hint_for(kind: ParseErrorKind) -> Option<&'static str> {
+    match kind {
+        ParseErrorKind::MissingKey => Some("each field must start with a field name followed by ':'"),
+        ParseErrorKind::MissingColon => Some(
+            "field names must be followed by ':'; continuation lines must start with a space or tab",
+        ),
+        ParseErrorKind::StrayContinuationLine => {
+            Some("continuation lines must directly follow the field they belong to")
+        }
+        ParseErrorKind::ExpectedNewline => None,
+        ParseErrorKind::NoParagraphs => Some("the input must contain at least one paragraph"),
+        ParseErrorKind::MultipleParagraphs => Some("the input must contain a single paragraph"),
+        ParseErrorKind::UnexpectedComment => Some("this format does not allow comments"),
+        ParseErrorKind::CarriageReturn => Some("lines must end in a plain newline, not CRLF"),
+        ParseErrorKind::DuplicateField => Some("each field may appear only once per paragraph"),
+        ParseErrorKind::LeadingBlankLine | ParseErrorKind::TrailingBlankLine => {
+            Some("remove the blank lines")
+        }
+    }
+}
+
+/// Returns a warning for every repeated occurrence of a field within a paragraph.
+///
+/// Each warning points at the name of the repeated field.
+pub fn duplicate_fields(deb822: &Deb822) -> Vec<Diagnostic> {
+    deb822
+        .paragraphs()
+        .flat_map(|p| p.duplicate_fields())
+        .flat_map(|field| {
+            let name = field.name;
+            field.entries.into_iter().skip(1).map(move |entry| {
+                let range = entry.key_range().unwrap_or_else(|| entry.text_range());
+                Diagnostic::warning(format!("duplicate field {}", name), range)
+                    .with_hint("each field may appear only once per paragraph")
+            })
+        })
+        .collect()
+}
+
+/// Render a list of diagnostics, separated by empty lines.
+pub fn render(source: &str, path: Option<&str>, diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| d.render(source, path))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render the errors returned by [`crate::Deb822::from_str_relaxed`] and friends.
+pub fn render_parse_errors(
+    source: &str,
+    path: Option<&str>,
+    errors: &[PositionedParseError],
+) -> String {
+    render(
+        source,
+        path,
+        &errors.iter().map(Diagnostic::from).collect::<Vec<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rowan::TextSize;
+
+    #[test]
+    fn test_render_missing_colon() {
+        let text = "Source: foo\nBar\n";
+        let (_, errors) = crate::Deb822::from_str_relaxed(text);
+        assert_eq!(
+            render_parse_errors(text, Some("debian/control"), &errors),
+            r#"error: expected ':', got None
+ --> debian/control:2:4
+  |
+2 | Bar
+  |    ^
+  = hint: field names must be followed by ':'; continuation lines must start with a space or tab
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_range() {
+        let text = "Source: foo\nSection: libz\n";
+        let diagnostic = Diagnostic::warning(
+            "unknown section",
+            TextRange::new(TextSize::from(21), TextSize::from(25)),
+        );
+        assert_eq!(
+            diagnostic.render(text, None),
+            r#"warning: unknown section
+ --> 2:10
+  |
+2 | Section: libz
+  |          ^^^^
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_multiline_range() {
+        let text = "Depends: foo,\n bar\n";
+        let diagnostic =
+            Diagnostic::error("bad", TextRange::new(TextSize::from(9), TextSize::from(18)))
+                .with_hint("fix it");
+        assert_eq!(
+            diagnostic.render(text, None),
+            r#"error: bad
+ --> 1:10
+  |
+1 | Depends: foo,
+  |          ^^^^
+  = hint: fix it
+"#
+        );
+    }
+
+    #[test]
+    fn test_render_tabs() {
+        let text = "A: b\n\tc: d\n";
+        let diagnostic =
+            Diagnostic::error("bad", TextRange::new(TextSize::from(6), TextSize::from(7)));
+        assert_eq!(
+            diagnostic.render(text, None),
+            "error: bad\n --> 2:2\n  |\n2 | \tc: d\n  | \t^\n"
+        );
+    }
+
+    #[test]
+    fn test_render_end_of_input() {
+        let text = "\n";
+        let err = text.parse::<crate::Paragraph>().unwrap_err();
+        assert_eq!(
+            render_parse_errors(text, None, err.errors()),
+            "error: no paragraphs\n --> 2:1\n  |\n2 | \n  | ^\n  = hint: the input must contain at least one paragraph\n"
+        );
+    }
+
+    #[test]
+    fn test_render_invalid_range() {
+        let text = "Name: f\u{f6}o\n";
+        // Past the end of the source
+        let diagnostic = Diagnostic::error("oops", TextRange::new(20.into(), 30.into()));
+        assert_eq!(
+            diagnostic.render(text, None),
+            "error: oops\n --> 2:1\n  |\n2 | \n  | ^\n"
+        );
+        // In the middle of a character
+        let diagnostic = Diagnostic::error("oops", TextRange::new(8.into(), 9.into()));
+        assert_eq!(
+            diagnostic.render(text, None),
+            "error: oops\n --> 1:8\n  |\n1 | Name: f\u{f6}o\n  |        ^\n"
+        );
+    }
+
+    #[test]
+    fn test_render_multiple() {
+        let text = "A: b\nc\n\nd\n";
+        let (_, errors) = crate::Deb822::from_str_relaxed(text);
+        let rendered = render_parse_errors(text, None, &errors);
+        assert_eq!(rendered.matches("error: ").count(), errors.len());
+        assert!(rendered.contains("\n\nerror: "));
+    }
+
+    #[test]
+    fn test_duplicate_fields() {
+        let text = "Package: foo\nDepends: bar\nSection: libs\ndepends: blah\n\nPackage: bar\n";
+        let deb822: Deb822 = text.parse().unwrap();
+        assert_eq!(
+            render(text, None, &duplicate_fields(&deb822)),
+            r#"warning: duplicate field Depends
+ --> 4:1
+  |
+4 | depends: blah
+  | ^^^^^^^
+  = hint: each field may appear only once per paragraph
+"#
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Options controlling how strictly deb822 text is parsed.
+//!
+//! Many formats are close relatives of deb822 but differ in the details: dpkg status files
+//! do not allow comments, APT's `.sources` files allow comments in the middle of a field,
+//! RFC822 headers use CRLF line endings and R `DESCRIPTION` files have no notion of comments
+//! at all. [`ParseOptions`] describes these differences, and [`Dialect`] provides the options
+//! for the common formats.
+//!
+//! The default options are lenient, and match the behaviour of the plain parsing functions.
+//!
+//! # Example
+//!
+//! ```rust
+//! use deb822_lossless::dialect::Dialect;
+//! use deb822_lossless::Deb822;
+//!
+//! let text = "Package: foo\n# Installed by hand\nStatus: install ok installed\n";
+//! assert!(Deb822::from_str_with_options(text, &Dialect::Deb822.options()).is_ok());
+//! assert!(Deb822::from_str_with_options(text, &Dialect::DpkgStatus.options()).is_err());
+//! ```
+use crate::common;
+
+/// How lines starting with `#` are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Comments {
+    /// `#` has no special meaning, and is parsed as part of a field name or value.
+    None,
+
+    /// Comment lines are recognized, but are reported as errors.
+    Forbidden,
+
+    /// Comment lines are allowed between fields and paragraphs.
+    #[default]
+    StartOfLine,
+
+    /// Comment lines are also allowed between the continuation lines of a field.
+    Anywhere,
+}
+
+/// How fields that appear more than once in a paragraph are treated.
+///
+/// The lossless parser keeps every field while parsing, so that the tree matches the input;
+/// with [`DuplicateFields::KeepFirst`], [`DuplicateFields::KeepLast`] and
+/// [`DuplicateFields::MergeAsList`] the duplicates are collapsed afterwards, as with
+/// [`crate::lossless::Paragraph::collapse_duplicates`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DuplicateFields {
+    /// Duplicate fields are kept.
+    #[default]
+    Allow,
+
+    /// Duplicate fields are reported as errors.
+    Error,
+
+    /// Only the first occurrence of a field is kept.
+    KeepFirst,
+
+    /// Only the last occurrence of a field is kept.
+    KeepLast,
+
+    /// The values of all occurrences are joined into a comma-separated list, in place of the
+    /// first occurrence.
+    MergeAsList,
+}
+
+/// Join two field values into a comma-separated list.
+pub(crate) fn merge_values(a: &str, b: &str) -> String {
+    let a = a.trim_end().trim_end_matches(',').trim_end();
+    let b = b.trim();
+    if a.is_empty() {
+        b.to_string()
+    } else if b.is_empty() {
+        a.to_string()
+    } else {
+        format!("{}, {}", a, b)
+    }
+}
+
+/// Options for parsing deb822 text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    /// How comment lines are treated.
+    pub comments: Comments,
+
+    /// Whether lines may end in `"\r\n"` (or a bare `"\r"`) rather than `"\n"`.
+    pub allow_crlf: bool,
+
+    /// How duplicate fields are treated.
+    pub duplicate_fields: DuplicateFields,
+
+    /// Whether the text may start with blank lines.
+    pub allow_leading_blank_lines: bool,
+
+    /// Whether the text may end with blank lines.
+    pub allow_trailing_blank_lines: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            comments: Comments::default(),
+            allow_crlf: true,
+            duplicate_fields: DuplicateFields::default(),
+            allow_leading_blank_lines: true,
+            allow_trailing_blank_lines: true,
+        }
+    }
+}
+
+/// A format based on deb822.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// Strict deb822, as described in deb822(5).
+    Deb822,
+
+    /// dpkg's status and available files.
+    DpkgStatus,
+
+    /// APT's deb822-style `.sources` files.
+    Apt,
+
+    /// RFC822 email headers.
+    Rfc822,
+
+    /// R package `DESCRIPTION` files.
+    RDescription,
+}
+
+impl Dialect {
+    /// Returns the parse options for this dialect.
+    pub fn options(self) -> ParseOptions {
+        match self {
+            Dialect::Deb822 => ParseOptions {
+                comments: Comments::StartOfLine,
+                allow_crlf: false,
+                duplicate_fields: DuplicateFields::Error,
+                allow_leading_blank_lines: false,
+                allow_trailing_blank_lines: false,
+            },
+            Dialect::DpkgStatus => ParseOptions {
+                comments: Comments::Forbidden,
+                allow_crlf: false,
+                duplicate_fields: DuplicateFields::Error,
+                allow_leading_blank_lines: true,
+                allow_trailing_blank_lines: true,
+            },
+            Dialect::Apt => ParseOptions {
+                comments: Comments::Anywhere,
+                ..ParseOptions::default()
+            },
+            Dialect::Rfc822 => ParseOptions {
+                comments: Comments::None,
+                ..ParseOptions::default()
+            },
+            Dialect::RDescription => ParseOptions {
+                comments: Comments::None,
+                duplicate_fields: DuplicateFields::Error,
+                ..ParseOptions::default()
+            },
+        }
+    }
+}
+
+impl From<Dialect> for ParseOptions {
+    fn from(dialect: Dialect) -> Self {
+        dialect.options()
+    }
+}
+
+/// Returns the byte ranges of the blank lines at the start and at the end of `text`.
+///
+/// If the text consists only of blank lines, they are all considered to be leading.
+pub(crate) fn blank_lines(
+    text: &str,
+) -> (
+    Option<std::ops::Range<usize>>,
+    Option<std::ops::Range<usize>>,
+) {
+    let is_blank = |c: char| common::is_indent(c) || common::is_newline(c);
+    let content_start = text.len() - text.trim_start_matches(is_blank).len();
+    // Only whole lines are blank; indentation of the first line with content is not.
+    let leading_end = text[..content_start]
+        .rfind(common::is_newline)
+        .map_or(0, |i| i + 1);
+    let leading = (leading_end > 0).then_some(0..leading_end);
+    if content_start == text.len() {
+        return (leading, None);
+    }
+
+    // Skip the rest of the last line with content, including its line ending.
+    let content_end = text.trim_end_matches(is_blank).len();
+    let rest = &text[content_end..];
+    let mut trailing_start =
+        content_end + (rest.len() - rest.trim_start_matches(common::is_indent).len());
+    if text[trailing_start..].starts_with("\r\n") {
+        trailing_start += 2;
+    } else if text[trailing_start..].starts_with(common::is_newline) {
+        trailing_start += 1;
+    }
+    let trailing = (trailing_start < text.len()).then_some(trailing_start..text.len());
+    (leading, trailing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_lines() {
+        assert_eq!(blank_lines("A: b\n"), (None, None));
+        assert_eq!(blank_lines("A: b"), (None, None));
+        assert_eq!(blank_lines("A: b  \r\n"), (None, None));
+        assert_eq!(blank_lines("\n \nA: b\n\n\t\n"), (Some(0..3), Some(8..11)));
+        assert_eq!(blank_lines("\n\n"), (Some(0..2), None));
+        assert_eq!(blank_lines(""), (None, None));
+    }
+
+    #[test]
+    fn test_merge_values() {
+        assert_eq!(merge_values("foo", "bar"), "foo, bar");
+        assert_eq!(merge_values("foo,\nbar, ", " blah"), "foo,\nbar, blah");
+        assert_eq!(merge_values("", "bar"), "bar");
+        assert_eq!(merge_values("foo", ""), "foo");
+    }
+
+    #[test]
+    fn test_options() {
+        assert_eq!(Dialect::Apt.options().comments, Comments::Anywhere);
+        assert_eq!(ParseOptions::from(Dialect::Rfc822).comments, Comments::None);
+        assert!(!Dialect::DpkgStatus.options().allow_crlf);
+    }
+}
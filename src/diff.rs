@@ -0,0 +1,464 @@
+//! Semantic differences between two deb822 documents.
+//!
+//! Paragraphs are matched up using a key extracted from each paragraph, typically the value of
+//! the `Package` or `Source` field. Within matched paragraphs, fields are compared by name
+//! (case-insensitively) and value, ignoring differences that only affect whitespace or the way
+//! a value is wrapped over multiple lines.
+//!
+//! # Example
+//!
+//! ```rust
+//! use deb822_lossless::diff::{render_unified, FieldChange, ParagraphChange};
+//! use deb822_lossless::Deb822;
+//!
+//! let old: Deb822 = "Source: foo\n\nPackage: foo\nDepends: bar,\n baz\n".parse().unwrap();
+//! let new: Deb822 = "Source: foo\n\nPackage: foo\nDepends: bar, baz, quux\n".parse().unwrap();
+//! let changes = old.diff(&new, |p| p.get("Package").or_else(|| p.get("Source")));
+//! assert_eq!(
+//!     changes,
+//!     vec![ParagraphChange::Modified {
+//!         key: Some("foo".to_string()),
+//!         old_index: 1,
+//!         new_index: 1,
+//!         changes: vec![FieldChange::Changed {
+//!             field: "Depends".to_string(),
+//!             old: "bar,\nbaz".to_string(),
+//!             new: "bar, baz, quux".to_string(),
+//!         }],
+//!     }]
+//! );
+//! assert_eq!(
+//!     render_unified(&changes, "a/debian/control", "b/debian/control"),
+//!     r#"--- a/debian/control
+//! +++ b/debian/control
+//! @@ foo @@
+//! -Depends: bar,
+//! - baz
+//! +Depends: bar, baz, quux
+//! "#
+//! );
+//! ```
+use crate::common::key_eq;
+use crate::lossless::{Deb822, Paragraph};
+use crate::multiline::MultiLineValue;
+use std::collections::{HashMap, VecDeque};
+
+/// A change to a single field within a paragraph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldChange {
+    /// The field only exists in the new paragraph.
+    Added {
+        /// Name of the field.
+        field: String,
+
+        /// Value of the field in the new paragraph.
+        value: String,
+    },
+
+    /// The field only exists in the old paragraph.
+    Removed {
+        /// Name of the field.
+        field: String,
+
+        /// Value of the field in the old paragraph.
+        value: String,
+    },
+
+    /// The field exists in both paragraphs, with different values.
+    Changed {
+        /// Name of the field, as it appears in the new paragraph.
+        field: String,
+
+        /// Value of the field in the old paragraph.
+        old: String,
+
+        /// Value of the field in the new paragraph.
+        new: String,
+    },
+}
+
+impl FieldChange {
+    /// Returns the name of the field that changed.
+    pub fn field(&self) -> &str {
+        match self {
+            FieldChange::Added { field, .. }
+            | FieldChange::Removed { field, .. }
+            | FieldChange::Changed { field, .. } => field,
+        }
+    }
+}
+
+/// A change to a paragraph between two documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParagraphChange {
+    /// The paragraph only exists in the new document.
+    Added {
+        /// Key of the paragraph.
+        key: Option<String>,
+
+        /// Index of the paragraph in the new document.
+        index: usize,
+
+        /// The fields of the paragraph.
+        fields: Vec<(String, String)>,
+    },
+
+    /// The paragraph only exists in the old document.
+    Removed {
+        /// Key of the paragraph.
+        key: Option<String>,
+
+        /// Index of the paragraph in the old document.
+        index: usize,
+
+        /// The fields of the paragraph.
+        fields: Vec<(String, String)>,
+    },
+
+    /// The paragraph exists in both documents, but its fields differ.
+    Modified {
+        /// Key of the paragraph.
+        key: Option<String>,
+
+        /// Index of the paragraph in the old document.
+        old_index: usize,
+
+        /// Index of the paragraph in the new document.
+        new_index: usize,
+
+        /// The changes to the fields of the paragraph.
+        changes: Vec<FieldChange>,
+    },
+}
+
+impl ParagraphChange {
+    /// Returns the key of the paragraph that changed.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            ParagraphChange::Added { key, .. }
+            | ParagraphChange::Removed { key, .. }
+            | ParagraphChange::Modified { key, .. } => key.as_deref(),
+        }
+    }
+}
+
+/// Returns the fields of a paragraph, with their values as returned by
+/// [`MultiLineValue::to_value`].
+fn fields(paragraph: &Paragraph) -> Vec<(String, String)> {
+    paragraph
+        .entries()
+        .filter_map(|e| Some((e.key()?, e.multiline_value().to_value())))
+        .collect()
+}
+
+/// Compare the fields of two paragraphs.
+///
+/// If a field occurs more than once in a paragraph, only its first occurrence is compared.
+pub fn diff_paragraphs(old: &Paragraph, new: &Paragraph) -> Vec<FieldChange> {
+    let old = fields(old);
+    let new = fields(new);
+    let find = |fields: &[(String, String)], name: &str| {
+        fields.iter().position(|(field, _)| key_eq(field, name))
+    };
+    let mut changes = vec![];
+    for (i, (field, value)) in old.iter().enumerate() {
+        if find(&old, field) != Some(i) {
+            continue;
+        }
+        match find(&new, field).map(|j| &new[j]) {
+            None => changes.push(FieldChange::Removed {
+                field: field.clone(),
+                value: value.clone(),
+            }),
+            Some((new_field, new_value)) if normalize(value) != normalize(new_value) => changes
+                .push(FieldChange::Changed {
+                    field: new_field.clone(),
+                    old: value.clone(),
+                    new: new_value.clone(),
+                }),
+            Some(_) => {}
+        }
+    }
+    for (i, (field, value)) in new.iter().enumerate() {
+        if find(&new, field) == Some(i) && find(&old, field).is_none() {
+            changes.push(FieldChange::Added {
+                field: field.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+    changes
+}
+
+/// Match up paragraphs by their keys.
+///
+/// Returns, for each old paragraph, the index of the matching new paragraph. Paragraphs with the
+/// same key are matched in order of appearance.
+pub(crate) fn match_paragraphs(
+    old_keys: &[Option<String>],
+    new_keys: &[Option<String>],
+) -> Vec<Option<usize>> {
+    let mut positions: HashMap<&Option<String>, VecDeque<usize>> = HashMap::new();
+    for (j, key) in new_keys.iter().enumerate() {
+        positions.entry(key).or_default().push_back(j);
+    }
+    old_keys
+        .iter()
+        .map(|key| positions.get_mut(key)?.pop_front())
+        .collect()
+}
+
+/// Normalize a value so that differences in whitespace and wrapping are ignored.
+pub(crate) fn normalize(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl Deb822 {
+    /// Compare this document to another one.
+    ///
+    /// Paragraphs are matched by the key returned by `key`. Paragraphs for which `key` returns
+    /// `None`, or whose key occurs more than once, are matched in order of appearance with the
+    /// remaining paragraphs that have the same key. Paragraphs that are identical (ignoring
+    /// whitespace and wrapping) are not included in the result.
+    ///
+    /// Removed and modified paragraphs are listed in the order of the old document, followed by
+    /// added paragraphs in the order of the new document.
+    pub fn diff(
+        &self,
+        other: &Deb822,
+        key: impl Fn(&Paragraph) -> Option<String>,
+    ) -> Vec<ParagraphChange> {
+        let old = self.paragraphs().collect::<Vec<_>>();
+        let new = other.paragraphs().collect::<Vec<_>>();
+        let old_keys = old.iter().map(&key).collect::<Vec<_>>();
+        let new_keys = new.iter().map(&key).collect::<Vec<_>>();
+        let matches = match_paragraphs(&old_keys, &new_keys);
+        let mut matched = vec![false; new.len()];
+        let mut changes = vec![];
+        for (i, paragraph) in old.iter().enumerate() {
+            match matches[i] {
+                Some(j) => {
+                    matched[j] = true;
+                    let field_changes = diff_paragraphs(paragraph, &new[j]);
+                    if !field_changes.is_empty() {
+                        changes.push(ParagraphChange::Modified {
+                            key: old_keys[i].clone(),
+                            old_index: i,
+                            new_index: j,
+                            changes: field_changes,
+                        });
+                    }
+                }
+                None => changes.push(ParagraphChange::Removed {
+                    key: old_keys[i].clone(),
+                    index: i,
+                    fields: fields(paragraph),
+                }),
+            }
+        }
+        for (j, paragraph) in new.iter().enumerate() {
+            if !matched[j] {
+                changes.push(ParagraphChange::Added {
+                    key: new_keys[j].clone(),
+                    index: j,
+                    fields: fields(paragraph),
+                });
+            }
+        }
+        changes
+    }
+}
+
+/// Write a field, prefixing every line with `prefix`.
+fn write_field(out: &mut String, prefix: char, field: &str, value: &str) {
+    let text = format!("{}:{}", field, MultiLineValue::from_value(value));
+    for line in text.lines() {
+        out.push(prefix);
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+/// Render a list of changes as a unified-style diff.
+///
+/// Each changed paragraph gets a `@@ key @@` header, using the paragraph index if it has no key.
+/// Removed lines are prefixed with `-` and added lines with `+`. Unchanged fields are not shown.
+pub fn render_unified(changes: &[ParagraphChange], old_label: &str, new_label: &str) -> String {
+    let mut out = String::new();
+    if changes.is_empty() {
+        return out;
+    }
+    out.push_str(&format!("--- {}\n+++ {}\n", old_label, new_label));
+    for change in changes {
+        let header = match (change.key(), change) {
+            (Some(key), _) => key.to_string(),
+            (None, ParagraphChange::Added { index, .. }) => format!("paragraph {}", index),
+            (None, ParagraphChange::Removed { index, .. })
+            | (
+                None,
+                ParagraphChange::Modified {
+                    old_index: index, ..
+                },
+            ) => format!("paragraph {}", index),
+        };
+        out.push_str(&format!("@@ {} @@\n", header));
+        match change {
+            ParagraphChange::Added { fields, .. } => {
+                for (field, value) in fields {
+                    write_field(&mut out, '+', field, value);
+                }
+            }
+            ParagraphChange::Removed { fields, .. } => {
+                for (field, value) in fields {
+                    write_field(&mut out, '-', field, value);
+                }
+            }
+            ParagraphChange::Modified { changes, .. } => {
+                for change in changes {
+                    match change {
+                        FieldChange::Added { field, value } => {
+                            write_field(&mut out, '+', field, value)
+                        }
+                        FieldChange::Removed { field, value } => {
+                            write_field(&mut out, '-', field, value)
+                        }
+                        FieldChange::Changed { field, old, new } => {
+                            write_field(&mut out, '-', field, old);
+                            write_field(&mut out, '+', field, new);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package_or_source(p: &Paragraph) -> Option<String> {
+        p.get("Package").or_else(|| p.get("Source"))
+    }
+
+    #[test]
+    fn test_identical() {
+        let old: Deb822 = "Source: foo\nBuild-Depends: a,\n b\n\nPackage: foo\n"
+            .parse()
+            .unwrap();
+        let new: Deb822 = "Source: foo\nbuild-depends:  a, b\n\nPackage: foo\n"
+            .parse()
+            .unwrap();
+        assert_eq!(old.diff(&new, package_or_source), vec![]);
+        assert_eq!(render_unified(&[], "a", "b"), "");
+    }
+
+    #[test]
+    fn test_match_paragraphs() {
+        let key = |k: Option<&str>| k.map(|k| k.to_string());
+        let old = [
+            key(Some("a")),
+            key(None),
+            key(Some("b")),
+            key(Some("a")),
+            key(None),
+        ];
+        let new = [key(None), key(Some("a")), key(Some("c")), key(Some("a"))];
+        assert_eq!(
+            match_paragraphs(&old, &new),
+            vec![Some(1), Some(0), None, Some(3), None]
+        );
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let old: Deb822 = "Source: foo\n\nPackage: foo\nArchitecture: any\n\nPackage: foo-doc\n"
+            .parse()
+            .unwrap();
+        let new: Deb822 = "Source: foo\n\nPackage: foo-data\n\nPackage: foo\nArchitecture: all\nMulti-Arch: foreign\n"
+            .parse()
+            .unwrap();
+        let changes = old.diff(&new, package_or_source);
+        assert_eq!(
+            changes,
+            vec![
+                ParagraphChange::Modified {
+                    key: Some("foo".to_string()),
+                    old_index: 1,
+                    new_index: 2,
+                    changes: vec![
+                        FieldChange::Changed {
+                            field: "Architecture".to_string(),
+                            old: "any".to_string(),
+                            new: "all".to_string(),
+                        },
+                        FieldChange::Added {
+                            field: "Multi-Arch".to_string(),
+                            value: "foreign".to_string(),
+                        },
+                    ],
+                },
+                ParagraphChange::Removed {
+                    key: Some("foo-doc".to_string()),
+                    index: 2,
+                    fields: vec![("Package".to_string(), "foo-doc".to_string())],
+                },
+                ParagraphChange::Added {
+                    key: Some("foo-data".to_string()),
+                    index: 1,
+                    fields: vec![("Package".to_string(), "foo-data".to_string())],
+                },
+            ]
+        );
+        assert_eq!(
+            render_unified(&changes, "old", "new"),
+            r#"--- old
++++ new
+@@ foo @@
+-Architecture: any
++Architecture: all
++Multi-Arch: foreign
+@@ foo-doc @@
+-Package: foo-doc
+@@ foo-data @@
++Package: foo-data
+"#
+        );
+    }
+
+    #[test]
+    fn test_without_key() {
+        let old: Deb822 = "A: 1\n\nB: 2\n".parse().unwrap();
+        let new: Deb822 = "A: 1\nC: 3\n".parse().unwrap();
+        let changes = old.diff(&new, |_| None);
+        assert_eq!(
+            render_unified(&changes, "old", "new"),
+            "--- old\n+++ new\n@@ paragraph 0 @@\n+C: 3\n@@ paragraph 1 @@\n-B: 2\n"
+        );
+    }
+
+    #[test]
+    fn test_multiline() {
+        let old: Deb822 = "Package: foo\nDescription: short\n long\n .\n more\n"
+            .parse()
+            .unwrap();
+        let new: Deb822 = "Package: foo\nDescription: short\n long more\n"
+            .parse()
+            .unwrap();
+        let changes = old.diff(&new, package_or_source);
+        assert_eq!(
+            render_unified(&changes, "old", "new"),
+            r#"--- old
++++ new
+@@ foo @@
+-Description: short
+- long
+- .
+- more
++Description: short
++ long more
+"#
+        );
+    }
+}
@@ -0,0 +1,866 @@
+//! Structured edits to deb822 documents.
+//!
+//! A [`ChangeSet`] is a list of [`Operation`]s that is applied to a [`Deb822`] as a whole: if
+//! any of the operations fails, the document is left untouched. Applying a change set returns a
+//! [`Report`] describing what changed, which can be logged or turned into a change set that
+//! reverts the changes. With the `serde` feature, change sets can be serialized, for example to
+//! store them alongside the report of a bot that generated them.
+//!
+//! # Example
+//!
+//! ```rust
+//! use deb822_lossless::edit::{ChangeSet, Operation, Selector};
+//! use deb822_lossless::Deb822;
+//!
+//! let mut deb822: Deb822 = "Source: foo\n\nPackage: foo\nDepends: bar\n".parse().unwrap();
+//! let changes = ChangeSet::new(vec![
+//!     Operation::InsertRelation {
+//!         paragraph: Selector::Field {
+//!             field: "Package".to_string(),
+//!             value: "foo".to_string(),
+//!         },
+//!         field: "Depends".to_string(),
+//!         entry: "baz (>= 1.0)".to_string(),
+//!     },
+//!     Operation::RemoveField {
+//!         paragraph: Selector::All,
+//!         field: "Priority".to_string(),
+//!     },
+//! ]);
+//! let report = changes.apply(&mut deb822).unwrap();
+//! assert_eq!(deb822.to_string(), "Source: foo\n\nPackage: foo\nDepends: bar, baz (>= 1.0)\n");
+//! assert_eq!(report.to_string(), "paragraph 1: Depends: \"bar\" -> \"bar, baz (>= 1.0)\"\n");
+//!
+//! report.inverse().apply(&mut deb822).unwrap();
+//! assert_eq!(deb822.to_string(), "Source: foo\n\nPackage: foo\nDepends: bar\n");
+//! ```
+use crate::lossless::{Deb822, Paragraph};
+use crate::merge::{format_relations, relations};
+use crate::multiline::MultiLineValue;
+
+/// Selects the paragraphs an operation applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Selector {
+    /// The paragraph with the given index.
+    Index(usize),
+
+    /// All paragraphs in which a field has the given value.
+    Field {
+        /// Name of the field, compared case-insensitively.
+        field: String,
+
+        /// Value of the field.
+        value: String,
+    },
+
+    /// All paragraphs.
+    All,
+}
+
+impl Selector {
+    /// Returns the indices of the selected paragraphs.
+    fn select(&self, paragraphs: &[Paragraph]) -> Vec<usize> {
+        match self {
+            Selector::Index(index) if *index < paragraphs.len() => vec![*index],
+            Selector::Index(_) => vec![],
+            Selector::Field { field, value } => (0..paragraphs.len())
+                .filter(|&i| paragraphs[i].get(field).as_deref() == Some(value.as_str()))
+                .collect(),
+            Selector::All => (0..paragraphs.len()).collect(),
+        }
+    }
+}
+
+/// A single edit to a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "op", rename_all = "kebab-case")
+)]
+pub enum Operation {
+    /// Set the value of a field in the selected paragraphs.
+    SetField {
+        /// The paragraphs to edit.
+        paragraph: Selector,
+
+        /// Name of the field.
+        field: String,
+
+        /// The new value. Newlines start continuation lines.
+        value: String,
+
+        /// If the field does not exist yet, insert it after this field rather than at the end
+        /// of the paragraph.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        after: Option<String>,
+
+        /// If the field does not exist yet and `after` is not set or not present, insert it
+        /// before this field rather than at the end of the paragraph.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        before: Option<String>,
+    },
+
+    /// Remove a field from the selected paragraphs, if it is present.
+    RemoveField {
+        /// The paragraphs to edit.
+        paragraph: Selector,
+
+        /// Name of the field.
+        field: String,
+    },
+
+    /// Rename a field in the selected paragraphs, keeping its value.
+    ///
+    /// This fails if none of the selected paragraphs contain the field.
+    RenameField {
+        /// The paragraphs to edit.
+        paragraph: Selector,
+
+        /// Current name of the field.
+        from: String,
+
+        /// New name of the field.
+        to: String,
+    },
+
+    /// Add a paragraph.
+    AddParagraph {
+        /// Index at which to insert the paragraph, or `None` to add it at the end.
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
+        position: Option<usize>,
+
+        /// The fields of the new paragraph.
+        fields: Vec<(String, String)>,
+    },
+
+    /// Remove the selected paragraphs.
+    RemoveParagraph {
+        /// The paragraphs to remove.
+        paragraph: Selector,
+    },
+
+    /// Add an entry to a comma-separated relation field, like `Depends`, in the selected
+    /// paragraphs.
+    ///
+    /// The field is created if it does not exist. Nothing happens if the field already contains
+    /// the entry.
+    InsertRelation {
+        /// The paragraphs to edit.
+        paragraph: Selector,
+
+        /// Name of the field.
+        field: String,
+
+        /// The entry to add, e.g. `debhelper-compat (= 13)`.
+        entry: String,
+    },
+}
+
+/// Error applying a change set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The selector of the operation with the given index did not match any paragraphs.
+    NoMatchingParagraph(usize),
+
+    /// The field to rename does not exist in any of the selected paragraphs.
+    NoSuchField {
+        /// Index of the operation.
+        operation: usize,
+
+        /// Name of the field.
+        field: String,
+    },
+
+    /// The position at which to add a paragraph is past the end of the document.
+    InvalidPosition {
+        /// Index of the operation.
+        operation: usize,
+
+        /// The requested position.
+        position: usize,
+    },
+
+    /// The field name is not valid in deb822.
+    InvalidFieldName {
+        /// Index of the operation.
+        operation: usize,
+
+        /// The invalid field name.
+        field: String,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::NoMatchingParagraph(operation) => {
+                write!(f, "operation {}: no matching paragraphs", operation)
+            }
+            Error::NoSuchField { operation, field } => {
+                write!(f, "operation {}: no such field: {}", operation, field)
+            }
+            Error::InvalidPosition {
+                operation,
+                position,
+            } => write!(f, "operation {}: invalid position {}", operation, position),
+            Error::InvalidFieldName { operation, field } => {
+                write!(
+                    f,
+                    "operation {}: invalid field name: {:?}",
+                    operation, field
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A change made by applying a change set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "change", rename_all = "kebab-case")
+)]
+pub enum Change {
+    /// A field was added, removed or changed.
+    Field {
+        /// Index of the paragraph, at the time of the change.
+        paragraph: usize,
+
+        /// Name of the field.
+        field: String,
+
+        /// The old value, or `None` if the field was added.
+        old: Option<String>,
+
+        /// The new value, or `None` if the field was removed.
+        new: Option<String>,
+
+        /// The field preceding it in the paragraph, if any.
+        after: Option<String>,
+
+        /// The field following it in the paragraph, if any.
+        before: Option<String>,
+    },
+
+    /// A field was renamed.
+    Renamed {
+        /// Index of the paragraph, at the time of the change.
+        paragraph: usize,
+
+        /// The old name of the field.
+        from: String,
+
+        /// The new name of the field.
+        to: String,
+    },
+
+    /// A paragraph was added.
+    ParagraphAdded {
+        /// Index of the new paragraph.
+        paragraph: usize,
+    },
+
+    /// A paragraph was removed.
+    ParagraphRemoved {
+        /// Index of the paragraph, at the time of the change.
+        paragraph: usize,
+
+        /// The fields of the removed paragraph.
+        fields: Vec<(String, String)>,
+    },
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Change::Field {
+                paragraph,
+                field,
+                old,
+                new,
+                ..
+            } => match (old, new) {
+                (Some(old), Some(new)) => {
+                    write!(
+                        f,
+                        "paragraph {}: {}: {:?} -> {:?}",
+                        paragraph, field, old, new
+                    )
+                }
+                (None, Some(new)) => {
+                    write!(f, "paragraph {}: added {}: {:?}", paragraph, field, new)
+                }
+                (Some(_), None) | (None, None) => {
+                    write!(f, "paragraph {}: removed {}", paragraph, field)
+                }
+            },
+            Change::Renamed {
+                paragraph,
+                from,
+                to,
+            } => write!(f, "paragraph {}: renamed {} to {}", paragraph, from, to),
+            Change::ParagraphAdded { paragraph } => write!(f, "added paragraph {}", paragraph),
+            Change::ParagraphRemoved { paragraph, .. } => {
+                write!(f, "removed paragraph {}", paragraph)
+            }
+        }
+    }
+}
+
+/// The changes made by applying a change set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    /// The changes, in the order in which they were made.
+    pub changes: Vec<Change>,
+}
+
+impl Report {
+    /// Returns whether nothing was changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns a change set that reverts the changes.
+    ///
+    /// Values and the order of fields and paragraphs are restored, but the formatting of
+    /// restored values may differ from the original.
+    pub fn inverse(&self) -> ChangeSet {
+        let operations = self
+            .changes
+            .iter()
+            .rev()
+            .map(|change| match change {
+                Change::Field {
+                    paragraph,
+                    field,
+                    old: Some(old),
+                    after,
+                    before,
+                    ..
+                } => Operation::SetField {
+                    paragraph: Selector::Index(*paragraph),
+                    field: field.clone(),
+                    value: old.clone(),
+                    after: after.clone(),
+                    before: before.clone(),
+                },
+                Change::Field {
+                    paragraph,
+                    field,
+                    old: None,
+                    ..
+                } => Operation::RemoveField {
+                    paragraph: Selector::Index(*paragraph),
+                    field: field.clone(),
+                },
+                Change::Renamed {
+                    paragraph,
+                    from,
+                    to,
+                } => Operation::RenameField {
+                    paragraph: Selector::Index(*paragraph),
+                    from: to.clone(),
+                    to: from.clone(),
+                },
+                Change::ParagraphAdded { paragraph } => Operation::RemoveParagraph {
+                    paragraph: Selector::Index(*paragraph),
+                },
+                Change::ParagraphRemoved { paragraph, fields } => Operation::AddParagraph {
+                    position: Some(*paragraph),
+                    fields: fields.clone(),
+                },
+            })
+            .collect();
+        ChangeSet { operations }
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// A list of operations, applied to a document as a whole.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChangeSet {
+    /// The operations, in the order in which they are applied.
+    pub operations: Vec<Operation>,
+}
+
+impl ChangeSet {
+    /// Create a change set from a list of operations.
+    pub fn new(operations: Vec<Operation>) -> Self {
+        Self { operations }
+    }
+
+    /// Add an operation to the change set.
+    pub fn push(&mut self, operation: Operation) {
+        self.operations.push(operation);
+    }
+
+    /// Apply the change set to a copy of the document, leaving the original untouched.
+    ///
+    /// This can be used to preview the changes.
+    pub fn preview(&self, deb822: &Deb822) -> Result<(Deb822, Report), Error> {
+        let mut copy = deb822.detached();
+        let mut report = Report::default();
+        for (i, operation) in self.operations.iter().enumerate() {
+            apply_operation(&mut copy, i, operation, &mut report)?;
+        }
+        Ok((copy, report))
+    }
+
+    /// Apply the change set to the document.
+    ///
+    /// Either all operations are applied, or, if any of them fails, none are. Since the
+    /// document is replaced as a whole, paragraphs obtained from it before the call no longer
+    /// refer to it afterwards.
+    pub fn apply(&self, deb822: &mut Deb822) -> Result<Report, Error> {
+        let (result, report) = self.preview(deb822)?;
+        *deb822 = result;
+        Ok(report)
+    }
+}
+
+fn check_field_name(operation: usize, field: &str) -> Result<(), Error> {
+    if crate::common::is_valid_key(field) {
+        Ok(())
+    } else {
+        Err(Error::InvalidFieldName {
+            operation,
+            field: field.to_string(),
+        })
+    }
+}
+
+/// Returns the names of the fields preceding and following `field` in the paragraph.
+fn neighbouring_fields(paragraph: &Paragraph, field: &str) -> (Option<String>, Option<String>) {
+    let keys = paragraph.keys().collect::<Vec<_>>();
+    let Some(index) = keys.iter().position(|k| crate::common::key_eq(k, field)) else {
+        return (None, None);
+    };
+    (keys[..index].last().cloned(), keys.get(index + 1).cloned())
+}
+
+/// Set a field, recording the change if the value differs.
+fn set_field(
+    paragraph: &mut Paragraph,
+    index: usize,
+    field: &str,
+    value: &MultiLineValue,
+    after: Option<&str>,
+    before: Option<&str>,
+    report: &mut Report,
+) {
+    let old = paragraph.get_multiline(field).map(|v| v.to_value());
+    if old.is_none() {
+        // Insert a placeholder in the right position, which is replaced below.
+        let inserted = after.is_some_and(|after| paragraph.insert_after(after, field, ""));
+        if !inserted {
+            if let Some(before) = before {
+                paragraph.insert_before(before, field, "");
+            }
+        }
+    }
+    paragraph.set_multiline(field, value);
+    let new = Some(value.to_value());
+    if old != new {
+        let (after, before) = neighbouring_fields(paragraph, field);
+        report.changes.push(Change::Field {
+            paragraph: index,
+            field: field.to_string(),
+            old,
+            new,
+            after,
+            before,
+        });
+    }
+}
+
+/// Returns the indices of the paragraphs selected by the operation with index `i`.
+fn select(selector: &Selector, paragraphs: &[Paragraph], i: usize) -> Result<Vec<usize>, Error> {
+    let selected = selector.select(paragraphs);
+    if selected.is_empty() {
+        Err(Error::NoMatchingParagraph(i))
+    } else {
+        Ok(selected)
+    }
+}
+
+fn apply_operation(
+    deb822: &mut Deb822,
+    i: usize,
+    operation: &Operation,
+    report: &mut Report,
+) -> Result<(), Error> {
+    let mut paragraphs = deb822.paragraphs().collect::<Vec<_>>();
+    match operation {
+        Operation::SetField {
+            paragraph,
+            field,
+            value,
+            after,
+            before,
+        } => {
+            check_field_name(i, field)?;
+            let value = MultiLineValue::from_value(value);
+            for index in select(paragraph, &paragraphs, i)? {
+                let paragraph = &mut paragraphs[index];
+                set_field(
+                    paragraph,
+                    index,
+                    field,
+                    &value,
+                    after.as_deref(),
+                    before.as_deref(),
+                    report,
+                );
+            }
+        }
+        Operation::RemoveField { paragraph, field } => {
+            for index in select(paragraph, &paragraphs, i)? {
+                let paragraph = &mut paragraphs[index];
+                if let Some(old) = paragraph.get_multiline(field) {
+                    let (after, before) = neighbouring_fields(paragraph, field);
+                    paragraph.remove(field);
+                    report.changes.push(Change::Field {
+                        paragraph: index,
+                        field: field.clone(),
+                        old: Some(old.to_value()),
+                        new: None,
+                        after,
+                        before,
+                    });
+                }
+            }
+        }
+        Operation::RenameField {
+            paragraph,
+            from,
+            to,
+        } => {
+            check_field_name(i, to)?;
+            let mut found = false;
+            for index in select(paragraph, &paragraphs, i)? {
+                let paragraph = &mut paragraphs[index];
+                let Some(old_name) = paragraph.get_entry(from).and_then(|e| e.key()) else {
+                    continue;
+                };
+                found = true;
+                if old_name != *to {
+                    paragraph.rename(from, to);
+                    report.changes.push(Change::Renamed {
+                        paragraph: index,
+                        from: old_name,
+                        to: to.clone(),
+                    });
+                }
+            }
+            if !found {
+                return Err(Error::NoSuchField {
+                    operation: i,
+                    field: from.clone(),
+                });
+            }
+        }
+        Operation::AddParagraph { position, fields } => {
+            for (field, _) in fields {
+                check_field_name(i, field)?;
+            }
+            let index = position.unwrap_or(paragraphs.len());
+            if index > paragraphs.len() {
+                return Err(Error::InvalidPosition {
+                    operation: i,
+                    position: index,
+                });
+            }
+            let mut paragraph = deb822.insert_paragraph(index);
+            for (field, value) in fields {
+                paragraph.set_multiline(field, &MultiLineValue::from_value(value));
+            }
+            report
+                .changes
+                .push(Change::ParagraphAdded { paragraph: index });
+        }
+        Operation::RemoveParagraph { paragraph } => {
+            let selected = select(paragraph, &paragraphs, i)?;
+            for index in selected.into_iter().rev() {
+                let fields = paragraphs[index]
+                    .entries()
+                    .filter_map(|e| Some((e.key()?, e.multiline_value().to_value())))
+                    .collect();
+                deb822.remove_paragraph(index);
+                report.changes.push(Change::ParagraphRemoved {
+                    paragraph: index,
+                    fields,
+                });
+            }
+        }
+        Operation::InsertRelation {
+            paragraph,
+            field,
+            entry,
+        } => {
+            check_field_name(i, field)?;
+            let entry = crate::diff::normalize(entry);
+            for index in select(paragraph, &paragraphs, i)? {
+                let paragraph = &mut paragraphs[index];
+                let template = paragraph
+                    .get_multiline(field)
+                    .unwrap_or_else(|| MultiLineValue::new("", vec![]));
+                let mut entries = relations(Some(&template.to_value()))
+                    .into_iter()
+                    .map(|(_, e)| e)
+                    .collect::<Vec<_>>();
+                if entries.contains(&entry) {
+                    continue;
+                }
+                entries.push(entry.clone());
+                let value = format_relations(&template, &entries);
+                set_field(paragraph, index, field, &value, None, None, report);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = r#"Source: foo
+Priority: optional
+Build-Depends: debhelper-compat (= 13),
+               libbar-dev
+
+Package: foo
+Architecture: any
+
+Package: foo-doc
+Architecture: all
+"#;
+
+    fn package(name: &str) -> Selector {
+        Selector::Field {
+            field: "Package".to_string(),
+            value: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_and_revert() {
+        let mut deb822: Deb822 = INPUT.parse().unwrap();
+        let changes = ChangeSet::new(vec![
+            Operation::SetField {
+                paragraph: Selector::Index(0),
+                field: "Section".to_string(),
+                value: "libs".to_string(),
+                after: Some("Source".to_string()),
+                before: None,
+            },
+            Operation::RemoveField {
+                paragraph: Selector::All,
+                field: "Priority".to_string(),
+            },
+            Operation::RenameField {
+                paragraph: package("foo"),
+                from: "architecture".to_string(),
+                to: "Architecture".to_string(),
+            },
+            Operation::InsertRelation {
+                paragraph: Selector::Index(0),
+                field: "Build-Depends".to_string(),
+                entry: "libbaz-dev".to_string(),
+            },
+            Operation::InsertRelation {
+                paragraph: package("foo"),
+                field: "Depends".to_string(),
+                entry: "${misc:Depends}".to_string(),
+            },
+            Operation::RemoveParagraph {
+                paragraph: package("foo-doc"),
+            },
+            Operation::AddParagraph {
+                position: Some(1),
+                fields: vec![("Package".to_string(), "libfoo1".to_string())],
+            },
+        ]);
+        let report = changes.apply(&mut deb822).unwrap();
+        assert_eq!(
+            deb822.to_string(),
+            r#"Source: foo
+Section: libs
+Build-Depends: debhelper-compat (= 13),
+               libbar-dev,
+               libbaz-dev
+
+Package: libfoo1
+
+Package: foo
+Architecture: any
+Depends: ${misc:Depends}
+"#
+        );
+        assert_eq!(
+            report.to_string(),
+            r#"paragraph 0: added Section: "libs"
+paragraph 0: removed Priority
+paragraph 0: Build-Depends: "debhelper-compat (= 13),\n              libbar-dev" -> "debhelper-compat (= 13),\n              libbar-dev,\n              libbaz-dev"
+paragraph 1: added Depends: "${misc:Depends}"
+removed paragraph 2
+added paragraph 1
+"#
+        );
+
+        let report = report.inverse().apply(&mut deb822).unwrap();
+        assert_eq!(report.changes.len(), 6);
+        assert_eq!(deb822.to_string(), INPUT);
+    }
+
+    #[test]
+    fn test_revert_remove_first_field() {
+        let mut deb822: Deb822 = "Source: foo\nSection: libs\n".parse().unwrap();
+        let changes = ChangeSet::new(vec![Operation::RemoveField {
+            paragraph: Selector::All,
+            field: "Source".to_string(),
+        }]);
+        let report = changes.apply(&mut deb822).unwrap();
+        assert_eq!(deb822.to_string(), "Section: libs\n");
+        report.inverse().apply(&mut deb822).unwrap();
+        assert_eq!(deb822.to_string(), "Source: foo\nSection: libs\n");
+    }
+
+    #[test]
+    fn test_all_or_nothing() {
+        let mut deb822: Deb822 = INPUT.parse().unwrap();
+        let changes = ChangeSet::new(vec![
+            Operation::RemoveField {
+                paragraph: Selector::All,
+                field: "Architecture".to_string(),
+            },
+            Operation::RenameField {
+                paragraph: Selector::All,
+                from: "Homepage".to_string(),
+                to: "Vcs-Browser".to_string(),
+            },
+        ]);
+        assert_eq!(
+            changes.apply(&mut deb822).unwrap_err(),
+            Error::NoSuchField {
+                operation: 1,
+                field: "Homepage".to_string()
+            }
+        );
+        assert_eq!(deb822.to_string(), INPUT);
+
+        let errors = [
+            (
+                Operation::RemoveParagraph {
+                    paragraph: package("bar"),
+                },
+                Error::NoMatchingParagraph(0),
+            ),
+            (
+                Operation::AddParagraph {
+                    position: Some(4),
+                    fields: vec![],
+                },
+                Error::InvalidPosition {
+                    operation: 0,
+                    position: 4,
+                },
+            ),
+            (
+                Operation::SetField {
+                    paragraph: Selector::All,
+                    field: "Foo: bar".to_string(),
+                    value: "".to_string(),
+                    after: None,
+                    before: None,
+                },
+                Error::InvalidFieldName {
+                    operation: 0,
+                    field: "Foo: bar".to_string(),
+                },
+            ),
+        ];
+        for (operation, error) in errors {
+            assert_eq!(
+                ChangeSet::new(vec![operation]).apply(&mut deb822),
+                Err(error)
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let deb822: Deb822 = INPUT.parse().unwrap();
+        let changes = ChangeSet::new(vec![
+            Operation::SetField {
+                paragraph: Selector::Index(0),
+                field: "Priority".to_string(),
+                value: "optional".to_string(),
+                after: None,
+                before: None,
+            },
+            Operation::InsertRelation {
+                paragraph: Selector::Index(0),
+                field: "Build-Depends".to_string(),
+                entry: "libbar-dev".to_string(),
+            },
+        ]);
+        let (preview, report) = changes.preview(&deb822).unwrap();
+        assert!(report.is_empty());
+        assert_eq!(preview.to_string(), INPUT);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_serialize() {
+        let changes = ChangeSet::new(vec![
+            Operation::SetField {
+                paragraph: Selector::Index(0),
+                field: "Section".to_string(),
+                value: "libs".to_string(),
+                after: None,
+                before: None,
+            },
+            Operation::RemoveParagraph {
+                paragraph: package("foo-doc"),
+            },
+        ]);
+        let value = serde_json::to_value(&changes).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"operations": [
+                {"op": "set-field", "paragraph": {"index": 0}, "field": "Section", "value": "libs"},
+                {"op": "remove-paragraph", "paragraph": {"field": {"field": "Package", "value": "foo-doc"}}},
+            ]})
+        );
+        assert_eq!(serde_json::from_value::<ChangeSet>(value).unwrap(), changes);
+    }
+}
@@ -0,0 +1,341 @@
+//! Safely editing files in place.
+//!
+//! [`FileEditor`] reads and parses a file, hands out the parsed document for editing, and
+//! writes it back when [`FileEditor::commit`] is called. The file is only written if the
+//! document changed, and is replaced atomically by writing to a temporary file in the same
+//! directory and renaming it over the original, keeping the original permissions. If the file
+//! was modified on disk after it was read, the commit fails rather than overwriting those
+//! changes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use deb822_lossless::editor::FileEditor;
+//! use deb822_lossless::Deb822;
+//!
+//! let td = tempfile::tempdir().unwrap();
+//! let path = td.path().join("control");
+//! std::fs::write(&path, "Source: foo\n# The section\nSection: libs\n").unwrap();
+//!
+//! let mut editor = FileEditor::<Deb822>::open(&path).unwrap();
+//! let mut source = editor.paragraphs().next().unwrap();
+//! source.set("Section", "devel");
+//! assert!(editor.commit().unwrap());
+//!
+//! assert_eq!(
+//!     std::fs::read_to_string(&path).unwrap(),
+//!     "Source: foo\n# The section\nSection: devel\n"
+//! );
+//! ```
+use crate::lossless::{Deb822, Paragraph, ParseError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A document that can be edited with a [`FileEditor`].
+pub trait Editable: Sized {
+    /// The error returned when the file can not be parsed.
+    type Error;
+
+    /// Parse the contents of a file.
+    fn parse(text: &str) -> Result<Self, Self::Error>;
+
+    /// Serialize the document, in the form in which it is written back to the file.
+    fn to_text(&self) -> String;
+}
+
+impl Editable for Deb822 {
+    type Error = ParseError;
+
+    fn parse(text: &str) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Editable for Paragraph {
+    type Error = ParseError;
+
+    fn parse(text: &str) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Error opening or committing a [`FileEditor`].
+#[derive(Debug)]
+pub enum EditorError<E> {
+    /// Reading or writing the file failed.
+    Io(std::io::Error),
+
+    /// The file could not be parsed.
+    Parse(E),
+
+    /// The file was modified on disk after it was read.
+    ChangedOnDisk(PathBuf),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for EditorError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EditorError::Io(e) => write!(f, "{}", e),
+            EditorError::Parse(e) => write!(f, "{}", e),
+            EditorError::ChangedOnDisk(path) => {
+                write!(f, "{} was modified since it was read", path.display())
+            }
+        }
+    }
+}
+
+impl<E: std::fmt::Debug + std::fmt::Display> std::error::Error for EditorError<E> {}
+
+impl<E> From<std::io::Error> for EditorError<E> {
+    fn from(e: std::io::Error) -> Self {
+        EditorError::Io(e)
+    }
+}
+
+/// A file that is being edited.
+///
+/// The editor dereferences to the parsed document. Changes are only written to disk by
+/// [`FileEditor::commit`]; dropping the editor discards them.
+#[derive(Debug)]
+pub struct FileEditor<T: Editable> {
+    path: PathBuf,
+    original: String,
+    document: T,
+}
+
+impl<T: Editable> FileEditor<T> {
+    /// Read and parse the file at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, EditorError<T::Error>> {
+        let path = path.as_ref().to_path_buf();
+        let original = std::fs::read_to_string(&path)?;
+        let document = T::parse(&original).map_err(EditorError::Parse)?;
+        Ok(Self {
+            path,
+            original,
+            document,
+        })
+    }
+
+    /// Returns the path of the file being edited.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the contents of the file as it was read.
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// Returns whether the document differs from the contents of the file as it was read.
+    pub fn has_changed(&self) -> bool {
+        self.document.to_text() != self.original
+    }
+
+    /// Write the document back to the file, if it changed.
+    ///
+    /// Returns whether the file was written. Fails with [`EditorError::ChangedOnDisk`] if the
+    /// contents of the file are no longer the same as when it was read, in which case the file
+    /// is left alone.
+    pub fn commit(self) -> Result<bool, EditorError<T::Error>> {
+        let text = self.document.to_text();
+        if text == self.original {
+            return Ok(false);
+        }
+        match std::fs::read_to_string(&self.path) {
+            Ok(current) if current == self.original => {}
+            Ok(_) => return Err(EditorError::ChangedOnDisk(self.path)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(EditorError::ChangedOnDisk(self.path))
+            }
+            Err(e) => return Err(e.into()),
+        }
+        write_atomic(&self.path, text.as_bytes())?;
+        Ok(true)
+    }
+}
+
+impl<T: Editable> std::ops::Deref for FileEditor<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.document
+    }
+}
+
+impl<T: Editable> std::ops::DerefMut for FileEditor<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.document
+    }
+}
+
+/// Replace the contents of the existing file at `path` by writing to a temporary file and
+/// renaming it.
+///
+/// The permissions of the existing file are kept. If `path` is a symbolic link, the file it
+/// points to is replaced rather than the link.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    /// Distinguishes the temporary files of concurrent calls within this process.
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    let path = &std::fs::canonicalize(path)?;
+    let permissions = std::fs::metadata(path)?.permissions();
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let (tmp_path, mut f) = loop {
+        let tmp_path = path.with_file_name(format!(
+            ".{}.{}.{}.tmp",
+            file_name,
+            std::process::id(),
+            COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        ));
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)
+        {
+            Ok(f) => break (tmp_path, f),
+            // Left behind by an earlier process with the same id.
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    };
+    let result = (|| {
+        f.write_all(contents)?;
+        f.sync_all()?;
+        std::fs::set_permissions(&tmp_path, permissions)?;
+        std::fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+        return result;
+    }
+    // Make sure the rename itself survives a crash.
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        std::fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("commit");
+        std::fs::write(&path, "Source: foo\n\nPackage: foo\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+        }
+
+        let mut editor = FileEditor::<Deb822>::open(&path).unwrap();
+        assert_eq!(editor.path(), path);
+        assert!(!editor.has_changed());
+        editor.add_paragraph().set("Package", "foo-doc");
+        assert!(editor.has_changed());
+        assert!(editor.commit().unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "Source: foo\n\nPackage: foo\n\nPackage: foo-doc\n"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o640);
+        }
+
+        // Opening a file that does not exist fails.
+        let editor = FileEditor::<Paragraph>::open(td.path().join("missing"));
+        assert!(matches!(editor, Err(EditorError::Io(_))));
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("unchanged");
+        std::fs::write(&path, "Source: foo\n").unwrap();
+        let mut editor = FileEditor::<Paragraph>::open(&path).unwrap();
+        editor.set("Source", "foo");
+        // The file is not written if the document did not change, even if the file did.
+        std::fs::write(&path, "Source: bar\n").unwrap();
+        assert!(!editor.commit().unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "Source: bar\n");
+    }
+
+    #[test]
+    fn test_changed_on_disk() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("changed");
+        std::fs::write(&path, "Source: foo\n").unwrap();
+        let editor = FileEditor::<Deb822>::open(&path).unwrap();
+        editor.paragraphs().next().unwrap().set("Section", "libs");
+        std::fs::write(&path, "Source: bar\n").unwrap();
+        assert!(matches!(
+            editor.commit(),
+            Err(EditorError::ChangedOnDisk(p)) if p == path
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "Source: bar\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commit_symlink() {
+        let td = tempfile::tempdir().unwrap();
+        let target = td.path().join("symlink-target");
+        let link = td.path().join("symlink");
+        std::fs::write(&target, "Source: foo\n").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let mut editor = FileEditor::<Paragraph>::open(&link).unwrap();
+        editor.set("Section", "libs");
+        assert!(editor.commit().unwrap());
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            std::fs::read_to_string(&target).unwrap(),
+            "Source: foo\nSection: libs\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("invalid");
+        std::fs::write(&path, "Source foo\n").unwrap();
+        assert!(matches!(
+            FileEditor::<Deb822>::open(&path),
+            Err(EditorError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_write_atomic_concurrent() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("concurrent");
+        std::fs::write(&path, "").unwrap();
+        let contents = (0..8)
+            .map(|i| format!("Package: foo{}\n", i))
+            .collect::<Vec<_>>();
+        std::thread::scope(|s| {
+            for c in &contents {
+                s.spawn(|| write_atomic(&path, c.as_bytes()).unwrap());
+            }
+        });
+        assert!(contents.contains(&std::fs::read_to_string(&path).unwrap()));
+        assert_eq!(std::fs::read_dir(td.path()).unwrap().count(), 1);
+    }
+}
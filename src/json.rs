@@ -0,0 +1,361 @@
+//! Conversion between deb822 documents and JSON or YAML.
+//!
+//! A document maps to an array of objects, one per paragraph, with the fields in their original
+//! order. Field values are strings; multi-line values can optionally be split into arrays of
+//! lines. The conversion in the other direction builds a [`Deb822`], so that data edited as JSON
+//! can be written back as deb822. [`update_from_json`] applies such edits to an existing document,
+//! keeping the formatting of fields that did not change.
+//!
+//! # Example
+//!
+//! ```rust
+//! use deb822_lossless::json::{from_json, to_json, JsonOptions};
+//! use deb822_lossless::Deb822;
+//!
+//! let deb822: Deb822 = "Package: foo\nDescription: short\n long\n".parse().unwrap();
+//! let json = to_json(&deb822, &JsonOptions { split_lines: true });
+//! assert_eq!(
+//!     json.to_string(),
+//!     r#"[{"Package":"foo","Description":["short","long"]}]"#
+//! );
+//! assert_eq!(from_json(&json).unwrap().to_string(), deb822.to_string());
+//! ```
+use crate::lossless::{Deb822, Paragraph};
+use crate::multiline::{ContinuationLine, MultiLineValue};
+use serde_json::{Map, Value};
+
+/// Options for converting to JSON.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonOptions {
+    /// Represent multi-line values as arrays of lines, rather than strings containing newlines.
+    ///
+    /// Empty lines (" ." in deb822) are represented as empty strings.
+    pub split_lines: bool,
+}
+
+/// Error converting JSON to deb822.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The document is not an array of objects.
+    ExpectedArray,
+
+    /// The paragraph at the given index is not an object.
+    ExpectedObject(usize),
+
+    /// The field name is not valid in deb822.
+    InvalidFieldName(String),
+
+    /// The value of the field can not be represented in deb822.
+    InvalidValue(String),
+
+    /// The YAML value can not be converted to JSON, for example because a mapping has a key
+    /// that is not a string.
+    InvalidYaml(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::ExpectedArray => f.write_str("expected an array of paragraphs"),
+            Error::ExpectedObject(i) => write!(f, "paragraph {} is not an object", i),
+            Error::InvalidFieldName(name) => write!(f, "invalid field name: {:?}", name),
+            Error::InvalidValue(name) => write!(f, "invalid value for field {}", name),
+            Error::InvalidYaml(e) => write!(f, "invalid YAML: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Convert a field value to JSON.
+fn value_to_json(value: MultiLineValue, options: &JsonOptions) -> Value {
+    if options.split_lines && !value.is_single_line() {
+        Value::Array(
+            std::iter::once(value.first_line)
+                .chain(value.lines.into_iter().map(|line| match line {
+                    ContinuationLine::Text(text) => text,
+                    ContinuationLine::Blank => String::new(),
+                }))
+                .map(Value::String)
+                .collect(),
+        )
+    } else {
+        Value::String(value.to_value())
+    }
+}
+
+/// Convert a JSON value to a field value, or `None` if the field should be omitted.
+fn value_from_json(name: &str, value: &Value) -> Result<Option<MultiLineValue>, Error> {
+    let scalar = |value: &Value| match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(if *b { "yes" } else { "no" }.to_string()),
+        _ => Err(Error::InvalidValue(name.to_string())),
+    };
+    match value {
+        Value::Null => Ok(None),
+        Value::Array(lines) => {
+            let lines = lines.iter().map(scalar).collect::<Result<Vec<_>, _>>()?;
+            Ok(Some(MultiLineValue::from_text(&lines.join("\n"))))
+        }
+        value => Ok(Some(MultiLineValue::from_value(&scalar(value)?))),
+    }
+}
+
+fn check_field_name(name: &str) -> Result<(), Error> {
+    if !crate::common::is_valid_key(name) {
+        return Err(Error::InvalidFieldName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Convert a paragraph to a JSON object.
+///
+/// If a field occurs more than once, only its last value is kept.
+pub fn paragraph_to_json(paragraph: &Paragraph, options: &JsonOptions) -> Value {
+    Value::Object(
+        paragraph
+            .entries()
+            .filter_map(|entry| {
+                let key = entry.key()?;
+                Some((key, value_to_json(entry.multiline_value(), options)))
+            })
+            .collect(),
+    )
+}
+
+/// Convert a document to a JSON array of objects.
+pub fn to_json(deb822: &Deb822, options: &JsonOptions) -> Value {
+    Value::Array(
+        deb822
+            .paragraphs()
+            .map(|p| paragraph_to_json(&p, options))
+            .collect(),
+    )
+}
+
+/// Build a paragraph from a JSON object.
+///
+/// Values may be strings, numbers, booleans (written as "yes" or "no") or arrays of lines.
+/// Fields that are `null` are omitted.
+pub fn paragraph_from_json(object: &Map<String, Value>) -> Result<Paragraph, Error> {
+    let mut paragraph = Paragraph::new();
+    for (name, value) in object {
+        check_field_name(name)?;
+        if let Some(value) = value_from_json(name, value)? {
+            paragraph.set_multiline(name, &value);
+        }
+    }
+    Ok(paragraph)
+}
+
+fn paragraphs(value: &Value) -> Result<Vec<&Map<String, Value>>, Error> {
+    value
+        .as_array()
+        .ok_or(Error::ExpectedArray)?
+        .iter()
+        .enumerate()
+        .map(|(i, p)| p.as_object().ok_or(Error::ExpectedObject(i)))
+        .collect()
+}
+
+/// Build a document from a JSON array of objects.
+pub fn from_json(value: &Value) -> Result<Deb822, Error> {
+    paragraphs(value)?
+        .into_iter()
+        .map(paragraph_from_json)
+        .collect()
+}
+
+/// Apply the contents of a JSON array of objects to an existing document.
+///
+/// Fields whose value did not change keep their formatting, fields that are missing from the
+/// JSON are removed and new fields are appended to their paragraph. Paragraphs are matched by
+/// position; any extra paragraphs are added or removed at the end.
+pub fn update_from_json(deb822: &mut Deb822, value: &Value) -> Result<(), Error> {
+    let objects = paragraphs(value)?;
+    let existing = deb822.paragraphs().count();
+    for (i, object) in objects.iter().enumerate() {
+        let mut paragraph = if i < existing {
+            deb822.paragraphs().nth(i).unwrap()
+        } else {
+            deb822.add_paragraph()
+        };
+        for key in paragraph.keys().collect::<Vec<_>>() {
+            if !object.keys().any(|k| crate::common::key_eq(k, &key)) {
+                paragraph.remove(&key);
+            }
+        }
+        for (name, value) in object.iter() {
+            check_field_name(name)?;
+            let Some(value) = value_from_json(name, value)? else {
+                paragraph.remove(name);
+                continue;
+            };
+            if paragraph.get_multiline(name).as_ref() != Some(&value) {
+                paragraph.set_multiline(name, &value);
+            }
+        }
+    }
+    for i in (objects.len()..existing).rev() {
+        deb822.remove_paragraph(i);
+    }
+    Ok(())
+}
+
+/// Convert a document to a YAML sequence of mappings.
+///
+/// This uses the same representation as [`to_json`].
+#[cfg(feature = "yaml")]
+pub fn to_yaml(deb822: &Deb822, options: &JsonOptions) -> serde_yaml::Value {
+    serde_yaml::to_value(to_json(deb822, options)).unwrap()
+}
+
+/// Build a document from a YAML sequence of mappings.
+///
+/// This accepts the same representation as [`from_json`].
+#[cfg(feature = "yaml")]
+pub fn from_yaml(value: &serde_yaml::Value) -> Result<Deb822, Error> {
+    let value: Value =
+        serde_yaml::from_value(value.clone()).map_err(|e| Error::InvalidYaml(e.to_string()))?;
+    from_json(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = r#"Source: foo
+# The maintainer
+Maintainer: Joe <joe@example.com>
+Build-Depends: debhelper-compat (= 13),
+               libfoo-dev
+
+Package: foo
+Architecture: any
+Description: short
+ long
+ .
+   verbatim
+"#;
+
+    #[test]
+    fn test_to_json() {
+        let deb822: Deb822 = INPUT.parse().unwrap();
+        assert_eq!(
+            to_json(&deb822, &JsonOptions::default()),
+            serde_json::json!([
+                {
+                    "Source": "foo",
+                    "Maintainer": "Joe <joe@example.com>",
+                    "Build-Depends": "debhelper-compat (= 13),\n              libfoo-dev",
+                },
+                {
+                    "Package": "foo",
+                    "Architecture": "any",
+                    "Description": "short\nlong\n.\n  verbatim",
+                },
+            ])
+        );
+        let json = to_json(&deb822, &JsonOptions { split_lines: true });
+        assert_eq!(
+            json[1]["Description"],
+            serde_json::json!(["short", "long", "", "  verbatim"])
+        );
+        // Field order is preserved.
+        assert_eq!(
+            json[0].as_object().unwrap().keys().collect::<Vec<_>>(),
+            vec!["Source", "Maintainer", "Build-Depends"]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let deb822: Deb822 = INPUT.parse().unwrap();
+        for options in [JsonOptions::default(), JsonOptions { split_lines: true }] {
+            let json = to_json(&deb822, &options);
+            let text = serde_json::to_string(&json).unwrap();
+            let rebuilt = from_json(&serde_json::from_str(&text).unwrap()).unwrap();
+            assert_eq!(rebuilt.to_string(), INPUT.replace("# The maintainer\n", ""));
+        }
+    }
+
+    #[test]
+    fn test_from_json() {
+        let deb822 = from_json(&serde_json::json!([
+            {"Package": "foo", "Installed-Size": 12, "Essential": true, "Homepage": null},
+        ]))
+        .unwrap();
+        assert_eq!(
+            deb822.to_string(),
+            "Package: foo\nInstalled-Size: 12\nEssential: yes\n"
+        );
+        assert_eq!(
+            from_json(&serde_json::json!({"Package": "foo"})).unwrap_err(),
+            Error::ExpectedArray
+        );
+        assert_eq!(
+            from_json(&serde_json::json!([{}, "foo"])).unwrap_err(),
+            Error::ExpectedObject(1)
+        );
+        assert_eq!(
+            from_json(&serde_json::json!([{"Package: foo": "bar"}])).unwrap_err(),
+            Error::InvalidFieldName("Package: foo".to_string())
+        );
+        assert_eq!(
+            from_json(&serde_json::json!([{"Files": {"a": "b"}}])).unwrap_err(),
+            Error::InvalidValue("Files".to_string())
+        );
+    }
+
+    #[test]
+    fn test_update_from_json() {
+        let mut deb822: Deb822 = INPUT.parse().unwrap();
+        let mut json = to_json(&deb822, &JsonOptions { split_lines: true });
+        json[0]["Maintainer"] = "Jane <jane@example.com>".into();
+        json[1].as_object_mut().unwrap().remove("Architecture");
+        json[1]["Section"] = "libs".into();
+        json.as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({"Package": "foo-doc"}));
+        update_from_json(&mut deb822, &json).unwrap();
+        assert_eq!(
+            deb822.to_string(),
+            r#"Source: foo
+# The maintainer
+Maintainer: Jane <jane@example.com>
+Build-Depends: debhelper-compat (= 13),
+               libfoo-dev
+
+Package: foo
+Description: short
+ long
+ .
+   verbatim
+Section: libs
+
+Package: foo-doc
+"#
+        );
+
+        json.as_array_mut().unwrap().truncate(1);
+        update_from_json(&mut deb822, &json).unwrap();
+        assert_eq!(deb822.paragraphs().count(), 1);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml() {
+        let deb822: Deb822 = INPUT.parse().unwrap();
+        let yaml = to_yaml(&deb822, &JsonOptions { split_lines: true });
+        let text = serde_yaml::to_string(&yaml).unwrap();
+        assert!(text.starts_with("- Source: foo\n  Maintainer: Joe <joe@example.com>\n"));
+        let rebuilt = from_yaml(&serde_yaml::from_str(&text).unwrap()).unwrap();
+        assert_eq!(rebuilt.to_string(), INPUT.replace("# The maintainer\n", ""));
+
+        let invalid = serde_yaml::from_str("- [a, b]: c\n").unwrap();
+        assert!(matches!(from_yaml(&invalid), Err(Error::InvalidYaml(_))));
+        let not_array = serde_yaml::from_str("Source: foo\n").unwrap();
+        assert_eq!(from_yaml(&not_array), Err(Error::ExpectedArray));
+    }
+}
@@ -1,9 +1,10 @@
 use crate::common;
+use crate::dialect::{Comments, ParseOptions};
 
 /// Let's start with defining all kinds of tokens and
 /// composite nodes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 #[repr(u16)]
 pub enum SyntaxKind {
     KEY = 0,
@@ -29,20 +30,25 @@ impl From<SyntaxKind> for rowan::SyntaxKind {
     }
 }
 
-fn lex_(mut input: &str, mut start_of_line: bool) -> impl Iterator<Item = (SyntaxKind, &str)> {
+fn lex_(
+    mut input: &str,
+    mut start_of_line: bool,
+    comments: bool,
+) -> impl Iterator<Item = (SyntaxKind, &str)> {
     let mut colon_count = if start_of_line { 0 } else { 1 };
     let mut indent = 0;
 
     std::iter::from_fn(move || {
         if let Some(c) = input.chars().next() {
             match c {
-                ':' if colon_count == 0 => {
+                ':' if colon_count == 0 && indent == 0 => {
                     colon_count += 1;
                     input = &input[1..];
                     Some((SyntaxKind::COLON, ":"))
                 }
                 _ if common::is_newline(c) => {
-                    let (nl, remaining) = input.split_at(1);
+                    let (nl, remaining) =
+                        input.split_at(if input.starts_with("\r\n") { 2 } else { 1 });
                     input = remaining;
                     start_of_line = true;
                     colon_count = 0;
@@ -60,7 +66,7 @@ fn lex_(mut input: &str, mut start_of_line: bool) -> impl Iterator<Item = (Synta
                         Some((SyntaxKind::WHITESPACE, whitespace))
                     }
                 }
-                '#' if start_of_line => {
+                '#' if start_of_line && indent == 0 && comments => {
                     let (comment, remaining) =
                         input.split_at(input.find(common::is_newline).unwrap_or(input.len()));
                     input = remaining;
@@ -85,7 +91,7 @@ fn lex_(mut input: &str, mut start_of_line: bool) -> impl Iterator<Item = (Synta
                     Some((SyntaxKind::VALUE, value))
                 }
                 _ => {
-                    let (text, remaining) = input.split_at(1);
+                    let (text, remaining) = input.split_at(c.len_utf8());
                     input = remaining;
                     Some((SyntaxKind::ERROR, text))
                 }
@@ -96,12 +102,20 @@ fn lex_(mut input: &str, mut start_of_line: bool) -> impl Iterator<Item = (Synta
     })
 }
 
+#[cfg(test)]
 pub(crate) fn lex(input: &str) -> impl Iterator<Item = (SyntaxKind, &str)> {
-    lex_(input, true)
+    lex_(input, true, true)
+}
+
+pub(crate) fn lex_with_options<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> impl Iterator<Item = (SyntaxKind, &'a str)> {
+    lex_(input, true, options.comments != Comments::None)
 }
 
 pub(crate) fn lex_inline(input: &str) -> impl Iterator<Item = (SyntaxKind, &str)> {
-    lex_(input, false)
+    lex_(input, false, true)
 }
 
 #[cfg(test)]
@@ -282,4 +296,67 @@ Section: vcs
             ]
         );
     }
+
+    #[test]
+    fn test_lex_crlf() {
+        assert_eq!(
+            super::lex("Foo: bar\r\n baz\r\n\r\n").collect::<Vec<_>>(),
+            vec![
+                (KEY, "Foo"),
+                (COLON, ":"),
+                (WHITESPACE, " "),
+                (VALUE, "bar"),
+                (NEWLINE, "\r\n"),
+                (INDENT, " "),
+                (VALUE, "baz"),
+                (NEWLINE, "\r\n"),
+                (NEWLINE, "\r\n")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_without_comments() {
+        let options = crate::dialect::ParseOptions {
+            comments: crate::dialect::Comments::None,
+            ..Default::default()
+        };
+        assert_eq!(
+            super::lex_with_options("#Foo: bar\n", &options).collect::<Vec<_>>(),
+            vec![
+                (KEY, "#Foo"),
+                (COLON, ":"),
+                (WHITESPACE, " "),
+                (VALUE, "bar"),
+                (NEWLINE, "\n")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_continuation_looks_like_syntax() {
+        // Continuation lines are values, even if they start with a colon or a hash.
+        assert_eq!(
+            super::lex("A:\n :b\n #c\n").collect::<Vec<_>>(),
+            vec![
+                (KEY, "A"),
+                (COLON, ":"),
+                (NEWLINE, "\n"),
+                (INDENT, " "),
+                (VALUE, ":b"),
+                (NEWLINE, "\n"),
+                (INDENT, " "),
+                (VALUE, "#c"),
+                (NEWLINE, "\n")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_non_ascii_error() {
+        assert_eq!(
+            super::lex("ä\n").collect::<Vec<_>>(),
+            vec![(ERROR, "ä"), (NEWLINE, "\n")]
+        );
+    }
 }
@@ -4,15 +4,39 @@
 // Until we drop support for PyO3 0.22, allow use of deprecated functions.
 #![allow(deprecated)]
 
+pub mod borrowed;
 mod common;
 pub mod convert;
+#[cfg(feature = "serde")]
+pub mod de;
+pub mod diagnostics;
+pub mod dialect;
+pub mod diff;
+pub mod edit;
+pub mod editor;
+#[cfg(feature = "json")]
+pub mod json;
 mod lex;
+#[cfg(feature = "serde")]
+pub mod lists;
 pub mod lossless;
 pub mod lossy;
+pub mod merge;
+pub mod multiline;
+#[cfg(test)]
+mod proptests;
+#[cfg(feature = "serde")]
+pub mod ser;
+pub mod stream;
 pub use convert::{FromDeb822Paragraph, ToDeb822Paragraph};
+#[cfg(feature = "serde")]
+pub use de::from_str;
 #[cfg(feature = "derive")]
 pub use deb822_derive::{FromDeb822, ToDeb822};
-pub use lossless::{Deb822, Error, Paragraph, ParseError};
+pub use lossless::{Deb822, Error, Paragraph, ParseError, ParseErrorKind, PositionedParseError};
+pub use rowan::TextRange;
+#[cfg(feature = "serde")]
+pub use ser::to_string;
 
 /// The indentation to use when writing a deb822 file.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
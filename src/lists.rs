@@ -0,0 +1,219 @@
+//! Serde helpers for fields that contain lists.
+//!
+//! By default, sequences are read from and written to fields as comma-separated lists. The
+//! modules in this file can be used with `#[serde(with = "...")]` to use a different encoding.
+//! Each of them has an `option` submodule for `Option<Vec<T>>` fields.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize, Debug, PartialEq)]
+//! struct Sources {
+//!     #[serde(rename = "Types", with = "deb822_lossless::lists::whitespace")]
+//!     types: Vec<String>,
+//!     #[serde(rename = "Checksums", with = "deb822_lossless::lists::lines")]
+//!     checksums: Vec<String>,
+//!     #[serde(rename = "Architectures", default, with = "deb822_lossless::lists::whitespace::option")]
+//!     architectures: Option<Vec<String>>,
+//! }
+//!
+//! let text = "Types: deb deb-src\nChecksums:\n abc 12 foo.dsc\n def 34 foo.tar.gz\n";
+//! let sources: Sources = deb822_lossless::from_str(text).unwrap();
+//! assert_eq!(sources.types, vec!["deb", "deb-src"]);
+//! assert_eq!(sources.checksums, vec!["abc 12 foo.dsc", "def 34 foo.tar.gz"]);
+//! assert_eq!(sources.architectures, None);
+//! assert_eq!(deb822_lossless::to_string(&sources).unwrap(), text);
+//! ```
+use crate::de::Items;
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Split a comma-separated list, ignoring empty items.
+pub(crate) fn split_comma(value: &str) -> Vec<&str> {
+    value
+        .split(',')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn split_whitespace(value: &str) -> Vec<&str> {
+    value.split_whitespace().collect()
+}
+
+fn split_lines(value: &str) -> Vec<&str> {
+    value
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && *line != ".")
+        .collect()
+}
+
+fn serialize_list<S: Serializer, T: Serialize>(
+    items: &[T],
+    join: fn(Vec<String>) -> String,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut values = vec![];
+    for item in items {
+        values.extend(crate::ser::to_value(item).map_err(ser::Error::custom)?);
+    }
+    serializer.serialize_str(&join(values))
+}
+
+fn deserialize_list<'de, D: Deserializer<'de>, T: de::DeserializeOwned>(
+    deserializer: D,
+    split: fn(&str) -> Vec<&str>,
+) -> Result<Vec<T>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Vec::<T>::deserialize(de::value::SeqAccessDeserializer::new(Items(
+        split(&value).into_iter(),
+    )))
+    .map_err(de::Error::custom)
+}
+
+macro_rules! list_module {
+    ($(#[$doc:meta])* $name:ident, $split:expr, $join:expr) => {
+        $(#[$doc])*
+        pub mod $name {
+            use super::*;
+
+            /// Serialize the items as a list.
+            pub fn serialize<S: Serializer, T: Serialize>(
+                items: &[T],
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                serialize_list(items, $join, serializer)
+            }
+
+            /// Deserialize the items of a list.
+            pub fn deserialize<'de, D: Deserializer<'de>, T: de::DeserializeOwned>(
+                deserializer: D,
+            ) -> Result<Vec<T>, D::Error> {
+                deserialize_list(deserializer, $split)
+            }
+
+            /// The same encoding, for optional fields.
+            pub mod option {
+                use super::*;
+
+                /// Serialize the items as a list, if there are any.
+                pub fn serialize<S: Serializer, T: Serialize>(
+                    items: &Option<Vec<T>>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    match items {
+                        Some(items) => serialize_list(items, $join, serializer),
+                        None => serializer.serialize_none(),
+                    }
+                }
+
+                /// Deserialize the items of a list.
+                pub fn deserialize<'de, D: Deserializer<'de>, T: de::DeserializeOwned>(
+                    deserializer: D,
+                ) -> Result<Option<Vec<T>>, D::Error> {
+                    deserialize_list(deserializer, $split).map(Some)
+                }
+            }
+        }
+    };
+}
+
+list_module!(
+    /// Comma-separated lists, like `Depends` or `Binary`.
+    comma,
+    split_comma,
+    |items| items.join(", ")
+);
+
+list_module!(
+    /// Whitespace-separated lists, like `Architecture`.
+    whitespace,
+    split_whitespace,
+    |items| items.join(" ")
+);
+
+list_module!(
+    /// One item per line, like `Checksums-Sha256`. The first line of the value is left empty.
+    lines,
+    split_lines,
+    |items| items
+        .iter()
+        .map(|item| format!("\n{}", item))
+        .collect::<String>()
+);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct Source {
+        #[serde(rename = "Source")]
+        name: String,
+        #[serde(rename = "Binary", with = "super::comma")]
+        binary: Vec<String>,
+        #[serde(rename = "Architecture", with = "super::whitespace")]
+        architecture: Vec<String>,
+        #[serde(rename = "Files", default, with = "super::lines::option")]
+        files: Option<Vec<String>>,
+        #[serde(rename = "Checksums", default, with = "super::lines::option")]
+        checksums: Option<Vec<String>>,
+        #[serde(rename = "Sizes", with = "super::whitespace")]
+        sizes: Vec<u32>,
+    }
+
+    #[test]
+    fn test_lists() {
+        let text = r#"Source: foo
+Binary: foo,
+ foo-doc
+Architecture: any  all
+Files:
+ abc 12 foo.dsc
+ .
+ def 34 foo.tar.gz
+Sizes: 1 2 3
+"#;
+        let source: Source = crate::from_str(text).unwrap();
+        assert_eq!(
+            source,
+            Source {
+                name: "foo".to_string(),
+                binary: vec!["foo".to_string(), "foo-doc".to_string()],
+                architecture: vec!["any".to_string(), "all".to_string()],
+                files: Some(vec![
+                    "abc 12 foo.dsc".to_string(),
+                    "def 34 foo.tar.gz".to_string()
+                ]),
+                checksums: None,
+                sizes: vec![1, 2, 3],
+            }
+        );
+        assert_eq!(
+            crate::to_string(&source).unwrap(),
+            r#"Source: foo
+Binary: foo, foo-doc
+Architecture: any all
+Files:
+ abc 12 foo.dsc
+ def 34 foo.tar.gz
+Sizes: 1 2 3
+"#
+        );
+    }
+
+    #[test]
+    fn test_invalid_item() {
+        let err = crate::from_str::<Source>(
+            "Source: foo\nBinary: foo\nArchitecture: any\nSizes: 1 two\n",
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, crate::de::Error::InvalidValue { ref field, .. } if field == "Sizes"),
+            "{}",
+            err
+        );
+    }
+}
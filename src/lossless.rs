@@ -34,17 +34,132 @@
 //! ```
 
 use crate::{
-    lex::lex,
+    common,
+    dialect::{blank_lines, merge_values, Comments, DuplicateFields, ParseOptions},
+    lex::lex_with_options,
     lex::SyntaxKind::{self, *},
+    multiline::{ContinuationLine, MultiLineValue},
     Indentation,
 };
 use rowan::ast::AstNode;
+use rowan::{TextRange, TextSize};
+use std::ops::Range;
 use std::path::Path;
 use std::str::FromStr;
 
+/// The kind of syntax error that was encountered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// A line that should start a new field does not start with a field name.
+    MissingKey,
+
+    /// A field name is not followed by a colon.
+    MissingColon,
+
+    /// A continuation line appears where a new field was expected.
+    StrayContinuationLine,
+
+    /// A token appears where the end of the line was expected.
+    ExpectedNewline,
+
+    /// The input does not contain any paragraphs.
+    NoParagraphs,
+
+    /// A comment appears, but comments are not allowed.
+    UnexpectedComment,
+
+    /// A line ends in a carriage return, but only `"\n"` line endings are allowed.
+    CarriageReturn,
+
+    /// A field appears more than once in a paragraph.
+    DuplicateField,
+
+    /// The input starts with blank lines.
+    LeadingBlankLine,
+
+    /// The input ends with blank lines.
+    TrailingBlankLine,
+
+    /// The input contains more than one paragraph, but only one is allowed.
+    MultipleParagraphs,
+}
+
+/// A syntax error, along with its location in the source text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PositionedParseError {
+    /// The kind of error.
+    pub kind: ParseErrorKind,
+
+    /// Human-readable description of the error.
+    pub message: String,
+
+    /// The byte range of the offending text.
+    pub range: TextRange,
+
+    /// The line on which the error starts (0-based).
+    pub line: usize,
+
+    /// The column, in characters, at which the error starts (0-based).
+    pub column: usize,
+
+    /// The text of the offending token, if there was one.
+    pub token: Option<String>,
+}
+
+impl PositionedParseError {
+    /// Create an error for the given range of `text`, computing its line and column.
+    pub fn new(
+        text: &str,
+        kind: ParseErrorKind,
+        message: String,
+        range: TextRange,
+        token: Option<String>,
+    ) -> Self {
+        let (line, column) = line_col(text, range.start());
+        Self {
+            kind,
+            message,
+            range,
+            line,
+            column,
+            token,
+        }
+    }
+}
+
+impl std::fmt::Display for PositionedParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for PositionedParseError {}
+
+/// Return the 0-based line and column (in characters) of the given offset in `text`.
+fn line_col(text: &str, offset: TextSize) -> (usize, usize) {
+    let before = &text[..usize::from(offset)];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count())
+}
+
 /// List of encountered syntax errors.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct ParseError(Vec<String>);
+pub struct ParseError(Vec<PositionedParseError>);
+
+impl ParseError {
+    /// Return the individual errors.
+    pub fn errors(&self) -> &[PositionedParseError] {
+        &self.0
+    }
+}
+
+impl From<ParseError> for Vec<PositionedParseError> {
+    fn from(err: ParseError) -> Self {
+        err.0
+    }
+}
 
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -119,22 +234,74 @@ use rowan::GreenNodeBuilder;
 struct Parse {
     green_node: GreenNode,
     #[allow(unused)]
-    errors: Vec<String>,
+    errors: Vec<PositionedParseError>,
 }
 
 fn parse(text: &str) -> Parse {
-    struct Parser {
+    parse_with_options(text, &ParseOptions::default())
+}
+
+/// Returns the errors for the blank lines at the start and end of `text` that `options` do not
+/// allow.
+fn blank_line_errors(text: &str, options: &ParseOptions) -> Vec<PositionedParseError> {
+    let (leading, trailing) = blank_lines(text);
+    let leading = leading
+        .filter(|_| !options.allow_leading_blank_lines)
+        .map(|range| {
+            (
+                ParseErrorKind::LeadingBlankLine,
+                "unexpected blank line at start of input",
+                range,
+            )
+        });
+    let trailing = trailing
+        .filter(|_| !options.allow_trailing_blank_lines)
+        .map(|range| {
+            (
+                ParseErrorKind::TrailingBlankLine,
+                "unexpected blank line at end of input",
+                range,
+            )
+        });
+    leading
+        .into_iter()
+        .chain(trailing)
+        .map(|(kind, message, range)| {
+            PositionedParseError::new(
+                text,
+                kind,
+                message.to_string(),
+                TextRange::new(
+                    TextSize::try_from(range.start).unwrap(),
+                    TextSize::try_from(range.end).unwrap(),
+                ),
+                Some(text[range].to_string()),
+            )
+        })
+        .collect()
+}
+
+fn parse_with_options(text: &str, options: &ParseOptions) -> Parse {
+    struct Parser<'a> {
+        /// the original text, used to compute error positions.
+        text: &'a str,
+        /// the dialect being parsed.
+        options: &'a ParseOptions,
+        /// the names of the fields seen so far in the current paragraph.
+        keys: Vec<String>,
         /// input tokens, including whitespace,
         /// in *reverse* order.
         tokens: Vec<(SyntaxKind, String)>,
+        /// offset of the first unprocessed token.
+        offset: TextSize,
         /// the in-progress tree.
         builder: GreenNodeBuilder<'static>,
         /// the list of syntax errors we've accumulated
         /// so far.
-        errors: Vec<String>,
+        errors: Vec<PositionedParseError>,
     }
 
-    impl Parser {
+    impl Parser<'_> {
         fn parse_entry(&mut self) {
             while self.current() == Some(COMMENT) {
                 self.bump();
@@ -147,39 +314,43 @@ fn parse(text: &str) -> Parse {
                         return;
                     }
                     Some(g) => {
-                        self.builder.start_node(ERROR.into());
-                        self.bump();
-                        self.errors.push(format!("expected newline, got {:?}", g));
-                        self.builder.finish_node();
+                        self.error_token(
+                            ParseErrorKind::ExpectedNewline,
+                            format!("expected newline, got {:?}", g),
+                        );
                     }
                 }
             }
 
+            // Comments at the end of the paragraph are not followed by an entry.
+            if self.current().is_none() || self.current() == Some(NEWLINE) {
+                return;
+            }
+
             self.builder.start_node(ENTRY.into());
 
             // First, parse the key and colon
             if self.current() == Some(KEY) {
                 self.bump();
                 self.skip_ws();
+            } else if self.current() == Some(INDENT) {
+                self.error_token(
+                    ParseErrorKind::StrayContinuationLine,
+                    "expected key, got continuation line".to_string(),
+                );
             } else {
-                self.builder.start_node(ERROR.into());
-                if self.current().is_some() {
-                    self.bump();
-                }
-                self.errors.push("expected key".to_string());
-                self.builder.finish_node();
+                self.error_token(ParseErrorKind::MissingKey, "expected key".to_string());
             }
             if self.current() == Some(COLON) {
                 self.bump();
                 self.skip_ws();
             } else {
-                self.builder.start_node(ERROR.into());
-                if self.current().is_some() {
-                    self.bump();
-                }
-                self.errors
-                    .push(format!("expected ':', got {:?}", self.current()));
-                self.builder.finish_node();
+                // For compatibility, the message names the token following the offending one.
+                let next = self.current().and(self.nth(1));
+                self.error_token(
+                    ParseErrorKind::MissingColon,
+                    format!("expected ':', got {:?}", next),
+                );
             }
             loop {
                 while self.current() == Some(WHITESPACE) || self.current() == Some(VALUE) {
@@ -194,10 +365,22 @@ fn parse(text: &str) -> Parse {
                         self.bump();
                     }
                     Some(g) => {
-                        self.builder.start_node(ERROR.into());
-                        self.bump();
-                        self.errors.push(format!("expected newline, got {:?}", g));
-                        self.builder.finish_node();
+                        self.error_token(
+                            ParseErrorKind::ExpectedNewline,
+                            format!("expected newline, got {:?}", g),
+                        );
+                    }
+                }
+                if self.options.comments == Comments::Anywhere {
+                    // Comment lines may appear between continuation lines.
+                    let mut n = 0;
+                    while self.nth(n) == Some(COMMENT) && self.nth(n + 1) == Some(NEWLINE) {
+                        n += 2;
+                    }
+                    if n > 0 && self.nth(n) == Some(INDENT) {
+                        for _ in 0..n {
+                            self.bump();
+                        }
                     }
                 }
                 if self.current() == Some(INDENT) {
@@ -211,6 +394,7 @@ fn parse(text: &str) -> Parse {
         }
 
         fn parse_paragraph(&mut self) {
+            self.keys.clear();
             self.builder.start_node(PARAGRAPH.into());
             while self.current() != Some(NEWLINE) && self.current().is_some() {
                 self.parse_entry();
@@ -232,20 +416,92 @@ fn parse(text: &str) -> Parse {
             // Close the root node.
             self.builder.finish_node();
 
+            self.errors
+                .extend(blank_line_errors(self.text, self.options));
+
             // Turn the builder into a GreenNode
             Parse {
                 green_node: self.builder.finish(),
                 errors: self.errors,
             }
         }
+        /// Wrap the current token (if any) in an ERROR node and record an error for it.
+        fn error_token(&mut self, kind: ParseErrorKind, message: String) {
+            let start = self.offset;
+            let token = self.tokens.last().map(|(_, t)| t.clone());
+            self.builder.start_node(ERROR.into());
+            if self.current().is_some() {
+                self.bump();
+            }
+            self.builder.finish_node();
+            self.errors.push(PositionedParseError::new(
+                self.text,
+                kind,
+                message,
+                TextRange::new(start, self.offset),
+                token,
+            ));
+        }
+        /// Record an error for the given range, without changing the tree.
+        fn error_at(&mut self, kind: ParseErrorKind, message: String, range: Range<usize>) {
+            let token = self.text[range.clone()].to_string();
+            self.errors.push(PositionedParseError::new(
+                self.text,
+                kind,
+                message,
+                TextRange::new(
+                    TextSize::try_from(range.start).unwrap(),
+                    TextSize::try_from(range.end).unwrap(),
+                ),
+                Some(token),
+            ));
+        }
         /// Advance one token, adding it to the current branch of the tree builder.
         fn bump(&mut self) {
             let (kind, text) = self.tokens.pop().unwrap();
+            let start = usize::from(self.offset);
+            self.offset += TextSize::of(text.as_str());
             self.builder.token(kind.into(), text.as_str());
+            let range = start..usize::from(self.offset);
+            match kind {
+                COMMENT if self.options.comments == Comments::Forbidden => {
+                    self.error_at(
+                        ParseErrorKind::UnexpectedComment,
+                        "comments are not allowed".to_string(),
+                        range,
+                    );
+                }
+                NEWLINE if !self.options.allow_crlf && text.contains('\r') => {
+                    self.error_at(
+                        ParseErrorKind::CarriageReturn,
+                        "unexpected carriage return".to_string(),
+                        range,
+                    );
+                }
+                KEY if self.options.duplicate_fields == DuplicateFields::Error => {
+                    if self.keys.iter().any(|k| common::key_eq(k, &text)) {
+                        self.error_at(
+                            ParseErrorKind::DuplicateField,
+                            format!("duplicate field {}", text),
+                            range,
+                        );
+                    } else {
+                        self.keys.push(text);
+                    }
+                }
+                _ => {}
+            }
         }
         /// Peek at the first unprocessed token
         fn current(&self) -> Option<SyntaxKind> {
-            self.tokens.last().map(|(kind, _)| *kind)
+            self.nth(0)
+        }
+        /// Peek at the unprocessed token `n` tokens ahead.
+        fn nth(&self, n: usize) -> Option<SyntaxKind> {
+            self.tokens
+                .len()
+                .checked_sub(n + 1)
+                .map(|i| self.tokens[i].0)
         }
         fn skip_ws(&mut self) {
             while self.current() == Some(WHITESPACE) || self.current() == Some(COMMENT) {
@@ -269,12 +525,16 @@ fn parse(text: &str) -> Parse {
         }
     }
 
-    let mut tokens = lex(text)
+    let mut tokens = lex_with_options(text, options)
         .map(|(k, t)| (k, t.to_string()))
         .collect::<Vec<_>>();
     tokens.reverse();
     Parser {
+        text,
+        options,
+        keys: Vec::new(),
         tokens,
+        offset: TextSize::from(0),
         builder: GreenNodeBuilder::new(),
         errors: Vec::new(),
     }
@@ -286,7 +546,6 @@ fn parse(text: &str) -> Parse {
 /// It is also immutable, like a GreenNode,
 /// but it contains parent pointers, offsets, and
 /// has identity semantics.
-
 type SyntaxNode = rowan::SyntaxNode<Lang>;
 #[allow(unused)]
 type SyntaxToken = rowan::SyntaxToken<Lang>;
@@ -294,7 +553,6 @@ type SyntaxToken = rowan::SyntaxToken<Lang>;
 type SyntaxElement = rowan::NodeOrToken<SyntaxNode, SyntaxToken>;
 
 impl Parse {
-    #[cfg(test)]
     fn syntax(&self) -> SyntaxNode {
         SyntaxNode::new_root(self.green_node.clone())
     }
@@ -321,6 +579,11 @@ macro_rules! ast_node {
                     None
                 }
             }
+
+            /// Returns the byte range of this node in the source text.
+            pub fn text_range(&self) -> TextRange {
+                self.0.text_range()
+            }
         }
 
         impl AstNode for $ast {
@@ -367,21 +630,26 @@ impl Deb822 {
         Deb822(SyntaxNode::new_root_mut(builder.finish()))
     }
 
+    /// Returns a copy of this file, which can be modified without affecting the original.
+    pub(crate) fn detached(&self) -> Deb822 {
+        Deb822(SyntaxNode::new_root_mut(self.0.green().into_owned()))
+    }
+
     /// Provide a formatter that can handle indentation and trailing separators
     ///
     /// # Arguments
     /// * `control` - The control file to format
     /// * `indentation` - The indentation to use
     /// * `immediate_empty_line` - Whether the value should always start with an empty line. If true,
-    ///                  then the result becomes something like "Field:\n value". This parameter
-    ///                  only applies to the values that will be formatted over more than one line.
+    ///   then the result becomes something like "Field:\n value". This parameter
+    ///   only applies to the values that will be formatted over more than one line.
     /// * `max_line_length_one_liner` - If set, then this is the max length of the value
-    ///                        if it is crammed into a "one-liner" value. If the value(s) fit into
-    ///                        one line, this parameter will overrule immediate_empty_line.
+    ///   if it is crammed into a "one-liner" value. If the value(s) fit into
+    ///   one line, this parameter will overrule immediate_empty_line.
     /// * `sort_paragraphs` - If set, then this function will sort the paragraphs according to the
-    ///                given function.
+    ///   given function.
     /// * `sort_entries` - If set, then this function will sort the entries according to the
-    ///               given function.
+    ///   given function.
     #[must_use]
     pub fn wrap_and_sort(
         &self,
@@ -453,6 +721,20 @@ impl Deb822 {
         self.0.children().filter_map(Paragraph::cast)
     }
 
+    /// Collapse fields that appear more than once in a paragraph, according to `policy`.
+    ///
+    /// See [`Paragraph::collapse_duplicates`]. Returns the names of the fields that were
+    /// collapsed in each paragraph, along with the index of the paragraph.
+    pub fn collapse_duplicates(&mut self, policy: DuplicateFields) -> Vec<(usize, Vec<String>)> {
+        self.paragraphs()
+            .enumerate()
+            .filter_map(|(i, mut p)| {
+                let collapsed = p.collapse_duplicates(policy);
+                (!collapsed.is_empty()).then_some((i, collapsed))
+            })
+            .collect()
+    }
+
     /// Converts the perceptual paragraph index to the node index.
     fn convert_index(&self, index: usize) -> Option<usize> {
         let mut current_pos = 0usize;
@@ -486,6 +768,19 @@ impl Deb822 {
         }
     }
 
+    /// Delete the empty lines directly before `end`.
+    fn delete_preceding_space(&self, end: usize) {
+        let start = self
+            .0
+            .children_with_tokens()
+            .take(end)
+            .collect::<Vec<_>>()
+            .iter()
+            .rposition(|node| node.kind() != EMPTY_LINE)
+            .map_or(0, |i| i + 1);
+        self.0.splice_children(start..end, []);
+    }
+
     /// Shared internal function to insert a new paragraph into the file.
     fn insert_empty_paragraph(&mut self, index: Option<usize>) -> Paragraph {
         let paragraph = Paragraph::new();
@@ -552,10 +847,23 @@ impl Deb822 {
     /// d.remove_paragraph(0);
     /// assert_eq!(d.to_string(), "");
     /// ```
+    ///
+    /// When the last paragraph is removed, the empty lines that separated it from the previous
+    /// paragraph are removed as well:
+    ///
+    /// ```
+    /// use deb822_lossless::Deb822;
+    /// let mut d: Deb822 = "Foo: Bar\n\nA: B\n".parse().unwrap();
+    /// d.remove_paragraph(1);
+    /// assert_eq!(d.to_string(), "Foo: Bar\n");
+    /// ```
     pub fn remove_paragraph(&mut self, index: usize) {
         if let Some(index) = self.convert_index(index) {
             self.0.splice_children(index..index + 1, []);
             self.delete_trailing_space(index);
+            if index == self.0.children_with_tokens().count() {
+                self.delete_preceding_space(index);
+            }
         }
     }
 
@@ -573,18 +881,46 @@ impl Deb822 {
     /// Read a deb822 file from the given path, ignoring any syntax errors.
     pub fn from_file_relaxed(
         path: impl AsRef<Path>,
-    ) -> Result<(Self, Vec<String>), std::io::Error> {
+    ) -> Result<(Self, Vec<PositionedParseError>), std::io::Error> {
         let text = std::fs::read_to_string(path)?;
         Ok(Self::from_str_relaxed(&text))
     }
 
     /// Parse a deb822 file from a string, allowing syntax errors.
-    pub fn from_str_relaxed(s: &str) -> (Self, Vec<String>) {
+    pub fn from_str_relaxed(s: &str) -> (Self, Vec<PositionedParseError>) {
         let parsed = parse(s);
         (parsed.root_mut(), parsed.errors)
     }
 
+    /// Parse a deb822 file from a string, using the rules of a particular dialect.
+    ///
+    /// If `options` asks for duplicate fields to be collapsed, the returned tree no longer
+    /// matches the input exactly.
+    pub fn from_str_with_options(s: &str, options: &ParseOptions) -> Result<Self, ParseError> {
+        let (deb822, errors) = Self::from_str_relaxed_with_options(s, options);
+        if errors.is_empty() {
+            Ok(deb822)
+        } else {
+            Err(ParseError(errors))
+        }
+    }
+
+    /// Parse a deb822 file from a string using the rules of a particular dialect, allowing
+    /// syntax errors.
+    pub fn from_str_relaxed_with_options(
+        s: &str,
+        options: &ParseOptions,
+    ) -> (Self, Vec<PositionedParseError>) {
+        let parsed = parse_with_options(s, options);
+        let mut deb822 = parsed.root_mut();
+        deb822.collapse_duplicates(options.duplicate_fields);
+        (deb822, parsed.errors)
+    }
+
     /// Read a deb822 file from a Read object.
+    ///
+    /// This reads the entire input into memory; see [`crate::stream::LosslessParagraphs`] for
+    /// processing large files one paragraph at a time.
     pub fn read<R: std::io::Read>(mut r: R) -> Result<Self, Error> {
         let mut buf = String::new();
         r.read_to_string(&mut buf)?;
@@ -592,13 +928,216 @@ impl Deb822 {
     }
 
     /// Read a deb822 file from a Read object, allowing syntax errors.
-    pub fn read_relaxed<R: std::io::Read>(mut r: R) -> Result<(Self, Vec<String>), std::io::Error> {
+    pub fn read_relaxed<R: std::io::Read>(
+        mut r: R,
+    ) -> Result<(Self, Vec<PositionedParseError>), std::io::Error> {
         let mut buf = String::new();
         r.read_to_string(&mut buf)?;
         Ok(Self::from_str_relaxed(&buf))
     }
+
+    /// Apply a text edit, reparsing only the paragraphs it affects.
+    ///
+    /// `errors` are the syntax errors of this document, as returned by
+    /// [`Deb822::from_str_relaxed`] or an earlier call to this function. The returned errors
+    /// are those of the edited document: errors outside the reparsed paragraphs are moved
+    /// along with the text, and errors inside them are replaced by those found while
+    /// reparsing. The green nodes of the paragraphs that are not affected by the edit are
+    /// shared with this document.
+    ///
+    /// The result is the same as parsing the edited text from scratch.
+    ///
+    /// # Errors
+    /// Returns [`InvalidTextEdit`] if the range of the edit is out of bounds or does not lie on
+    /// character boundaries, in which case nothing is reparsed.
+    ///
+    /// # Example
+    /// ```
+    /// use deb822_lossless::lossless::TextEdit;
+    /// use deb822_lossless::{Deb822, TextRange};
+    ///
+    /// let text = "Source: foo\n\nPackage: foo\n";
+    /// let (deb822, errors) = Deb822::from_str_relaxed(text);
+    /// let edit = TextEdit::new(TextRange::new(22.into(), 25.into()), "bar");
+    /// let (deb822, errors) = deb822.reparse(&errors, &edit).unwrap();
+    /// assert!(errors.is_empty());
+    /// assert_eq!(deb822.to_string(), "Source: foo\n\nPackage: bar\n");
+    /// ```
+    pub fn reparse(
+        &self,
+        errors: &[PositionedParseError],
+        edit: &TextEdit,
+    ) -> Result<(Deb822, Vec<PositionedParseError>), InvalidTextEdit> {
+        self.reparse_with_options(errors, edit, &ParseOptions::default())
+    }
+
+    /// Apply a text edit, reparsing only the paragraphs it affects using the rules of a
+    /// particular dialect.
+    ///
+    /// `options` should be the options the document was parsed with, e.g. by
+    /// [`Deb822::from_str_relaxed_with_options`]; the result is then the same as parsing the
+    /// edited text from scratch with those options. See [`Deb822::reparse`].
+    pub fn reparse_with_options(
+        &self,
+        errors: &[PositionedParseError],
+        edit: &TextEdit,
+        options: &ParseOptions,
+    ) -> Result<(Deb822, Vec<PositionedParseError>), InvalidTextEdit> {
+        let old_text = self.to_string();
+        let text = edit.apply(&old_text)?;
+        let children = self.0.children_with_tokens().collect::<Vec<_>>();
+        let start = usize::from(edit.range.start());
+        let end = usize::from(edit.range.end());
+        let delta = text.len() as isize - old_text.len() as isize;
+
+        // Reparse the children touched by the edit. The reparsed region starts after a line
+        // that is not part of a paragraph, and ends with a blank line that is not part of a
+        // paragraph either; from there on, the text is parsed as before.
+        let is_empty_line = |i: usize| children[i].kind() == EMPTY_LINE;
+        let mut lo = children
+            .iter()
+            .position(|c| usize::from(c.text_range().end()) >= start)
+            .unwrap_or(children.len());
+        let mut hi = children
+            .iter()
+            .rposition(|c| usize::from(c.text_range().start()) <= end)
+            .map_or(lo, |i| i + 1)
+            .max(lo);
+        while lo > 0 && (!is_empty_line(lo - 1) || children[lo - 1].to_string().ends_with('\r')) {
+            lo -= 1;
+        }
+        let region_start = children
+            .get(lo)
+            .map_or(old_text.len(), |c| usize::from(c.text_range().start()));
+        // Blank lines at the start and end of the document are checked for the whole text
+        // below, rather than for the reparsed region.
+        let region_options = ParseOptions {
+            allow_leading_blank_lines: true,
+            allow_trailing_blank_lines: true,
+            ..options.clone()
+        };
+        let parse = |text: &str| parse_with_options(text, &region_options);
+        let parsed =
+            loop {
+                let Some(child) = children.get(hi) else {
+                    break parse(&text[region_start..]);
+                };
+                hi += 1;
+                let blank_line = child.to_string();
+                if child.kind() != EMPTY_LINE || !blank_line.chars().all(common::is_newline) {
+                    continue;
+                }
+                let region_end = (usize::from(child.text_range().end()) as isize + delta) as usize;
+                let parsed = parse(&text[region_start..region_end]);
+                if parsed.syntax().last_child().is_some_and(|last| {
+                    last.kind() == EMPTY_LINE && last.text() == blank_line.as_str()
+                }) {
+                    break parsed;
+                }
+            };
+        let old_region_end = children
+            .get(hi)
+            .map_or(old_text.len(), |c| usize::from(c.text_range().start()));
+        let green = self.0.green().splice_children(
+            lo..hi,
+            parsed
+                .green_node
+                .children()
+                .map(|c| c.to_owned())
+                .collect::<Vec<_>>(),
+        );
+
+        let offset = |e: &PositionedParseError, by: isize| {
+            let range = TextRange::new(
+                TextSize::from((usize::from(e.range.start()) as isize + by) as u32),
+                TextSize::from((usize::from(e.range.end()) as isize + by) as u32),
+            );
+            PositionedParseError::new(&text, e.kind, e.message.clone(), range, e.token.clone())
+        };
+        let in_region = |e: &PositionedParseError| {
+            let start = usize::from(e.range.start());
+            (region_start..old_region_end).contains(&start)
+                || (start == old_text.len() && old_region_end == old_text.len())
+        };
+        let is_blank_line_error = |e: &&PositionedParseError| {
+            matches!(
+                e.kind,
+                ParseErrorKind::LeadingBlankLine | ParseErrorKind::TrailingBlankLine
+            )
+        };
+        let mut new_errors = errors
+            .iter()
+            .filter(|e| !is_blank_line_error(e))
+            .filter(|e| usize::from(e.range.start()) < region_start)
+            .cloned()
+            .collect::<Vec<_>>();
+        new_errors.extend(
+            parsed
+                .errors
+                .iter()
+                .map(|e| offset(e, region_start as isize)),
+        );
+        new_errors.extend(
+            errors
+                .iter()
+                .filter(|e| !is_blank_line_error(e))
+                .filter(|e| usize::from(e.range.start()) >= region_start && !in_region(e))
+                .map(|e| offset(e, delta)),
+        );
+        new_errors.extend(blank_line_errors(&text, options));
+
+        let mut deb822 = Deb822(SyntaxNode::new_root_mut(green));
+        deb822.collapse_duplicates(options.duplicate_fields);
+        Ok((deb822, new_errors))
+    }
+}
+
+/// A change to the text of a document: the text in `range` is replaced by `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    /// The byte range of the text to replace.
+    pub range: TextRange,
+
+    /// The text to insert in its place.
+    pub replacement: String,
+}
+
+impl TextEdit {
+    /// Create a new text edit.
+    pub fn new(range: TextRange, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Apply the edit to `text`.
+    ///
+    /// Fails if the range of the edit is out of bounds or does not lie on character boundaries.
+    pub fn apply(&self, text: &str) -> Result<String, InvalidTextEdit> {
+        let range = usize::from(self.range.start())..usize::from(self.range.end());
+        if !text.is_char_boundary(range.start) || !text.is_char_boundary(range.end) {
+            return Err(InvalidTextEdit(self.range));
+        }
+        let mut text = text.to_string();
+        text.replace_range(range, &self.replacement);
+        Ok(text)
+    }
 }
 
+/// The range of a [`TextEdit`] is out of bounds or does not lie on character boundaries of
+/// the text it is applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidTextEdit(pub TextRange);
+
+impl std::fmt::Display for InvalidTextEdit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "invalid range for text edit: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTextEdit {}
+
 fn inject(builder: &mut GreenNodeBuilder, node: SyntaxNode) {
     builder.start_node(node.kind().into());
     for child in node.children_with_tokens() {
@@ -614,6 +1153,25 @@ fn inject(builder: &mut GreenNodeBuilder, node: SyntaxNode) {
     builder.finish_node();
 }
 
+/// Add a single node or token to the builder.
+fn inject_element(builder: &mut GreenNodeBuilder, element: SyntaxElement) {
+    match element {
+        rowan::NodeOrToken::Node(node) => inject(builder, node),
+        rowan::NodeOrToken::Token(token) => builder.token(token.kind().into(), token.text()),
+    }
+}
+
+/// Split the given comments into lines, making sure that each starts with `#`.
+fn comment_lines<'a>(comments: &'a [&'a str]) -> impl Iterator<Item = String> + 'a {
+    comments.iter().flat_map(|c| c.lines()).map(|line| {
+        if line.starts_with('#') {
+            line.to_string()
+        } else {
+            format!("# {}", line)
+        }
+    })
+}
+
 impl FromIterator<Paragraph> for Deb822 {
     fn from_iter<T: IntoIterator<Item = Paragraph>>(iter: T) -> Self {
         let mut builder = GreenNodeBuilder::new();
@@ -699,15 +1257,20 @@ impl Paragraph {
         Paragraph(SyntaxNode::new_root_mut(builder.finish()))
     }
 
+    /// Returns a copy of this paragraph that is not part of any file.
+    pub(crate) fn detached(&self) -> Paragraph {
+        Paragraph(SyntaxNode::new_root_mut(self.0.green().into_owned()))
+    }
+
     /// Reformat this paragraph
     ///
     /// # Arguments
     /// * `indentation` - The indentation to use
     /// * `immediate_empty_line` - Whether multi-line values should always start with an empty line
     /// * `max_line_length_one_liner` - If set, then this is the max length of the value if it is
-    ///     crammed into a "one-liner" value
+    ///   crammed into a "one-liner" value
     /// * `sort_entries` - If set, then this function will sort the entries according to the given
-    /// function
+    ///   function
     /// * `format_value` - If set, then this function will format the value according to the given
     ///   function
     #[must_use]
@@ -731,7 +1294,9 @@ impl Paragraph {
                     entries.push((current, Entry::cast(c.as_node().unwrap().clone()).unwrap()));
                     current = vec![];
                 }
-                ERROR | COMMENT => {
+                // Comments (and the newlines that terminate them) stay with the entry they
+                // precede.
+                ERROR | COMMENT | NEWLINE => {
                     current.push(c);
                 }
                 _ => {}
@@ -748,7 +1313,7 @@ impl Paragraph {
 
         for (pre, entry) in entries.into_iter() {
             for c in pre.into_iter() {
-                builder.token(c.kind().into(), c.as_token().unwrap().text());
+                inject_element(&mut builder, c);
             }
 
             inject(
@@ -765,30 +1330,107 @@ impl Paragraph {
         }
 
         for c in current {
-            builder.token(c.kind().into(), c.as_token().unwrap().text());
+            inject_element(&mut builder, c);
         }
 
         builder.finish_node();
         Self(SyntaxNode::new_root_mut(builder.finish()))
     }
 
+    /// Returns all comment lines within the paragraph, including the leading `#`.
+    ///
+    /// This includes the comments above each field, but not the comments directly above the
+    /// paragraph itself; see [`Paragraph::leading_comments`] for those.
+    pub fn comments(&self) -> Vec<String> {
+        self.0
+            .children_with_tokens()
+            .filter_map(|c| c.into_token())
+            .filter(|t| t.kind() == COMMENT)
+            .map(|t| t.text().to_string())
+            .collect()
+    }
+
+    /// Returns the comment lines directly above the paragraph, including the leading `#`.
+    ///
+    /// Comments that are separated from the paragraph by an empty line are not included.
+    pub fn leading_comments(&self) -> Vec<String> {
+        self.leading_comment_lines()
+            .iter()
+            .flat_map(|line| line.children_with_tokens())
+            .filter_map(|c| c.into_token())
+            .filter(|t| t.kind() == COMMENT)
+            .map(|t| t.text().to_string())
+            .collect()
+    }
+
+    /// Returns the `EMPTY_LINE` nodes holding the comments directly above the paragraph.
+    fn leading_comment_lines(&self) -> Vec<SyntaxNode> {
+        let mut lines = vec![];
+        let mut prev = self.0.prev_sibling();
+        while let Some(node) = prev {
+            if node.kind() != EMPTY_LINE
+                || !node.children_with_tokens().any(|c| c.kind() == COMMENT)
+            {
+                break;
+            }
+            prev = node.prev_sibling();
+            lines.push(node);
+        }
+        lines.reverse();
+        lines
+    }
+
+    /// Replace the comments directly above the paragraph.
+    ///
+    /// Lines that don't start with `#` are prefixed with `# `. Passing an empty slice removes
+    /// the existing comments. This has no effect if the paragraph is not part of a file.
+    pub fn set_leading_comments(&mut self, comments: &[&str]) {
+        let Some(parent) = self.0.parent() else {
+            return;
+        };
+        let lines = self.leading_comment_lines();
+        let start = lines.first().map_or(self.0.index(), |line| line.index());
+        for line in lines {
+            line.detach();
+        }
+        let new_lines = comment_lines(comments)
+            .map(|line| {
+                let mut builder = GreenNodeBuilder::new();
+                builder.start_node(EMPTY_LINE.into());
+                builder.token(COMMENT.into(), &line);
+                builder.token(NEWLINE.into(), "\n");
+                builder.finish_node();
+                SyntaxNode::new_root_mut(builder.finish()).into()
+            })
+            .collect::<Vec<SyntaxElement>>();
+        parent.splice_children(start..start, new_lines);
+    }
+
     /// Returns the value of the given key in the paragraph.
+    ///
+    /// Keys are compared case-insensitively, as field names in deb822 files are not
+    /// case-sensitive.
     pub fn get(&self, key: &str) -> Option<String> {
         self.entries()
-            .find(|e| e.key().as_deref() == Some(key))
+            .find(|e| e.key_matches(key))
             .map(|e| e.value())
     }
 
-    /// Returns whether the paragraph contains the given key.
+    /// Returns whether the paragraph contains the given key (compared case-insensitively).
     pub fn contains_key(&self, key: &str) -> bool {
         self.get(key).is_some()
     }
 
     /// Returns an iterator over all entries in the paragraph.
-    fn entries(&self) -> impl Iterator<Item = Entry> + '_ {
+    pub fn entries(&self) -> impl Iterator<Item = Entry> + '_ {
         self.0.children().filter_map(Entry::cast)
     }
 
+    /// Returns the entry for the given key (compared case-insensitively).
+    pub fn get_entry(&self, key: &str) -> Option<Entry> {
+        self.entries().find(|e| e.key_matches(key))
+    }
+
     /// Returns an iterator over all items in the paragraph.
     pub fn items(&self) -> impl Iterator<Item = (String, String)> + '_ {
         self.entries()
@@ -797,8 +1439,9 @@ impl Paragraph {
 
     /// Returns an iterator over all values for the given key in the paragraph.
     pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = String> + 'a {
-        self.items()
-            .filter_map(move |(k, v)| if k.as_str() == key { Some(v) } else { None })
+        self.entries()
+            .filter(move |e| e.key_matches(key))
+            .map(|e| e.value())
     }
 
     /// Returns an iterator over all keys in the paragraph.
@@ -806,10 +1449,97 @@ impl Paragraph {
         self.entries().filter_map(|e| e.key())
     }
 
+    /// Returns the fields that appear more than once in the paragraph.
+    ///
+    /// Field names are compared case-insensitively. The fields are returned in the order in
+    /// which they first appear.
+    ///
+    /// # Example
+    /// ```
+    /// use deb822_lossless::Paragraph;
+    /// let p: Paragraph = "Package: foo\nDepends: bar\nDepends: blah\n".parse().unwrap();
+    /// let duplicates = p.duplicate_fields();
+    /// assert_eq!(duplicates.len(), 1);
+    /// assert_eq!(duplicates[0].name, "Depends");
+    /// assert_eq!(duplicates[0].entries.len(), 2);
+    /// ```
+    pub fn duplicate_fields(&self) -> Vec<DuplicateField> {
+        let mut fields: Vec<DuplicateField> = Vec::new();
+        for entry in self.entries() {
+            let Some(key) = entry.key() else {
+                continue;
+            };
+            match fields.iter_mut().find(|f| common::key_eq(&f.name, &key)) {
+                Some(field) => field.entries.push(entry),
+                None => fields.push(DuplicateField {
+                    name: key,
+                    entries: vec![entry],
+                }),
+            }
+        }
+        fields.retain(|f| f.entries.len() > 1);
+        fields
+    }
+
+    /// Collapse fields that appear more than once in the paragraph, according to `policy`.
+    ///
+    /// With [`DuplicateFields::Allow`] and [`DuplicateFields::Error`] the paragraph is left
+    /// alone. Comments attached to removed fields are kept.
+    ///
+    /// # Returns
+    /// The names of the fields that were collapsed.
+    ///
+    /// # Example
+    /// ```
+    /// use deb822_lossless::dialect::DuplicateFields;
+    /// use deb822_lossless::Paragraph;
+    /// let mut p: Paragraph = "Package: foo\nDepends: bar\nDepends: blah\n".parse().unwrap();
+    /// p.collapse_duplicates(DuplicateFields::MergeAsList);
+    /// assert_eq!(p.to_string(), "Package: foo\nDepends: bar, blah\n");
+    /// ```
+    pub fn collapse_duplicates(&mut self, policy: DuplicateFields) -> Vec<String> {
+        if matches!(policy, DuplicateFields::Allow | DuplicateFields::Error) {
+            return vec![];
+        }
+        let mut collapsed = vec![];
+        for field in self.duplicate_fields() {
+            let mut entries = field.entries;
+            match policy {
+                DuplicateFields::KeepFirst => {
+                    for mut entry in entries.drain(1..) {
+                        entry.detach();
+                    }
+                }
+                DuplicateFields::KeepLast => {
+                    let count = entries.len();
+                    for mut entry in entries.drain(..count - 1) {
+                        entry.detach();
+                    }
+                }
+                DuplicateFields::MergeAsList => {
+                    let value = entries
+                        .iter()
+                        .map(|e| e.value())
+                        .reduce(|a, b| merge_values(&a, &b))
+                        .unwrap_or_default();
+                    for mut entry in entries.drain(1..) {
+                        entry.detach();
+                    }
+                    self.set(&field.name, &value);
+                }
+                DuplicateFields::Allow | DuplicateFields::Error => unreachable!(),
+            }
+            collapsed.push(field.name);
+        }
+        collapsed
+    }
+
     /// Remove the given field from the paragraph.
+    ///
+    /// All fields whose name matches `key` case-insensitively are removed.
     pub fn remove(&mut self, key: &str) {
-        for mut entry in self.entries() {
-            if entry.key().as_deref() == Some(key) {
+        for mut entry in self.entries().collect::<Vec<_>>() {
+            if entry.key_matches(key) {
                 entry.detach();
             }
         }
@@ -822,12 +1552,113 @@ impl Paragraph {
         self.0.splice_children(count..count, vec![entry.0.into()]);
     }
 
+    /// Insert a new field directly before the field `before`.
+    ///
+    /// `before` is compared case-insensitively. The new field is placed above any comments
+    /// attached to `before`.
+    ///
+    /// # Returns
+    /// `false` if there is no field named `before`, in which case nothing is inserted.
+    pub fn insert_before(&mut self, before: &str, key: &str, value: &str) -> bool {
+        let Some(entry) = self.get_entry(before) else {
+            return false;
+        };
+        let index = entry.insertion_index();
+        self.0
+            .splice_children(index..index, vec![Entry::new(key, value).0.into()]);
+        true
+    }
+
+    /// Insert a new field directly after the field `after`.
+    ///
+    /// `after` is compared case-insensitively.
+    ///
+    /// # Returns
+    /// `false` if there is no field named `after`, in which case nothing is inserted.
+    pub fn insert_after(&mut self, after: &str, key: &str, value: &str) -> bool {
+        let Some(entry) = self.get_entry(after) else {
+            return false;
+        };
+        let index = entry.0.index() + 1;
+        self.0
+            .splice_children(index..index, vec![Entry::new(key, value).0.into()]);
+        true
+    }
+
+    /// Set a field in the paragraph, placing new fields according to a canonical field order.
+    ///
+    /// If the field already exists, it is updated in place as with [`Paragraph::set`].
+    /// Otherwise, it is inserted before the first existing field that comes after it in
+    /// `field_order`, or after the last existing field that comes before it. Fields that are
+    /// not listed in `field_order` are appended at the end of the paragraph.
+    ///
+    /// # Example
+    /// ```
+    /// use deb822_lossless::Paragraph;
+    /// let mut p: Paragraph = "Source: foo\nMaintainer: Joe <joe@example.com>\nHomepage: https://example.com/\n"
+    ///     .parse()
+    ///     .unwrap();
+    /// p.set_with_field_order("Section", "net", &["Source", "Section", "Maintainer", "Homepage"]);
+    /// assert_eq!(
+    ///     p.to_string(),
+    ///     "Source: foo\nSection: net\nMaintainer: Joe <joe@example.com>\nHomepage: https://example.com/\n"
+    /// );
+    /// ```
+    pub fn set_with_field_order(&mut self, key: &str, value: &str, field_order: &[&str]) {
+        if self.contains_key(key) {
+            self.set(key, value);
+            return;
+        }
+        let position = |key: &str| field_order.iter().position(|f| common::key_eq(f, key));
+        let Some(key_position) = position(key) else {
+            self.insert(key, value);
+            return;
+        };
+        let positions = self
+            .entries()
+            .filter_map(|e| e.key().and_then(|k| position(&k)).map(|p| (e, p)))
+            .collect::<Vec<_>>();
+        let index = if let Some((next, _)) = positions.iter().find(|(_, p)| *p > key_position) {
+            next.insertion_index()
+        } else if let Some((prev, _)) = positions.last() {
+            prev.0.index() + 1
+        } else {
+            self.0.children_with_tokens().count()
+        };
+        self.0
+            .splice_children(index..index, vec![Entry::new(key, value).0.into()]);
+    }
+
     /// Set a field in the paragraph
+    ///
+    /// If a field whose name matches `key` case-insensitively already exists, its value is
+    /// replaced and its original spelling is kept.
     pub fn set(&mut self, key: &str, value: &str) {
-        let new_entry = Entry::new(key, value);
+        self.set_entry(key, |key| Entry::new(key, value));
+    }
+
+    /// Returns the value of the given key, split into its logical lines.
+    ///
+    /// Keys are compared case-insensitively.
+    pub fn get_multiline(&self, key: &str) -> Option<MultiLineValue> {
+        self.entries()
+            .find(|e| e.key_matches(key))
+            .map(|e| e.multiline_value())
+    }
 
+    /// Set a field in the paragraph from its logical lines.
+    ///
+    /// Unlike [`Paragraph::set`], blank lines are written as " ." and any indentation of the
+    /// continuation lines is preserved. If the field already exists, its original spelling is kept.
+    pub fn set_multiline(&mut self, key: &str, value: &MultiLineValue) {
+        self.set_entry(key, |key| Entry::new_multiline(key, value));
+    }
+
+    /// Replace the entry for `key`, or append a new one if it does not exist yet.
+    fn set_entry(&mut self, key: &str, new_entry: impl FnOnce(&str) -> Entry) {
         for entry in self.entries() {
-            if entry.key().as_deref() == Some(key) {
+            if let Some(existing_key) = entry.key().filter(|k| common::key_eq(k, key)) {
+                let new_entry = new_entry(&existing_key);
                 self.0.splice_children(
                     entry.0.index()..entry.0.index() + 1,
                     vec![new_entry.0.into()],
@@ -835,24 +1666,69 @@ impl Paragraph {
                 return;
             }
         }
+        let new_entry = new_entry(key);
         let count = self.0.children_with_tokens().count();
         self.0
             .splice_children(count..count, vec![new_entry.0.into()]);
     }
 
     /// Rename the given field in the paragraph.
+    ///
+    /// `old_key` is compared case-insensitively. The value and its formatting are preserved.
     pub fn rename(&mut self, old_key: &str, new_key: &str) -> bool {
         for entry in self.entries() {
-            if entry.key().as_deref() == Some(old_key) {
+            if entry.key_matches(old_key) {
                 self.0.splice_children(
                     entry.0.index()..entry.0.index() + 1,
-                    vec![Entry::new(new_key, entry.value().as_str()).0.into()],
+                    vec![entry.with_key(new_key).0.into()],
                 );
                 return true;
             }
         }
         false
     }
+
+    /// Returns the fields whose name only differs in case from one of `known_fields`.
+    ///
+    /// Each item consists of the name as it appears in the paragraph and the expected spelling.
+    /// This is useful for callers that want to be strict about the spelling of field names,
+    /// since all other lookups ignore case.
+    pub fn wrong_case_fields<'a>(&self, known_fields: &[&'a str]) -> Vec<(String, &'a str)> {
+        self.keys()
+            .filter_map(|key| {
+                known_fields
+                    .iter()
+                    .find(|known| common::key_eq(known, &key) && **known != key)
+                    .map(|known| (key, *known))
+            })
+            .collect()
+    }
+
+    /// Renames fields whose name only differs in case from one of `known_fields` to the
+    /// expected spelling.
+    ///
+    /// The values and formatting of the renamed fields are preserved.
+    ///
+    /// # Returns
+    /// The fields that were renamed, as returned by [`Paragraph::wrong_case_fields`].
+    pub fn normalize_field_case<'a>(&mut self, known_fields: &[&'a str]) -> Vec<(String, &'a str)> {
+        let mut renamed = vec![];
+        for entry in self.entries().collect::<Vec<_>>() {
+            let Some(key) = entry.key() else {
+                continue;
+            };
+            if let Some(known) = known_fields
+                .iter()
+                .find(|known| common::key_eq(known, &key) && **known != key)
+            {
+                let index = entry.0.index();
+                self.0
+                    .splice_children(index..index + 1, vec![entry.with_key(known).0.into()]);
+                renamed.push((key, *known));
+            }
+        }
+        renamed
+    }
 }
 
 impl Default for Paragraph {
@@ -869,9 +1745,15 @@ impl std::str::FromStr for Paragraph {
 
         let mut paragraphs = deb822.paragraphs();
 
-        paragraphs
-            .next()
-            .ok_or_else(|| ParseError(vec!["no paragraphs".to_string()]))
+        paragraphs.next().ok_or_else(|| {
+            ParseError(vec![PositionedParseError::new(
+                text,
+                ParseErrorKind::NoParagraphs,
+                "no paragraphs".to_string(),
+                TextRange::empty(TextSize::of(text)),
+                None,
+            )])
+        })
     }
 }
 
@@ -894,11 +1776,21 @@ impl pyo3::FromPyObject<'_> for Paragraph {
     fn extract_bound(obj: &pyo3::Bound<pyo3::PyAny>) -> pyo3::PyResult<Self> {
         use pyo3::prelude::*;
         let d = obj.call_method0("__str__")?.extract::<String>()?;
-        Ok(Paragraph::from_str(&d)
-            .map_err(|e| pyo3::exceptions::PyValueError::new_err((e.to_string(),)))?)
+        Paragraph::from_str(&d)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err((e.to_string(),)))
     }
 }
 
+/// A field that appears more than once in a paragraph.
+#[derive(Debug)]
+pub struct DuplicateField {
+    /// The name of the field, as spelled in its first occurrence.
+    pub name: String,
+
+    /// All occurrences of the field, in order.
+    pub entries: Vec<Entry>,
+}
+
 impl Entry {
     /// Create a new entry with the given key and value.
     pub fn new(key: &str, value: &str) -> Entry {
@@ -912,7 +1804,39 @@ impl Entry {
             if i > 0 {
                 builder.token(INDENT.into(), " ");
             }
-            builder.token(VALUE.into(), line);
+            builder.token(VALUE.into(), line);
+            builder.token(NEWLINE.into(), "\n");
+        }
+        builder.finish_node();
+        Entry(SyntaxNode::new_root_mut(builder.finish()))
+    }
+
+    /// Create a new entry with the given key and a value consisting of multiple lines.
+    ///
+    /// Blank lines are written as " .", and any indentation of the continuation lines beyond
+    /// the leading space is preserved.
+    pub fn new_multiline(key: &str, value: &MultiLineValue) -> Entry {
+        let mut builder = GreenNodeBuilder::new();
+
+        builder.start_node(ENTRY.into());
+        builder.token(KEY.into(), key);
+        builder.token(COLON.into(), ":");
+        if !value.first_line.is_empty() {
+            builder.token(WHITESPACE.into(), " ");
+            builder.token(VALUE.into(), &value.first_line);
+        }
+        builder.token(NEWLINE.into(), "\n");
+        for line in &value.lines {
+            let text = line.as_str();
+            let rest = text.trim_start_matches(common::is_indent);
+            if rest.is_empty() {
+                builder.token(INDENT.into(), " ");
+                builder.token(VALUE.into(), ".");
+            } else {
+                let indent = format!(" {}", &text[..text.len() - rest.len()]);
+                builder.token(INDENT.into(), &indent);
+                builder.token(VALUE.into(), rest);
+            }
             builder.token(NEWLINE.into(), "\n");
         }
         builder.finish_node();
@@ -926,9 +1850,9 @@ impl Entry {
     /// * `indentation` - The indentation to use
     /// * `immediate_empty_line` - Whether multi-line values should always start with an empty line
     /// * `max_line_length_one_liner` - If set, then this is the max length of the value if it is
-    ///    crammed into a "one-liner" value
+    ///   crammed into a "one-liner" value
     /// * `format_value` - If set, then this function will format the value according to the given
-    ///    function
+    ///   function
     ///
     /// # Returns
     /// The reformatted entry
@@ -1034,6 +1958,109 @@ impl Entry {
             .map(|it| it.text().to_string())
     }
 
+    /// Returns the byte range of the key of this entry in the source text.
+    pub fn key_range(&self) -> Option<TextRange> {
+        self.0
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|it| it.kind() == KEY)
+            .map(|it| it.text_range())
+    }
+
+    /// Returns the comment lines directly above this entry, including the leading `#`.
+    ///
+    /// Comments above the first field of a paragraph are usually attached to the paragraph
+    /// instead; see [`Paragraph::leading_comments`].
+    pub fn leading_comments(&self) -> Vec<String> {
+        self.leading_comment_tokens()
+            .into_iter()
+            .filter(|t| t.kind() == COMMENT)
+            .map(|t| t.text().to_string())
+            .collect()
+    }
+
+    /// Returns the index in the paragraph at which new entries should be inserted to end up
+    /// before this entry and its comments.
+    fn insertion_index(&self) -> usize {
+        self.leading_comment_tokens()
+            .first()
+            .map_or(self.0.index(), |token| token.index())
+    }
+
+    /// Returns the comment and newline tokens directly above this entry.
+    fn leading_comment_tokens(&self) -> Vec<SyntaxToken> {
+        let mut tokens = vec![];
+        let mut prev = self.0.prev_sibling_or_token();
+        while let Some(rowan::NodeOrToken::Token(token)) = prev {
+            if token.kind() != COMMENT && token.kind() != NEWLINE {
+                break;
+            }
+            prev = token.prev_sibling_or_token();
+            tokens.push(token);
+        }
+        tokens.reverse();
+        tokens
+    }
+
+    /// Replace the comments directly above this entry.
+    ///
+    /// Lines that don't start with `#` are prefixed with `# `. Passing an empty slice removes
+    /// the existing comments. This has no effect if the entry is not part of a paragraph.
+    pub fn set_leading_comments(&mut self, comments: &[&str]) {
+        let Some(parent) = self.0.parent() else {
+            return;
+        };
+        let start = self.insertion_index();
+        for token in self.leading_comment_tokens() {
+            token.detach();
+        }
+
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(PARAGRAPH.into());
+        for line in comment_lines(comments) {
+            builder.token(COMMENT.into(), &line);
+            builder.token(NEWLINE.into(), "\n");
+        }
+        builder.finish_node();
+        let new_tokens = SyntaxNode::new_root_mut(builder.finish())
+            .children_with_tokens()
+            .collect::<Vec<_>>();
+        for token in &new_tokens {
+            token.detach();
+        }
+        parent.splice_children(start..start, new_tokens);
+    }
+
+    /// Returns whether the key of this entry matches `key`, ignoring case.
+    fn key_matches(&self, key: &str) -> bool {
+        self.key().is_some_and(|k| common::key_eq(&k, key))
+    }
+
+    /// Returns a copy of this entry with the key replaced, keeping the value as-is.
+    fn with_key(&self, key: &str) -> Entry {
+        let green = self.0.green();
+        let green = match green.children().position(|c| c.kind() == KEY.into()) {
+            Some(index) => {
+                green.replace_child(index, rowan::GreenToken::new(KEY.into(), key).into())
+            }
+            None => green.into_owned(),
+        };
+        Entry(SyntaxNode::new_root_mut(green))
+    }
+
+    /// Returns the byte range of the value of this entry in the source text, from the start of
+    /// its first line to the end of its last line.
+    pub fn value_range(&self) -> Option<TextRange> {
+        let mut values = self
+            .0
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| it.kind() == VALUE)
+            .map(|it| it.text_range());
+        let first = values.next()?;
+        Some(values.fold(first, |range, it| range.cover(it)))
+    }
+
     /// Returns the value of the entry.
     pub fn value(&self) -> String {
         self.0
@@ -1045,6 +2072,40 @@ impl Entry {
             .join("\n")
     }
 
+    /// Returns the value of the entry, split into its logical lines.
+    ///
+    /// Unlike [`Entry::value`], this decodes " ." lines as blank lines and preserves any
+    /// indentation of continuation lines beyond the first character.
+    pub fn multiline_value(&self) -> MultiLineValue {
+        let mut first_line = String::new();
+        let mut lines = vec![];
+        let mut current: Option<String> = None;
+        for token in self
+            .0
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+        {
+            match token.kind() {
+                INDENT => {
+                    if let Some(line) = current.take() {
+                        lines.push(ContinuationLine::decode(&line));
+                    }
+                    // The first indentation character only marks the continuation line.
+                    current = Some(token.text()[1..].to_string());
+                }
+                VALUE => match current.as_mut() {
+                    Some(line) => line.push_str(token.text()),
+                    None => first_line.push_str(token.text()),
+                },
+                _ => {}
+            }
+        }
+        if let Some(line) = current {
+            lines.push(ContinuationLine::decode(&line));
+        }
+        MultiLineValue { first_line, lines }
+    }
+
     /// Detach this entry from the paragraph.
     pub fn detach(&mut self) {
         self.0.detach();
@@ -1157,7 +2218,7 @@ Description: This is a description
       NEWLINE@202..203 "\n"
 "###
     );
-    assert_eq!(parsed.errors, Vec::<String>::new());
+    assert_eq!(parsed.errors, Vec::<PositionedParseError>::new());
 
     let root = parsed.root_mut();
     assert_eq!(root.paragraphs().count(), 2);
@@ -1228,7 +2289,7 @@ Maintainer: Foo Bar <foo@example.com>
     NEWLINE@51..52 "\n"
 "###
     );
-    assert_eq!(parsed.errors, Vec::<String>::new());
+    assert_eq!(parsed.errors, Vec::<PositionedParseError>::new());
 
     let root = parsed.root_mut();
     assert_eq!(root.paragraphs().count(), 1);
@@ -1301,6 +2362,7 @@ fn rebuild_value(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dialect::Dialect;
     #[test]
     fn test_parse() {
         let d: super::Deb822 = r#"Source: foo
@@ -1419,6 +2481,11 @@ Homepage: https://salsa.debian.org/debian/foo
 
         // Nonexistent field
         assert!(!p.rename("Nonexistent", "Homepage"));
+
+        // Continuation lines are kept as-is
+        let mut p: super::Paragraph = "Depends:  foo,\n    bar\n".parse().unwrap();
+        assert!(p.rename("Depends", "Pre-Depends"));
+        assert_eq!(p.to_string(), "Pre-Depends:  foo,\n    bar\n");
     }
 
     #[test]
@@ -1519,11 +2586,11 @@ Foo: Bar
 
         // test delete
         d.remove_paragraph(1);
-        assert_eq!(d.to_string(), "Foo: Blah\n\n");
+        assert_eq!(d.to_string(), "Foo: Blah\n");
 
         // test update again
         p.set("Foo", "Baz");
-        assert_eq!(d.to_string(), "Foo: Baz\n\n");
+        assert_eq!(d.to_string(), "Foo: Baz\n");
 
         // test delete again
         d.remove_paragraph(0);
@@ -1747,19 +2814,61 @@ C: D
         );
     }
 
+    fn foo_error() -> PositionedParseError {
+        PositionedParseError::new(
+            "foo",
+            ParseErrorKind::MissingColon,
+            "foo".to_string(),
+            TextRange::new(0.into(), 3.into()),
+            Some("foo".to_string()),
+        )
+    }
+
     #[test]
     fn test_format_parse_error() {
-        assert_eq!(ParseError(vec!["foo".to_string()]).to_string(), "foo\n");
+        assert_eq!(ParseError(vec![foo_error()]).to_string(), "foo\n");
     }
 
     #[test]
     fn test_format_error() {
         assert_eq!(
-            super::Error::ParseError(ParseError(vec!["foo".to_string()])).to_string(),
+            super::Error::ParseError(ParseError(vec![foo_error()])).to_string(),
             "foo\n"
         );
     }
 
+    #[test]
+    fn test_positioned_errors() {
+        let (_, errors) = super::Deb822::from_str_relaxed("Source: foo\nBar\n");
+        assert_eq!(
+            errors,
+            vec![PositionedParseError {
+                kind: ParseErrorKind::MissingColon,
+                message: "expected ':', got None".to_string(),
+                range: TextRange::new(15.into(), 16.into()),
+                line: 1,
+                column: 3,
+                token: Some("\n".to_string()),
+            }]
+        );
+
+        let (_, errors) = super::Deb822::from_str_relaxed("Source: foo\n\n baz\n");
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].kind, ParseErrorKind::StrayContinuationLine);
+        assert_eq!(errors[0].range, TextRange::new(13.into(), 14.into()));
+        assert_eq!((errors[0].line, errors[0].column), (2, 0));
+        assert_eq!(errors[0].token.as_deref(), Some(" "));
+    }
+
+    #[test]
+    fn test_no_paragraphs_error() {
+        let err = "\n".parse::<super::Paragraph>().unwrap_err();
+        assert_eq!(err.errors().len(), 1);
+        assert_eq!(err.errors()[0].kind, ParseErrorKind::NoParagraphs);
+        assert_eq!((err.errors()[0].line, err.errors()[0].column), (1, 0));
+        assert_eq!(err.to_string(), "no paragraphs\n");
+    }
+
     #[test]
     fn test_get_all() {
         let d: super::Deb822 = r#"Source: foo
@@ -1773,4 +2882,533 @@ Maintainer: Bar Foo <bar@example.com>"#
             vec!["Foo Bar <foo@example.com>", "Bar Foo <bar@example.com>"]
         );
     }
+
+    #[test]
+    fn test_case_insensitive() {
+        let d: super::Deb822 = r#"Source: foo
+Build-depends: debhelper,
+ python3
+Section: net
+"#
+        .parse()
+        .unwrap();
+        let mut p = d.paragraphs().next().unwrap();
+        assert_eq!(
+            p.get("Build-Depends").as_deref(),
+            Some("debhelper,\npython3")
+        );
+        assert!(p.contains_key("SOURCE"));
+        assert_eq!(p.get_all("build-depends").count(), 1);
+
+        p.set("BUILD-DEPENDS", "debhelper");
+        p.remove("section");
+        assert!(p.rename("source", "Source"));
+        assert_eq!(p.to_string(), "Source: foo\nBuild-depends: debhelper\n");
+    }
+
+    #[test]
+    fn test_normalize_field_case() {
+        let d: super::Deb822 = r#"Source: foo
+build-depends: debhelper,
+ python3
+Vcs-git: https://example.com/foo
+"#
+        .parse()
+        .unwrap();
+        let mut p = d.paragraphs().next().unwrap();
+        let known = ["Source", "Build-Depends", "Vcs-Git"];
+        assert_eq!(
+            p.wrong_case_fields(&known),
+            vec![
+                ("build-depends".to_string(), "Build-Depends"),
+                ("Vcs-git".to_string(), "Vcs-Git")
+            ]
+        );
+        assert_eq!(p.normalize_field_case(&known).len(), 2);
+        assert_eq!(
+            p.to_string(),
+            r#"Source: foo
+Build-Depends: debhelper,
+ python3
+Vcs-Git: https://example.com/foo
+"#
+        );
+        assert!(p.wrong_case_fields(&known).is_empty());
+    }
+
+    #[test]
+    fn test_multiline_value() {
+        let text = r#"Package: foo
+Description: short
+ Some text.
+ .
+   verbatim line
+Signed-By:
+ -----BEGIN PGP PUBLIC KEY BLOCK-----
+ .
+ mDMEY
+ -----END PGP PUBLIC KEY BLOCK-----
+"#;
+        let d: super::Deb822 = text.parse().unwrap();
+        let mut p = d.paragraphs().next().unwrap();
+        let description = p.get_multiline("description").unwrap();
+        assert_eq!(
+            description,
+            MultiLineValue::new(
+                "short",
+                vec![
+                    ContinuationLine::Text("Some text.".to_string()),
+                    ContinuationLine::Blank,
+                    ContinuationLine::Text("  verbatim line".to_string()),
+                ]
+            )
+        );
+        let key = p.get_multiline("Signed-By").unwrap();
+        assert_eq!(key.first_line, "");
+        assert_eq!(key.lines.len(), 4);
+        assert!(p.get_multiline("Package").unwrap().is_single_line());
+
+        // Writing the values back produces the original text.
+        p.set_multiline("Description", &description);
+        p.set_multiline("signed-by", &key);
+        assert_eq!(p.to_string(), text);
+
+        p.set_multiline("Description", &MultiLineValue::from_text("new\n\n text"));
+        assert_eq!(
+            p.get_multiline("Description").unwrap().to_text(),
+            "new\n\n text"
+        );
+        assert!(p.to_string().contains("Description: new\n .\n  text\n"));
+    }
+
+    #[test]
+    fn test_comments() {
+        let d: super::Deb822 = r#"Source: foo
+
+# About bar
+Package: bar
+# Keep in sync with upstream setup.py
+# (really)
+Depends: python3
+Architecture: all
+# trailing
+"#
+        .parse()
+        .unwrap();
+        let mut p = d.paragraphs().nth(1).unwrap();
+        assert_eq!(
+            p.comments(),
+            vec![
+                "# Keep in sync with upstream setup.py",
+                "# (really)",
+                "# trailing"
+            ]
+        );
+        assert_eq!(p.leading_comments(), vec!["# About bar"]);
+        assert!(d.paragraphs().next().unwrap().leading_comments().is_empty());
+
+        assert!(p
+            .get_entry("Package")
+            .unwrap()
+            .leading_comments()
+            .is_empty());
+        let mut depends = p.get_entry("Depends").unwrap();
+        assert_eq!(
+            depends.leading_comments(),
+            vec!["# Keep in sync with upstream setup.py", "# (really)"]
+        );
+        let mut architecture = p.get_entry("Architecture").unwrap();
+        assert!(architecture.leading_comments().is_empty());
+
+        depends.set_leading_comments(&[]);
+        architecture.set_leading_comments(&["Must be all", "# really"]);
+        p.set_leading_comments(&["The bar package"]);
+        assert_eq!(
+            d.to_string(),
+            r#"Source: foo
+
+# The bar package
+Package: bar
+Depends: python3
+# Must be all
+# really
+Architecture: all
+# trailing
+"#
+        );
+        assert_eq!(
+            architecture.leading_comments(),
+            vec!["# Must be all", "# really"]
+        );
+
+        p.set_leading_comments(&[]);
+        assert_eq!(
+            d.to_string(),
+            "Source: foo\n\nPackage: bar\nDepends: python3\n# Must be all\n# really\nArchitecture: all\n# trailing\n"
+        );
+    }
+
+    #[test]
+    fn test_wrap_and_sort_moves_comments() {
+        let d: super::Deb822 = r#"Source: foo
+# About Maintainer
+Maintainer: Foo Bar <foo@example.com>
+# About Build-Depends
+Build-Depends: debhelper
+# trailing
+"#
+        .parse()
+        .unwrap();
+        let p = d.paragraphs().next().unwrap();
+        let result = p.wrap_and_sort(
+            crate::Indentation::FieldNameLength,
+            false,
+            None,
+            Some(&|a: &super::Entry, b: &super::Entry| a.key().cmp(&b.key())),
+            None,
+        );
+        assert_eq!(
+            result.to_string(),
+            r#"# About Build-Depends
+Build-Depends: debhelper
+# About Maintainer
+Maintainer: Foo Bar <foo@example.com>
+Source: foo
+# trailing
+"#
+        );
+    }
+
+    #[test]
+    fn test_insert_before_after() {
+        let d: super::Deb822 = r#"Source: foo
+# The maintainer
+Maintainer: Foo Bar <foo@example.com>
+"#
+        .parse()
+        .unwrap();
+        let mut p = d.paragraphs().next().unwrap();
+        assert!(p.insert_before("maintainer", "Section", "net"));
+        assert!(p.insert_after("Maintainer", "Homepage", "https://example.com/"));
+        assert!(!p.insert_after("Nonexistent", "Priority", "optional"));
+        assert_eq!(
+            p.to_string(),
+            r#"Source: foo
+Section: net
+# The maintainer
+Maintainer: Foo Bar <foo@example.com>
+Homepage: https://example.com/
+"#
+        );
+    }
+
+    #[test]
+    fn test_set_with_field_order() {
+        const ORDER: &[&str] = &[
+            "Source",
+            "Section",
+            "Maintainer",
+            "Build-Depends",
+            "Standards-Version",
+            "Vcs-Git",
+            "Homepage",
+            "Rules-Requires-Root",
+        ];
+        let d: super::Deb822 = r#"Source: foo
+Maintainer: Foo Bar <foo@example.com>
+Standards-Version: 4.6.0
+X-Custom: blah
+"#
+        .parse()
+        .unwrap();
+        let mut p = d.paragraphs().next().unwrap();
+        p.set_with_field_order("Vcs-Git", "https://example.com/foo.git", ORDER);
+        p.set_with_field_order("Build-Depends", "debhelper", ORDER);
+        p.set_with_field_order("Rules-Requires-Root", "no", ORDER);
+        p.set_with_field_order("X-Other", "blah", ORDER);
+        p.set_with_field_order("maintainer", "Joe <joe@example.com>", ORDER);
+        assert_eq!(
+            p.to_string(),
+            r#"Source: foo
+Maintainer: Joe <joe@example.com>
+Build-Depends: debhelper
+Standards-Version: 4.6.0
+Vcs-Git: https://example.com/foo.git
+Rules-Requires-Root: no
+X-Custom: blah
+X-Other: blah
+"#
+        );
+
+        let mut empty = super::Paragraph::new();
+        empty.set_with_field_order("Homepage", "https://example.com/", ORDER);
+        empty.set_with_field_order("Source", "foo", ORDER);
+        assert_eq!(
+            empty.to_string(),
+            "Source: foo\nHomepage: https://example.com/\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_with_options() {
+        use crate::dialect::{Dialect, ParseOptions};
+
+        let kinds = |text: &str, options: &ParseOptions| {
+            let (deb822, errors) = Deb822::from_str_relaxed_with_options(text, options);
+            // The tree is always lossless, even if there are errors.
+            assert_eq!(deb822.to_string(), text);
+            errors.into_iter().map(|e| e.kind).collect::<Vec<_>>()
+        };
+
+        let text = "Package: foo\n# Comment\nSigned-By:\n a\n# Comment\n  b\n";
+        assert_eq!(
+            kinds(text, &ParseOptions::default()),
+            vec![
+                ParseErrorKind::StrayContinuationLine,
+                ParseErrorKind::MissingColon
+            ]
+        );
+        assert_eq!(
+            kinds(text, &Dialect::DpkgStatus.options()),
+            vec![
+                ParseErrorKind::UnexpectedComment,
+                ParseErrorKind::UnexpectedComment,
+                ParseErrorKind::StrayContinuationLine,
+                ParseErrorKind::MissingColon
+            ]
+        );
+        let deb822 = Deb822::from_str_with_options(text, &Dialect::Apt.options()).unwrap();
+        let paragraph = deb822.paragraphs().next().unwrap();
+        assert_eq!(paragraph.get("Signed-By").as_deref(), Some("a\nb"));
+        assert_eq!(
+            paragraph.get_multiline("Signed-By").unwrap().to_string(),
+            "\n a\n  b\n"
+        );
+
+        assert_eq!(kinds("#Foo: bar\n", &Dialect::Rfc822.options()), vec![]);
+        assert_eq!(
+            Deb822::from_str_with_options("#Foo: bar\n", &Dialect::Rfc822.options())
+                .unwrap()
+                .paragraphs()
+                .next()
+                .unwrap()
+                .get("#Foo")
+                .as_deref(),
+            Some("bar")
+        );
+
+        let strict = Dialect::Deb822.options();
+        assert_eq!(kinds("A: b\r\nC: d\r\n", &ParseOptions::default()), vec![]);
+        assert_eq!(
+            kinds("A: b\r\nC: d\n", &strict),
+            vec![ParseErrorKind::CarriageReturn]
+        );
+        assert_eq!(
+            kinds("A: b\na: c\n\nA: d\n", &strict),
+            vec![ParseErrorKind::DuplicateField]
+        );
+        assert_eq!(
+            kinds("\n# Comment\nA: b\n\n", &strict),
+            vec![
+                ParseErrorKind::LeadingBlankLine,
+                ParseErrorKind::TrailingBlankLine
+            ]
+        );
+        let err = Deb822::from_str_with_options("A: b\nA: c\n", &strict).unwrap_err();
+        assert_eq!(err.errors()[0].line, 1);
+        assert_eq!(err.errors()[0].token.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn test_collapse_duplicates() {
+        use crate::dialect::{DuplicateFields, ParseOptions};
+
+        let text =
+            "Package: foo\nDepends: a\n# Comment\nSection: libs\ndepends: b,\n c\n\nPackage: bar\n";
+        let collapse = |policy| {
+            let mut deb822: Deb822 = text.parse().unwrap();
+            let collapsed = deb822.collapse_duplicates(policy);
+            (collapsed, deb822.to_string())
+        };
+        assert_eq!(collapse(DuplicateFields::Allow), (vec![], text.to_string()));
+        assert_eq!(
+            collapse(DuplicateFields::KeepFirst),
+            (
+                vec![(0, vec!["Depends".to_string()])],
+                "Package: foo\nDepends: a\n# Comment\nSection: libs\n\nPackage: bar\n".to_string()
+            )
+        );
+        assert_eq!(
+            collapse(DuplicateFields::KeepLast).1,
+            "Package: foo\n# Comment\nSection: libs\ndepends: b,\n c\n\nPackage: bar\n"
+        );
+        assert_eq!(
+            collapse(DuplicateFields::MergeAsList).1,
+            "Package: foo\nDepends: a, b,\n c\n# Comment\nSection: libs\n\nPackage: bar\n"
+        );
+
+        let deb822: Deb822 = text.parse().unwrap();
+        let paragraph = deb822.paragraphs().next().unwrap();
+        let duplicates = paragraph.duplicate_fields();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(
+            duplicates[0]
+                .entries
+                .iter()
+                .map(|e| e.key_range().unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                TextRange::new(13.into(), 20.into()),
+                TextRange::new(48.into(), 55.into())
+            ]
+        );
+
+        // The parser reports duplicates, or collapses them.
+        let options = ParseOptions {
+            duplicate_fields: DuplicateFields::Error,
+            ..Default::default()
+        };
+        let (_, errors) = Deb822::from_str_relaxed_with_options(text, &options);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ParseErrorKind::DuplicateField);
+        assert_eq!((errors[0].line, errors[0].column), (4, 0));
+        let options = ParseOptions {
+            duplicate_fields: DuplicateFields::KeepLast,
+            ..Default::default()
+        };
+        let deb822 = Deb822::from_str_with_options(text, &options).unwrap();
+        assert_eq!(
+            deb822
+                .paragraphs()
+                .next()
+                .unwrap()
+                .get("Depends")
+                .as_deref(),
+            Some("b,\nc")
+        );
+    }
+
+    #[test]
+    fn test_reparse() {
+        let text = "# Header\n\nSource: foo\nBuild-Depends: bar,\n blah\n\n\nPackage: foo\n# Comment\nDescription: short\n long\n .\n more\n\nPackage: foo-doc\r\nBroken\n";
+        let (deb822, errors) = Deb822::from_str_relaxed(text);
+        for start in 0..=text.len() {
+            for (len, replacement) in [
+                (0, "\n"),
+                (0, "x"),
+                (0, " "),
+                (0, "# c\n"),
+                (1, ""),
+                (2, ""),
+            ] {
+                let end = start + len;
+                if end > text.len() || !text.is_char_boundary(end) {
+                    continue;
+                }
+                let edit = TextEdit::new(
+                    TextRange::new((start as u32).into(), (end as u32).into()),
+                    replacement,
+                );
+                let (reparsed, reparsed_errors) = deb822.reparse(&errors, &edit).unwrap();
+                let (expected, expected_errors) =
+                    Deb822::from_str_relaxed(&edit.apply(text).unwrap());
+                assert_eq!(
+                    format!("{:#?}", reparsed.0),
+                    format!("{:#?}", expected.0),
+                    "{:?}",
+                    edit
+                );
+                assert_eq!(reparsed_errors, expected_errors, "{:?}", edit);
+            }
+        }
+
+        // Paragraphs that are not affected by the edit share their green nodes.
+        let offset = text.find("long").unwrap() as u32;
+        let edit = TextEdit::new(TextRange::at(offset.into(), 4.into()), "LONG");
+        let (reparsed, _) = deb822.reparse(&errors, &edit).unwrap();
+        let shared = |i: usize| {
+            let old = deb822.paragraphs().nth(i).unwrap();
+            let new = reparsed.paragraphs().nth(i).unwrap();
+            std::ptr::eq(&*old.0.green(), &*new.0.green())
+        };
+        assert!(shared(0));
+        assert!(!shared(1));
+        assert!(shared(2));
+        assert_eq!(
+            reparsed
+                .paragraphs()
+                .nth(1)
+                .unwrap()
+                .get("Description")
+                .as_deref(),
+            Some("short\nLONG\n.\nmore")
+        );
+    }
+
+    #[test]
+    fn test_reparse_with_options() {
+        // The checks of the dialect the document was parsed with apply to the reparsed text.
+        let text = "\nSource: foo\nsource: bar\n# Comment\nBuild-Depends: bar,\n# Comment\n blah\n\nPackage: foo\r\nDescription: short\n long\n\n";
+        for options in [
+            Dialect::Deb822.options(),
+            Dialect::DpkgStatus.options(),
+            Dialect::Apt.options(),
+            Dialect::Rfc822.options(),
+            ParseOptions {
+                duplicate_fields: DuplicateFields::MergeAsList,
+                ..Dialect::Deb822.options()
+            },
+        ] {
+            // Collapsing duplicate fields changes the text, so start from the collapsed text.
+            let text = Deb822::from_str_relaxed_with_options(text, &options)
+                .0
+                .to_string();
+            let (deb822, errors) = Deb822::from_str_relaxed_with_options(&text, &options);
+            for start in 0..=text.len() {
+                for (len, replacement) in [(0, "\n"), (0, "# c\n"), (0, "source: x\n"), (1, "")] {
+                    let end = start + len;
+                    if end > text.len() {
+                        continue;
+                    }
+                    let edit = TextEdit::new(
+                        TextRange::new((start as u32).into(), (end as u32).into()),
+                        replacement,
+                    );
+                    let (reparsed, reparsed_errors) = deb822
+                        .reparse_with_options(&errors, &edit, &options)
+                        .unwrap();
+                    let (expected, expected_errors) = Deb822::from_str_relaxed_with_options(
+                        &edit.apply(&text).unwrap(),
+                        &options,
+                    );
+                    assert_eq!(
+                        format!("{:#?}", reparsed.0),
+                        format!("{:#?}", expected.0),
+                        "{:?} {:?}",
+                        options,
+                        edit
+                    );
+                    assert_eq!(reparsed_errors, expected_errors, "{:?} {:?}", options, edit);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reparse_invalid_edit() {
+        let text = "Package: f\u{f6}o\n";
+        let (deb822, errors) = Deb822::from_str_relaxed(text);
+        for range in [
+            TextRange::new(15.into(), 16.into()),
+            TextRange::new(40.into(), 41.into()),
+            TextRange::new(11.into(), 12.into()),
+        ] {
+            let edit = TextEdit::new(range, "x");
+            assert_eq!(edit.apply(text), Err(InvalidTextEdit(range)));
+            assert_eq!(
+                deb822.reparse(&errors, &edit).unwrap_err(),
+                InvalidTextEdit(range)
+            );
+        }
+    }
 }
@@ -2,6 +2,10 @@
 //!
 //! This parser is lossy in the sense that it will discard whitespace and comments
 //! in the input.
+//!
+//! Values that start on a continuation line, with nothing after the colon, keep a leading
+//! newline so that they are written back in the same form. This differs from
+//! [`crate::lossless::Paragraph::get`], which omits it.
 use crate::lex::SyntaxKind;
 
 /// Error type for the parser.
@@ -16,6 +20,12 @@ pub enum Error {
     /// Expected end-of-file.
     ExpectedEof,
 
+    /// A field appears more than once in a paragraph.
+    DuplicateField(String),
+
+    /// The input starts or ends with blank lines.
+    UnexpectedBlankLine,
+
     /// IO error.
     Io(std::io::Error),
 }
@@ -33,6 +43,8 @@ impl std::fmt::Display for Error {
             Self::UnexpectedEof => f.write_str("Unexpected end-of-file"),
             Self::Io(e) => write!(f, "IO error: {}", e),
             Self::ExpectedEof => f.write_str("Expected end-of-file"),
+            Self::DuplicateField(name) => write!(f, "Duplicate field: {}", name),
+            Self::UnexpectedBlankLine => f.write_str("Unexpected blank line"),
         }
     }
 }
@@ -57,10 +69,11 @@ pub struct Paragraph {
 impl Paragraph {
     /// Get the value of a field by name.
     ///
+    /// Field names are compared case-insensitively.
     /// Returns `None` if the field does not exist.
     pub fn get(&self, name: &str) -> Option<&str> {
         for field in &self.fields {
-            if field.name == name {
+            if crate::common::key_eq(&field.name, name) {
                 return Some(&field.value);
             }
         }
@@ -104,11 +117,11 @@ impl Paragraph {
 
     /// Set the value of a field.
     ///
-    /// If a field with the same name already exists, its value
-    /// will be updated.
+    /// If a field with the same name (compared case-insensitively) already
+    /// exists, its value will be updated and its name kept as-is.
     pub fn set(&mut self, name: &str, value: &str) {
         for field in &mut self.fields {
-            if field.name == name {
+            if crate::common::key_eq(&field.name, name) {
                 field.value = value.to_string();
                 return;
             }
@@ -117,8 +130,11 @@ impl Paragraph {
     }
 
     /// Remove a field from the paragraph.
+    ///
+    /// Field names are compared case-insensitively.
     pub fn remove(&mut self, name: &str) {
-        self.fields.retain(|field| field.name != name);
+        self.fields
+            .retain(|field| !crate::common::key_eq(&field.name, name));
     }
 }
 
@@ -241,6 +257,9 @@ impl Deb822 {
     }
 
     /// Read from a reader.
+    ///
+    /// This reads the entire input into memory; see [`crate::stream::LossyParagraphs`] for
+    /// processing large files one paragraph at a time.
     pub fn from_reader<R: std::io::Read>(mut r: R) -> Result<Self, Error> {
         let mut buf = String::new();
         r.read_to_string(&mut buf)?;
@@ -252,120 +271,30 @@ impl std::str::FromStr for Deb822 {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut tokens = crate::lex::lex(s).peekable();
-
-        let mut paragraphs = Vec::new();
-        let mut current_paragraph = Vec::new();
-
-        while let Some((k, t)) = tokens.next() {
-            match k {
-                SyntaxKind::EMPTY_LINE
-                | SyntaxKind::PARAGRAPH
-                | SyntaxKind::ROOT
-                | SyntaxKind::ENTRY => unreachable!(),
-                SyntaxKind::INDENT | SyntaxKind::COLON | SyntaxKind::ERROR => {
-                    return Err(Error::UnexpectedToken(k, t.to_string()));
-                }
-                SyntaxKind::WHITESPACE => {
-                    // ignore whitespace
-                }
-                SyntaxKind::KEY => {
-                    current_paragraph.push(Field {
-                        name: t.to_string(),
-                        value: String::new(),
-                    });
-
-                    match tokens.next() {
-                        Some((SyntaxKind::COLON, _)) => {}
-                        Some((k, t)) => {
-                            return Err(Error::UnexpectedToken(k, t.to_string()));
-                        }
-                        None => {
-                            return Err(Error::UnexpectedEof);
-                        }
-                    }
-
-                    while tokens.peek().map(|(k, _)| k) == Some(&SyntaxKind::WHITESPACE) {
-                        tokens.next();
-                    }
-
-                    for (k, t) in tokens.by_ref() {
-                        match k {
-                            SyntaxKind::VALUE => {
-                                current_paragraph.last_mut().unwrap().value = t.to_string();
-                            }
-                            SyntaxKind::NEWLINE => {
-                                break;
-                            }
-                            _ => return Err(Error::UnexpectedToken(k, t.to_string())),
-                        }
-                    }
-
-                    current_paragraph.last_mut().unwrap().value.push('\n');
-
-                    // while the next line starts with INDENT, it's a continuation of the value
-                    while tokens.peek().map(|(k, _)| k) == Some(&SyntaxKind::INDENT) {
-                        tokens.next();
-                        loop {
-                            match tokens.peek() {
-                                Some((SyntaxKind::VALUE, t)) => {
-                                    current_paragraph.last_mut().unwrap().value.push_str(t);
-                                    tokens.next();
-                                }
-                                Some((SyntaxKind::COMMENT, _)) => {
-                                    // ignore comments
-                                    tokens.next();
-                                }
-                                Some((SyntaxKind::NEWLINE, n)) => {
-                                    current_paragraph.last_mut().unwrap().value.push_str(n);
-                                    tokens.next();
-                                    break;
-                                }
-                                Some((SyntaxKind::KEY, _)) => {
-                                    break;
-                                }
-                                Some((k, _)) => {
-                                    return Err(Error::UnexpectedToken(*k, t.to_string()));
-                                }
-                                None => {
-                                    break;
-                                }
-                            }
-                        }
-                    }
-
-                    // Trim the trailing newline
-                    assert_eq!(
-                        current_paragraph.last_mut().unwrap().value.pop(),
-                        Some('\n')
-                    );
-                }
-                SyntaxKind::VALUE => {
-                    return Err(Error::UnexpectedToken(k, t.to_string()));
-                }
-                SyntaxKind::COMMENT => {
-                    for (k, _) in tokens.by_ref() {
-                        if k == SyntaxKind::NEWLINE {
-                            break;
-                        }
-                    }
-                }
-                SyntaxKind::NEWLINE => {
-                    if !current_paragraph.is_empty() {
-                        paragraphs.push(Paragraph {
-                            fields: current_paragraph,
-                        });
-                        current_paragraph = Vec::new();
-                    }
-                }
-            }
-        }
-        if !current_paragraph.is_empty() {
-            paragraphs.push(Paragraph {
-                fields: current_paragraph,
-            });
-        }
-        Ok(Deb822(paragraphs))
+        parse(s).map_err(|(e, _line)| e)
+    }
+}
+
+impl Deb822 {
+    /// Parse a deb822 document, using the rules of a particular dialect.
+    pub fn from_str_with_options(
+        s: &str,
+        options: &crate::dialect::ParseOptions,
+    ) -> Result<Self, Error> {
+        crate::borrowed::parse_with_options(s, options)
+            .map(Deb822::from)
+            .map_err(|(e, _line)| e)
+    }
+}
+
+/// Parse a deb822 document, returning the (0-based) line on which any error occurred.
+pub(crate) fn parse(s: &str) -> Result<Deb822, (Error, usize)> {
+    crate::borrowed::parse(s).map(Deb822::from)
+}
+
+impl From<crate::borrowed::BorrowedDeb822<'_>> for Deb822 {
+    fn from(doc: crate::borrowed::BorrowedDeb822<'_>) -> Self {
+        Deb822(doc.into())
     }
 }
 
@@ -545,4 +474,20 @@ Version: 2.10
             "Description: A program that says hello\n Some more text\n"
         );
     }
+
+    #[test]
+    fn test_continuation_at_eof() {
+        let deb822: Deb822 = "Description: short\n long".parse().unwrap();
+        let para = deb822.iter().next().unwrap();
+        assert_eq!(para.get("Description"), Some("short\nlong"));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let mut para: Paragraph = "Build-depends: foo\nSection: net\n".parse().unwrap();
+        assert_eq!(para.get("Build-Depends"), Some("foo"));
+        para.set("BUILD-DEPENDS", "bar");
+        para.remove("section");
+        assert_eq!(para.to_string(), "Build-depends: bar\n");
+    }
 }
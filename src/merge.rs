@@ -0,0 +1,705 @@
+//! Three-way merging of deb822 documents.
+//!
+//! Paragraphs are matched up by a key, like in [`crate::diff`], and their fields are merged
+//! independently: a field that was only changed on one side takes the value from that side.
+//! Fields that contain comma-separated relations, like `Depends`, are merged one relation at a
+//! time, so that unrelated changes to the same field do not conflict.
+//!
+//! The result is based on "our" version of the document, so its formatting and comments are
+//! kept. Where both sides made conflicting changes, our version is kept and a [`Conflict`] is
+//! reported.
+//!
+//! # Example
+//!
+//! ```rust
+//! use deb822_lossless::merge::{merge, MergeOptions};
+//! use deb822_lossless::Deb822;
+//!
+//! let base: Deb822 = "Package: foo\nDepends: a, b\n".parse().unwrap();
+//! let ours: Deb822 = "Package: foo\nDepends: a (>= 1), b\n".parse().unwrap();
+//! let theirs: Deb822 = "Package: foo\nDepends: a, b, c\n".parse().unwrap();
+//! let merged = merge(&base, &ours, &theirs, |p| p.get("Package"), &MergeOptions::default());
+//! assert!(merged.conflicts.is_empty());
+//! assert_eq!(merged.result.to_string(), "Package: foo\nDepends: a (>= 1), b, c\n");
+//! ```
+use crate::diff::{match_paragraphs, normalize};
+use crate::lossless::{Deb822, Paragraph};
+use crate::multiline::{ContinuationLine, MultiLineValue};
+
+/// Fields that contain comma-separated relations, merged entry by entry by default.
+pub const RELATION_FIELDS: &[&str] = &[
+    "Build-Depends",
+    "Build-Depends-Indep",
+    "Build-Depends-Arch",
+    "Build-Conflicts",
+    "Build-Conflicts-Indep",
+    "Build-Conflicts-Arch",
+    "Pre-Depends",
+    "Depends",
+    "Recommends",
+    "Suggests",
+    "Enhances",
+    "Breaks",
+    "Conflicts",
+    "Replaces",
+    "Provides",
+    "Built-Using",
+    "Static-Built-Using",
+];
+
+/// Options for [`merge`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// Fields containing comma-separated relations, which are merged entry by entry.
+    ///
+    /// Entries are identified by the first package name they contain.
+    pub relation_fields: Vec<String>,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            relation_fields: RELATION_FIELDS.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+}
+
+/// One side of a merge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Our version, on which the result is based.
+    Ours,
+
+    /// Their version, which is merged into ours.
+    Theirs,
+}
+
+/// A conflict found while merging.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// A paragraph was modified on one side, and removed on the other.
+    Paragraph {
+        /// Key of the paragraph.
+        key: Option<String>,
+
+        /// The side that removed the paragraph.
+        removed_by: Side,
+    },
+
+    /// A field was changed differently on both sides.
+    Field {
+        /// Key of the paragraph containing the field.
+        paragraph: Option<String>,
+
+        /// Name of the field.
+        field: String,
+
+        /// Value in the common ancestor.
+        base: Option<String>,
+
+        /// Value in our version, which is kept in the result.
+        ours: Option<String>,
+
+        /// Value in their version.
+        theirs: Option<String>,
+    },
+
+    /// An entry in a relation field was changed differently on both sides.
+    Relation {
+        /// Key of the paragraph containing the field.
+        paragraph: Option<String>,
+
+        /// Name of the field.
+        field: String,
+
+        /// Entry in the common ancestor.
+        base: Option<String>,
+
+        /// Entry in our version, which is kept in the result.
+        ours: Option<String>,
+
+        /// Entry in their version.
+        theirs: Option<String>,
+    },
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let paragraph = |key: &Option<String>| match key {
+            Some(key) => format!("paragraph {}", key),
+            None => "paragraph without key".to_string(),
+        };
+        let value = |value: &Option<String>| match value {
+            Some(value) => format!("{:?}", value),
+            None => "(absent)".to_string(),
+        };
+        match self {
+            Conflict::Paragraph {
+                key,
+                removed_by: Side::Ours,
+            } => write!(
+                f,
+                "{} was removed by us and modified by them",
+                paragraph(key)
+            ),
+            Conflict::Paragraph {
+                key,
+                removed_by: Side::Theirs,
+            } => write!(
+                f,
+                "{} was modified by us and removed by them",
+                paragraph(key)
+            ),
+            Conflict::Field {
+                paragraph: key,
+                field,
+                base,
+                ours,
+                theirs,
+            }
+            | Conflict::Relation {
+                paragraph: key,
+                field,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "{}: conflicting changes to {}: base {}, ours {}, theirs {}",
+                paragraph(key),
+                field,
+                value(base),
+                value(ours),
+                value(theirs),
+            ),
+        }
+    }
+}
+
+/// The result of a merge.
+#[derive(Debug)]
+pub struct Merge {
+    /// The merged document.
+    pub result: Deb822,
+
+    /// The conflicts that were found. Where possible, our version was used for these.
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Compare two optional values, ignoring whitespace and wrapping.
+fn same(a: &Option<String>, b: &Option<String>) -> bool {
+    a.as_deref().map(normalize) == b.as_deref().map(normalize)
+}
+
+fn value(paragraph: Option<&Paragraph>, field: &str) -> Option<String> {
+    paragraph?.get_multiline(field).map(|v| v.to_value())
+}
+
+/// Returns the name identifying a relation entry.
+fn relation_name(entry: &str) -> &str {
+    entry
+        .split(|c: char| c.is_whitespace() || c == '(' || c == '[' || c == '<')
+        .next()
+        .unwrap_or(entry)
+}
+
+/// Split a relation field into its entries, keyed by their names.
+///
+/// If a name occurs more than once, the entries are keyed by their full text instead.
+pub(crate) fn relations(value: Option<&str>) -> Vec<(String, String)> {
+    let entries = value
+        .unwrap_or_default()
+        .split(',')
+        .map(normalize)
+        .filter(|e| !e.is_empty())
+        .collect::<Vec<_>>();
+    let names = entries.iter().map(|e| relation_name(e)).collect::<Vec<_>>();
+    let unique = names
+        .iter()
+        .enumerate()
+        .all(|(i, name)| !names[..i].contains(name));
+    entries
+        .iter()
+        .map(|e| {
+            let name = if unique { relation_name(e) } else { e };
+            (name.to_string(), e.clone())
+        })
+        .collect()
+}
+
+/// Merge the entries of a relation field.
+///
+/// Returns the merged entries, and the base, our and their versions of each entry that
+/// conflicted.
+fn merge_relations(
+    base: Option<&str>,
+    ours: &str,
+    theirs: &str,
+) -> (
+    Vec<String>,
+    Vec<(Option<String>, Option<String>, Option<String>)>,
+) {
+    let base = relations(base);
+    let ours = relations(Some(ours));
+    let theirs = relations(Some(theirs));
+    let find = |list: &[(String, String)], name: &str| {
+        list.iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, entry)| entry.clone())
+    };
+    let mut conflicts = vec![];
+    let mut merged: Vec<(String, String)> = vec![];
+    for (name, entry) in &ours {
+        let o = Some(entry.clone());
+        let b = find(&base, name);
+        let t = find(&theirs, name);
+        if o == t || b == t {
+            merged.push((name.clone(), entry.clone()));
+        } else if b == o {
+            if let Some(t) = t {
+                merged.push((name.clone(), t));
+            }
+        } else {
+            conflicts.push((b, o, t));
+            merged.push((name.clone(), entry.clone()));
+        }
+    }
+    let mut cursor = 0;
+    for (name, entry) in &theirs {
+        if let Some(pos) = merged.iter().position(|(n, _)| n == name) {
+            cursor = pos + 1;
+        } else if let Some(b) = find(&base, name) {
+            if &b != entry {
+                conflicts.push((Some(b), None, Some(entry.clone())));
+            }
+        } else {
+            merged.insert(cursor, (name.clone(), entry.clone()));
+            cursor += 1;
+        }
+    }
+    (merged.into_iter().map(|(_, e)| e).collect(), conflicts)
+}
+
+/// Format relation entries in the same style as `template`.
+pub(crate) fn format_relations(template: &MultiLineValue, entries: &[String]) -> MultiLineValue {
+    if template.is_single_line() {
+        return MultiLineValue::new(&entries.join(", "), vec![]);
+    }
+    let trailing_comma = template.to_value().trim_end().ends_with(',');
+    let indent = template
+        .lines
+        .iter()
+        .map(|line| line.as_str())
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line[..line.len() - line.trim_start().len()].to_string())
+        .unwrap_or_default();
+    let mut lines = entries
+        .iter()
+        .enumerate()
+        .map(|(i, e)| {
+            if i + 1 < entries.len() || trailing_comma {
+                format!("{},", e)
+            } else {
+                e.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+    let first_line = if template.first_line.trim().is_empty() || lines.is_empty() {
+        String::new()
+    } else {
+        lines.remove(0)
+    };
+    MultiLineValue::new(
+        &first_line,
+        lines
+            .into_iter()
+            .map(|line| ContinuationLine::Text(format!("{}{}", indent, line)))
+            .collect(),
+    )
+}
+
+/// Set a field that did not exist in our version, placing it after the field that precedes it
+/// in their version.
+fn add_field(paragraph: &mut Paragraph, theirs: &Paragraph, field: &str, value: &MultiLineValue) {
+    let keys = theirs.keys().collect::<Vec<_>>();
+    let index = keys
+        .iter()
+        .position(|k| crate::common::key_eq(k, field))
+        .unwrap_or(keys.len());
+    let previous = keys[..index]
+        .iter()
+        .rev()
+        .find(|k| paragraph.contains_key(k));
+    // Insert a placeholder in the right position, which is then replaced by the actual value.
+    if let Some(previous) = previous {
+        paragraph.insert_after(previous, field, "");
+    } else {
+        let first = paragraph.keys().next();
+        if let Some(first) = first {
+            paragraph.insert_before(&first, field, "");
+        }
+    }
+    paragraph.set_multiline(field, value);
+}
+
+/// Merge their changes to a paragraph into ours.
+fn merge_paragraph(
+    key: &Option<String>,
+    base: Option<&Paragraph>,
+    ours: &mut Paragraph,
+    theirs: &Paragraph,
+    options: &MergeOptions,
+    conflicts: &mut Vec<Conflict>,
+) {
+    let mut fields: Vec<String> = vec![];
+    for field in ours
+        .keys()
+        .chain(theirs.keys())
+        .chain(base.into_iter().flat_map(|b| b.keys().collect::<Vec<_>>()))
+        .collect::<Vec<_>>()
+    {
+        if !fields.iter().any(|f| crate::common::key_eq(f, &field)) {
+            fields.push(field);
+        }
+    }
+    for field in fields {
+        let b = value(base, &field);
+        let o = value(Some(ours), &field);
+        let t = value(Some(theirs), &field);
+        if same(&o, &t) || same(&b, &t) {
+            continue;
+        }
+        if same(&b, &o) {
+            match theirs.get_multiline(&field) {
+                Some(value) if o.is_some() => ours.set_multiline(&field, &value),
+                Some(value) => add_field(ours, theirs, &field, &value),
+                None => ours.remove(&field),
+            }
+            continue;
+        }
+        let is_relation = options
+            .relation_fields
+            .iter()
+            .any(|f| crate::common::key_eq(f, &field));
+        match (&o, &t) {
+            (Some(o), Some(t)) if is_relation => {
+                let (entries, entry_conflicts) = merge_relations(b.as_deref(), o, t);
+                conflicts.extend(entry_conflicts.into_iter().map(|(base, ours, theirs)| {
+                    Conflict::Relation {
+                        paragraph: key.clone(),
+                        field: field.clone(),
+                        base,
+                        ours,
+                        theirs,
+                    }
+                }));
+                if entries
+                    != relations(Some(o))
+                        .into_iter()
+                        .map(|(_, e)| e)
+                        .collect::<Vec<_>>()
+                {
+                    let template = ours.get_multiline(&field).unwrap();
+                    ours.set_multiline(&field, &format_relations(&template, &entries));
+                }
+            }
+            _ => conflicts.push(Conflict::Field {
+                paragraph: key.clone(),
+                field,
+                base: b,
+                ours: o,
+                theirs: t,
+            }),
+        }
+    }
+}
+
+/// Copy the fields of `source` into `target`.
+fn copy_fields(target: &mut Paragraph, source: &Paragraph) {
+    for entry in source.entries() {
+        if let Some(key) = entry.key() {
+            target.set_multiline(&key, &entry.multiline_value());
+        }
+    }
+}
+
+/// Merge the changes between `base` and `theirs` into `ours`.
+///
+/// Paragraphs are matched by the key returned by `key`, in the same way as by
+/// [`Deb822::diff`]. Paragraphs added by them are inserted after the paragraph that precedes them
+/// in their version.
+pub fn merge(
+    base: &Deb822,
+    ours: &Deb822,
+    theirs: &Deb822,
+    key: impl Fn(&Paragraph) -> Option<String>,
+    options: &MergeOptions,
+) -> Merge {
+    let mut result = ours.detached();
+    let base_paragraphs = base.paragraphs().collect::<Vec<_>>();
+    let theirs_paragraphs = theirs.paragraphs().collect::<Vec<_>>();
+    let mut ours_paragraphs = result.paragraphs().collect::<Vec<_>>();
+    let base_keys = base_paragraphs.iter().map(&key).collect::<Vec<_>>();
+    let ours_keys = ours_paragraphs.iter().map(&key).collect::<Vec<_>>();
+    let theirs_keys = theirs_paragraphs.iter().map(&key).collect::<Vec<_>>();
+
+    let base_to_ours = match_paragraphs(&base_keys, &ours_keys);
+    let base_to_theirs = match_paragraphs(&base_keys, &theirs_keys);
+    let unmatched = |count: usize, matches: &[Option<usize>]| {
+        let mut in_base = vec![false; count];
+        for &i in matches.iter().flatten() {
+            in_base[i] = true;
+        }
+        (0..count).filter(|&i| !in_base[i]).collect::<Vec<_>>()
+    };
+    let keys_of = |indices: &[usize], keys: &[Option<String>]| {
+        indices.iter().map(|&i| keys[i].clone()).collect::<Vec<_>>()
+    };
+    // Paragraphs that were added on both sides are matched with each other.
+    let added_by_us = unmatched(ours_keys.len(), &base_to_ours);
+    let added_by_them = unmatched(theirs_keys.len(), &base_to_theirs);
+    let mut theirs_to_ours = vec![None; theirs_paragraphs.len()];
+    // Whether each of their paragraphs also exists in the base or our version.
+    let mut matched = vec![false; theirs_paragraphs.len()];
+    let both_added = match_paragraphs(
+        &keys_of(&added_by_us, &ours_keys),
+        &keys_of(&added_by_them, &theirs_keys),
+    );
+    for (i, j) in both_added.into_iter().enumerate() {
+        if let Some(j) = j {
+            theirs_to_ours[added_by_them[j]] = Some(added_by_us[i]);
+            matched[added_by_them[j]] = true;
+        }
+    }
+
+    let mut conflicts = vec![];
+    let mut removed = vec![false; ours_paragraphs.len()];
+    for (b, base_paragraph) in base_paragraphs.iter().enumerate() {
+        match (base_to_ours[b], base_to_theirs[b]) {
+            (Some(o), Some(t)) => {
+                theirs_to_ours[t] = Some(o);
+                matched[t] = true;
+                merge_paragraph(
+                    &base_keys[b],
+                    Some(base_paragraph),
+                    &mut ours_paragraphs[o],
+                    &theirs_paragraphs[t],
+                    options,
+                    &mut conflicts,
+                );
+            }
+            (Some(o), None) => {
+                if crate::diff::diff_paragraphs(base_paragraph, &ours_paragraphs[o]).is_empty() {
+                    removed[o] = true;
+                } else {
+                    conflicts.push(Conflict::Paragraph {
+                        key: base_keys[b].clone(),
+                        removed_by: Side::Theirs,
+                    });
+                }
+            }
+            (None, Some(t)) => {
+                if !crate::diff::diff_paragraphs(base_paragraph, &theirs_paragraphs[t]).is_empty() {
+                    conflicts.push(Conflict::Paragraph {
+                        key: base_keys[b].clone(),
+                        removed_by: Side::Ours,
+                    });
+                }
+                matched[t] = true;
+            }
+            (None, None) => {}
+        }
+    }
+    let mut is_added_by_us = vec![false; ours_paragraphs.len()];
+    for &o in &added_by_us {
+        is_added_by_us[o] = true;
+    }
+    for (t, o) in theirs_to_ours.iter().enumerate() {
+        if let Some(o) = *o {
+            if is_added_by_us[o] {
+                merge_paragraph(
+                    &ours_keys[o],
+                    None,
+                    &mut ours_paragraphs[o],
+                    &theirs_paragraphs[t],
+                    options,
+                    &mut conflicts,
+                );
+            }
+        }
+    }
+
+    // Insert the paragraphs added by them. `added[i]` holds the paragraphs to insert before
+    // our `i`th paragraph, following the one before it.
+    let mut added = vec![vec![]; ours_paragraphs.len() + 1];
+    let mut cursor = 0;
+    for (t, o) in theirs_to_ours.iter().enumerate() {
+        match o {
+            Some(o) => cursor = o + 1,
+            None if matched[t] => {}
+            None => added[cursor].push(t),
+        }
+    }
+    // Work backwards, so that the positions of our earlier paragraphs do not change.
+    for (i, added) in added.iter().enumerate().rev() {
+        for (n, &t) in added.iter().enumerate() {
+            let mut paragraph = result.insert_paragraph(i + n);
+            copy_fields(&mut paragraph, &theirs_paragraphs[t]);
+        }
+        if i > 0 && removed[i - 1] {
+            result.remove_paragraph(i - 1);
+        }
+    }
+
+    Merge { result, conflicts }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package_or_source(p: &Paragraph) -> Option<String> {
+        p.get("Package").or_else(|| p.get("Source"))
+    }
+
+    fn merge_str(base: &str, ours: &str, theirs: &str) -> (String, Vec<Conflict>) {
+        let merged = merge(
+            &base.parse().unwrap(),
+            &ours.parse().unwrap(),
+            &theirs.parse().unwrap(),
+            package_or_source,
+            &MergeOptions::default(),
+        );
+        (merged.result.to_string(), merged.conflicts)
+    }
+
+    #[test]
+    fn test_fields() {
+        let base =
+            "Source: foo\nSection: libs\nPriority: optional\nHomepage: https://example.com/\n";
+        let ours = "Source: foo\n# Moved\nSection: devel\nPriority: optional\nHomepage: https://example.com/\n";
+        let theirs = "Source: foo\nSection: libs\nRules-Requires-Root: no\nPriority: optional\nStandards-Version: 4.6.0\n";
+        assert_eq!(
+            merge_str(base, ours, theirs),
+            (
+                "Source: foo\n# Moved\nSection: devel\nRules-Requires-Root: no\nPriority: optional\nStandards-Version: 4.6.0\n"
+                    .to_string(),
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn test_field_conflict() {
+        let (result, conflicts) = merge_str(
+            "Source: foo\nMaintainer: A <a@example.com>\n",
+            "Source: foo\nMaintainer: B <b@example.com>\n",
+            "Source: foo\nMaintainer: C <c@example.com>\n",
+        );
+        assert_eq!(result, "Source: foo\nMaintainer: B <b@example.com>\n");
+        assert_eq!(
+            conflicts,
+            vec![Conflict::Field {
+                paragraph: Some("foo".to_string()),
+                field: "Maintainer".to_string(),
+                base: Some("A <a@example.com>".to_string()),
+                ours: Some("B <b@example.com>".to_string()),
+                theirs: Some("C <c@example.com>".to_string()),
+            }]
+        );
+        assert_eq!(
+            conflicts[0].to_string(),
+            r#"paragraph foo: conflicting changes to Maintainer: base "A <a@example.com>", ours "B <b@example.com>", theirs "C <c@example.com>""#
+        );
+    }
+
+    #[test]
+    fn test_relations() {
+        let base = "Source: foo\nBuild-Depends: debhelper-compat (= 12),\n               libbar-dev,\n               libbaz-dev\n";
+        let ours = "Source: foo\nBuild-Depends: debhelper-compat (= 13),\n               libbar-dev,\n               libbaz-dev\n";
+        let theirs =
+            "Source: foo\nBuild-Depends: debhelper-compat (= 12), libbar-dev, libquux-dev\n";
+        assert_eq!(
+            merge_str(base, ours, theirs),
+            (
+                "Source: foo\nBuild-Depends: debhelper-compat (= 13),\n               libbar-dev,\n               libquux-dev\n"
+                    .to_string(),
+                vec![]
+            )
+        );
+
+        let (result, conflicts) = merge_str(
+            "Package: foo\nDepends: a, b (>= 1)\n",
+            "Package: foo\nDepends: a, b (>= 2)\n",
+            "Package: foo\nDepends: a, b (>= 3), c\n",
+        );
+        assert_eq!(result, "Package: foo\nDepends: a, b (>= 2), c\n");
+        assert_eq!(
+            conflicts,
+            vec![Conflict::Relation {
+                paragraph: Some("foo".to_string()),
+                field: "Depends".to_string(),
+                base: Some("b (>= 1)".to_string()),
+                ours: Some("b (>= 2)".to_string()),
+                theirs: Some("b (>= 3)".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let base = "Source: foo\n\nPackage: foo\n\nPackage: foo-doc\n\nPackage: foo-old\n";
+        let ours = "Source: foo\n\nPackage: foo\nArchitecture: any\n\nPackage: foo-doc\n\nPackage: foo-old\n";
+        let theirs = "Source: foo\n\nPackage: foo-data\n\nPackage: foo\n\nPackage: foo-old\nSection: oldlibs\n";
+        assert_eq!(
+            merge_str(base, ours, theirs),
+            (
+                "Source: foo\n\nPackage: foo-data\n\nPackage: foo\nArchitecture: any\n\nPackage: foo-old\nSection: oldlibs\n"
+                    .to_string(),
+                vec![]
+            )
+        );
+
+        let (result, conflicts) = merge_str(
+            base,
+            "Source: foo\n\nPackage: foo\n",
+            "Source: foo\n\nPackage: foo\n\nPackage: foo-doc\nSection: doc\n\nPackage: foo-old\n",
+        );
+        assert_eq!(result, "Source: foo\n\nPackage: foo\n");
+        assert_eq!(
+            conflicts,
+            vec![Conflict::Paragraph {
+                key: Some("foo-doc".to_string()),
+                removed_by: Side::Ours,
+            }]
+        );
+
+        // Paragraphs added at the start, and next to a removed paragraph.
+        assert_eq!(
+            merge_str(
+                "Package: a\n\nPackage: b\n\nPackage: c\n",
+                "Package: a\n\nPackage: b\n\nPackage: c\nSection: libs\n",
+                "Package: y\n\nPackage: z\n\nPackage: a\n\nPackage: d\n\nPackage: c\n\nPackage: e\n",
+            ),
+            (
+                "Package: y\n\nPackage: z\n\nPackage: a\n\nPackage: d\n\nPackage: c\nSection: libs\n\nPackage: e\n"
+                    .to_string(),
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn test_added_on_both_sides() {
+        let (result, conflicts) = merge_str(
+            "Source: foo\n",
+            "Source: foo\n\nPackage: foo\nArchitecture: any\n",
+            "Source: foo\n\nPackage: foo\nArchitecture: all\nSection: libs\n",
+        );
+        assert_eq!(
+            result,
+            "Source: foo\n\nPackage: foo\nArchitecture: any\nSection: libs\n"
+        );
+        assert_eq!(conflicts.len(), 1);
+    }
+}
@@ -0,0 +1,191 @@
+//! Structured access to multi-line field values.
+//!
+//! deb822(5) values can span multiple lines. Each continuation line starts with a space or tab,
+//! which is not part of the value. Since empty lines would end the paragraph, a continuation
+//! line consisting of just " ." represents an empty line.
+//!
+//! [`MultiLineValue`] takes care of these rules, so that fields like `Description`, license
+//! texts or `Signed-By` key blocks can be read and written as plain lines of text.
+//!
+//! # Example
+//!
+//! ```rust
+//! use deb822_lossless::multiline::{ContinuationLine, MultiLineValue};
+//! use deb822_lossless::Paragraph;
+//!
+//! let mut p: Paragraph = "Description: short\n first\n .\n second\n".parse().unwrap();
+//! let value = p.get_multiline("Description").unwrap();
+//! assert_eq!(value.first_line, "short");
+//! assert_eq!(value.lines[1], ContinuationLine::Blank);
+//! assert_eq!(value.to_text(), "short\nfirst\n\nsecond");
+//!
+//! p.set_multiline("Description", &MultiLineValue::from_text("new\nsome\n\ntext"));
+//! assert_eq!(p.to_string(), "Description: new\n some\n .\n text\n");
+//! ```
+
+/// A single continuation line of a multi-line value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ContinuationLine {
+    /// A line of text, without the leading space that marks it as a continuation line.
+    ///
+    /// Any further indentation is preserved, e.g. for verbatim lines in a `Description`.
+    Text(String),
+
+    /// An empty line, written as " ." in the file.
+    Blank,
+}
+
+impl ContinuationLine {
+    /// Decode the text of a continuation line, with the first indentation character removed.
+    pub(crate) fn decode(text: &str) -> Self {
+        if text == "." || text.trim().is_empty() {
+            ContinuationLine::Blank
+        } else {
+            ContinuationLine::Text(text.to_string())
+        }
+    }
+
+    /// Returns the text of this line, as it would be displayed.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ContinuationLine::Text(text) => text,
+            ContinuationLine::Blank => "",
+        }
+    }
+}
+
+/// A field value, split into its logical lines.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct MultiLineValue {
+    /// The text directly after the field name, which may be empty.
+    pub first_line: String,
+
+    /// The continuation lines.
+    pub lines: Vec<ContinuationLine>,
+}
+
+impl MultiLineValue {
+    /// Create a new value from its first line and continuation lines.
+    pub fn new(first_line: &str, lines: Vec<ContinuationLine>) -> Self {
+        Self {
+            first_line: first_line.to_string(),
+            lines,
+        }
+    }
+
+    /// Create a value from a field value as returned by [`crate::lossy::Paragraph::get`] or
+    /// [`crate::lossless::Paragraph::get`].
+    ///
+    /// Lines consisting of a single "." are decoded as blank lines.
+    pub fn from_value(value: &str) -> Self {
+        let mut lines = value.split('\n');
+        Self {
+            first_line: lines.next().unwrap_or_default().to_string(),
+            lines: lines.map(ContinuationLine::decode).collect(),
+        }
+    }
+
+    /// Encode this value as a field value, as accepted by [`crate::lossy::Paragraph::set`].
+    ///
+    /// Blank lines are encoded as ".".
+    pub fn to_value(&self) -> String {
+        let mut ret = self.first_line.clone();
+        for line in &self.lines {
+            ret.push('\n');
+            match line {
+                ContinuationLine::Text(text) => ret.push_str(text),
+                ContinuationLine::Blank => ret.push('.'),
+            }
+        }
+        ret
+    }
+
+    /// Create a value from plain text, where empty lines are blank lines.
+    pub fn from_text(text: &str) -> Self {
+        let mut lines = text.split('\n');
+        Self {
+            first_line: lines.next().unwrap_or_default().to_string(),
+            lines: lines
+                .map(|line| {
+                    if line.trim().is_empty() {
+                        ContinuationLine::Blank
+                    } else {
+                        ContinuationLine::Text(line.to_string())
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the value as plain text, with blank lines as empty lines.
+    pub fn to_text(&self) -> String {
+        std::iter::once(self.first_line.as_str())
+            .chain(self.lines.iter().map(|line| line.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns whether the value fits on the same line as the field name.
+    pub fn is_single_line(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+impl std::fmt::Display for MultiLineValue {
+    /// Formats the value as it appears in a deb822 file, after the field name and colon.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if !self.first_line.is_empty() {
+            write!(f, " {}", self.first_line)?;
+        }
+        writeln!(f)?;
+        for line in &self.lines {
+            match line {
+                ContinuationLine::Text(text) => writeln!(f, " {}", text)?,
+                ContinuationLine::Blank => writeln!(f, " .")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_value() {
+        let value = MultiLineValue::from_value("short\nlong\n.\n  verbatim");
+        assert_eq!(
+            value,
+            MultiLineValue::new(
+                "short",
+                vec![
+                    ContinuationLine::Text("long".to_string()),
+                    ContinuationLine::Blank,
+                    ContinuationLine::Text("  verbatim".to_string()),
+                ]
+            )
+        );
+        assert_eq!(value.to_value(), "short\nlong\n.\n  verbatim");
+        assert_eq!(value.to_text(), "short\nlong\n\n  verbatim");
+        assert!(!value.is_single_line());
+        assert!(MultiLineValue::from_value("foo").is_single_line());
+    }
+
+    #[test]
+    fn test_from_text() {
+        let value = MultiLineValue::from_text("\nfoo\n\nbar");
+        assert_eq!(value.first_line, "");
+        assert_eq!(value.to_text(), "\nfoo\n\nbar");
+        assert_eq!(value.to_string(), "\n foo\n .\n bar\n");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(MultiLineValue::from_value("foo").to_string(), " foo\n");
+        assert_eq!(
+            MultiLineValue::from_value("foo\n.\nbar").to_string(),
+            " foo\n .\n bar\n"
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Property tests for the parsers.
+//!
+//! [`document`] generates well-formed deb822 documents along with the fields they contain, so
+//! that the parsers can be checked against the model and against each other. Arbitrary text
+//! is used to check that the lossless parser never panics and never loses input.
+use crate::dialect::Dialect;
+use proptest::prelude::*;
+
+/// A generated field: its name and the lines of its value, without indentation.
+#[derive(Debug, Clone)]
+struct Field {
+    comment: Option<String>,
+    name: String,
+    first_line: String,
+    continuation: Vec<String>,
+}
+
+impl Field {
+    /// The value as returned by [`crate::lossless::Paragraph::get`].
+    fn value(&self) -> String {
+        let mut lines = Vec::new();
+        if !self.first_line.is_empty() || self.continuation.is_empty() {
+            lines.push(self.first_line.as_str());
+        }
+        lines.extend(self.continuation.iter().map(|l| l.as_str()));
+        lines.join("\n")
+    }
+
+    fn render(&self, indent: &str) -> String {
+        let mut ret = String::new();
+        if let Some(comment) = &self.comment {
+            ret.push_str(comment);
+            ret.push('\n');
+        }
+        ret.push_str(&self.name);
+        ret.push(':');
+        if !self.first_line.is_empty() {
+            ret.push(' ');
+            ret.push_str(&self.first_line);
+        }
+        ret.push('\n');
+        for line in &self.continuation {
+            ret.push_str(indent);
+            ret.push_str(line);
+            ret.push('\n');
+        }
+        ret
+    }
+}
+
+fn field_name() -> impl Strategy<Value = String> {
+    "[A-Za-z][A-Za-z0-9-]{0,15}"
+}
+
+/// A line of a field value. It does not start with whitespace, since that would be stripped.
+fn value_line() -> impl Strategy<Value = String> {
+    "[!-~äé€][ -~äé€]{0,30}"
+}
+
+fn field() -> impl Strategy<Value = Field> {
+    (
+        proptest::option::weighted(0.2, "#[ -~]{0,20}"),
+        field_name(),
+        prop_oneof![Just(String::new()), value_line()],
+        prop::collection::vec(value_line(), 0..4),
+    )
+        .prop_map(|(comment, name, first_line, continuation)| Field {
+            comment,
+            name,
+            first_line,
+            continuation,
+        })
+}
+
+/// A paragraph with fields whose names are unique, ignoring case.
+fn paragraph() -> impl Strategy<Value = Vec<Field>> {
+    prop::collection::vec(field(), 1..8).prop_map(|mut fields| {
+        let mut seen = std::collections::HashSet::new();
+        fields.retain(|f| seen.insert(f.name.to_lowercase()));
+        fields
+    })
+}
+
+/// A well-formed deb822 document, along with the paragraphs it contains.
+fn document() -> impl Strategy<Value = (String, Vec<Vec<Field>>)> {
+    (
+        prop::collection::vec((paragraph(), 1..3usize), 0..5),
+        prop_oneof![Just(" "), Just("  "), Just("\t")],
+    )
+        .prop_map(|(paragraphs, indent)| {
+            let mut text = String::new();
+            for (i, (fields, blank_lines)) in paragraphs.iter().enumerate() {
+                if i > 0 {
+                    text.push_str(&"\n".repeat(*blank_lines));
+                }
+                for field in fields {
+                    text.push_str(&field.render(indent));
+                }
+            }
+            (text, paragraphs.into_iter().map(|(p, _)| p).collect())
+        })
+}
+
+/// Text built from the characters that are significant to the lexer.
+fn deb822ish() -> impl Strategy<Value = String> {
+    "([A-Za-z]{1,4}|[:# \t\r\n-]){0,60}"
+}
+
+proptest! {
+    #[test]
+    fn lossless_roundtrip((text, _) in document()) {
+        let (deb822, errors) = crate::Deb822::from_str_relaxed(&text);
+        prop_assert!(errors.is_empty(), "{:?}", errors);
+        prop_assert_eq!(deb822.to_string(), text);
+    }
+
+    #[test]
+    fn lossless_values((text, paragraphs) in document()) {
+        let deb822: crate::Deb822 = text.parse().unwrap();
+        let parsed = deb822.paragraphs().collect::<Vec<_>>();
+        prop_assert_eq!(parsed.len(), paragraphs.len());
+        for (paragraph, fields) in parsed.iter().zip(&paragraphs) {
+            let expected = fields
+                .iter()
+                .map(|f| (f.name.clone(), f.value()))
+                .collect::<Vec<_>>();
+            prop_assert_eq!(paragraph.items().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn lossy_agrees_with_lossless((text, _) in document()) {
+        let lossless: crate::Deb822 = text.parse().unwrap();
+        let lossy: crate::lossy::Deb822 = text.parse().unwrap();
+        prop_assert_eq!(lossy.len(), lossless.paragraphs().count());
+        for (lossy, lossless) in lossy.iter().zip(lossless.paragraphs()) {
+            // The lossy parser keeps the leading newline of values that start on a
+            // continuation line.
+            let expected = lossless
+                .entries()
+                .map(|e| {
+                    let value = e.multiline_value();
+                    if value.first_line.is_empty() && !value.lines.is_empty() {
+                        (e.key().unwrap(), format!("\n{}", e.value()))
+                    } else {
+                        (e.key().unwrap(), e.value())
+                    }
+                })
+                .collect::<Vec<_>>();
+            let lossy = lossy
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>();
+            prop_assert_eq!(lossy, expected);
+        }
+    }
+
+    #[test]
+    fn lossless_never_loses_input(text in prop_oneof![deb822ish(), any::<String>()]) {
+        let (deb822, _) = crate::Deb822::from_str_relaxed(&text);
+        prop_assert_eq!(deb822.to_string(), text);
+    }
+
+    #[test]
+    fn lossless_dialects_never_lose_input(text in deb822ish()) {
+        for dialect in [
+            Dialect::Deb822,
+            Dialect::DpkgStatus,
+            Dialect::Apt,
+            Dialect::Rfc822,
+            Dialect::RDescription,
+        ] {
+            let (deb822, _) =
+                crate::Deb822::from_str_relaxed_with_options(&text, &dialect.options());
+            prop_assert_eq!(deb822.to_string(), text.as_str(), "{:?}", dialect);
+        }
+    }
+
+    #[test]
+    fn lossy_never_panics(text in prop_oneof![deb822ish(), any::<String>()]) {
+        let _ = text.parse::<crate::lossy::Deb822>();
+    }
+}
@@ -0,0 +1,647 @@
+//! Serialize Rust types as deb822 text with serde.
+//!
+//! A struct or map is written as a single paragraph, a sequence of them as multiple paragraphs.
+//! Fields that are `None` are omitted, booleans are written as "yes" or "no" and sequences in a
+//! field are comma-separated by default; see [`crate::lists`] for other encodings.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct Source {
+//!     #[serde(rename = "Source")]
+//!     name: String,
+//!     #[serde(rename = "Build-Depends")]
+//!     build_depends: Vec<String>,
+//!     #[serde(rename = "Homepage")]
+//!     homepage: Option<String>,
+//! }
+//!
+//! let source = Source {
+//!     name: "foo".to_string(),
+//!     build_depends: vec!["bar".to_string(), "baz".to_string()],
+//!     homepage: None,
+//! };
+//! assert_eq!(
+//!     deb822_lossless::to_string(&source).unwrap(),
+//!     "Source: foo\nBuild-Depends: bar, baz\n"
+//! );
+//! ```
+use crate::lossy::{Field, Paragraph};
+use crate::multiline::MultiLineValue;
+use serde::ser::{self, Impossible, Serialize};
+
+/// Error serializing a value as deb822.
+#[derive(Debug)]
+pub enum Error {
+    /// The value can not be represented in deb822, e.g. a nested struct.
+    Unsupported(&'static str),
+
+    /// The name of a field is not valid in deb822, e.g. because it contains a colon.
+    InvalidFieldName(String),
+
+    /// The value of a field could not be serialized.
+    InvalidValue {
+        /// The name of the field.
+        field: String,
+
+        /// Description of the error.
+        message: String,
+    },
+
+    /// Any other error.
+    Message(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Unsupported(what) => write!(f, "{} can not be represented in deb822", what),
+            Error::InvalidFieldName(name) => write!(f, "invalid field name: {:?}", name),
+            Error::InvalidValue { field, message } => {
+                write!(f, "invalid value for field {}: {}", field, message)
+            }
+            Error::Message(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+/// Serialize a value as deb822 text.
+///
+/// Paragraphs without any fields, e.g. because all of them are `None`, are left out.
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    let mut ret = String::new();
+    for (i, paragraph) in value
+        .serialize(DocumentSerializer { nested: false })?
+        .iter()
+        .filter(|paragraph| !paragraph.is_empty())
+        .enumerate()
+    {
+        if i > 0 {
+            ret.push('\n');
+        }
+        for field in &paragraph.fields {
+            ret.push_str(&field.name);
+            ret.push(':');
+            ret.push_str(&MultiLineValue::from_value(&field.value).to_string());
+        }
+    }
+    Ok(ret)
+}
+
+/// Serialize a value as a single paragraph.
+pub fn to_paragraph<T: Serialize + ?Sized>(value: &T) -> Result<Paragraph, Error> {
+    let mut paragraphs = value.serialize(DocumentSerializer { nested: true })?;
+    match paragraphs.len() {
+        0 => Ok(Paragraph { fields: vec![] }),
+        1 => Ok(paragraphs.remove(0)),
+        _ => Err(Error::Unsupported("a sequence")),
+    }
+}
+
+/// Serialize a value as the value of a field.
+///
+/// Returns `None` if the field should be omitted.
+pub(crate) fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Option<String>, Error> {
+    value.serialize(ValueSerializer)
+}
+
+macro_rules! unsupported {
+    ($($method:ident($($arg:ty),*) -> $ret:ty: $what:literal,)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<$ret, Error> {
+                Err(Error::Unsupported($what))
+            }
+        )*
+    };
+}
+
+/// Serializes a document, consisting of one paragraph per struct or map.
+struct DocumentSerializer {
+    /// Whether this is an element of a sequence, which can't be a sequence itself.
+    nested: bool,
+}
+
+impl ser::Serializer for DocumentSerializer {
+    type Ok = Vec<Paragraph>;
+    type Error = Error;
+    type SerializeSeq = ParagraphsSerializer;
+    type SerializeTuple = ParagraphsSerializer;
+    type SerializeTupleStruct = Impossible<Self::Ok, Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+    type SerializeMap = ParagraphSerializer;
+    type SerializeStruct = ParagraphSerializer;
+    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+
+    unsupported! {
+        serialize_bool(bool) -> Self::Ok: "a boolean",
+        serialize_i8(i8) -> Self::Ok: "an integer",
+        serialize_i16(i16) -> Self::Ok: "an integer",
+        serialize_i32(i32) -> Self::Ok: "an integer",
+        serialize_i64(i64) -> Self::Ok: "an integer",
+        serialize_u8(u8) -> Self::Ok: "an integer",
+        serialize_u16(u16) -> Self::Ok: "an integer",
+        serialize_u32(u32) -> Self::Ok: "an integer",
+        serialize_u64(u64) -> Self::Ok: "an integer",
+        serialize_f32(f32) -> Self::Ok: "a number",
+        serialize_f64(f64) -> Self::Ok: "a number",
+        serialize_char(char) -> Self::Ok: "a character",
+        serialize_str(&str) -> Self::Ok: "a string",
+        serialize_bytes(&[u8]) -> Self::Ok: "a byte array",
+        serialize_unit_variant(&'static str, u32, &'static str) -> Self::Ok: "an enum",
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct: "a tuple struct",
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant: "an enum",
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant: "an enum",
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Error> {
+        Ok(vec![])
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Error> {
+        Ok(vec![])
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Error> {
+        Ok(vec![])
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Error> {
+        Err(Error::Unsupported("an enum"))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        if self.nested {
+            return Err(Error::Unsupported("a nested sequence"));
+        }
+        Ok(ParagraphsSerializer(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(ParagraphSerializer::default())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Ok(ParagraphSerializer::default())
+    }
+}
+
+/// Serializes a sequence of paragraphs.
+struct ParagraphsSerializer(Vec<Paragraph>);
+
+impl ser::SerializeSeq for ParagraphsSerializer {
+    type Ok = Vec<Paragraph>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.0
+            .extend(value.serialize(DocumentSerializer { nested: true })?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        Ok(self.0)
+    }
+}
+
+impl ser::SerializeTuple for ParagraphsSerializer {
+    type Ok = Vec<Paragraph>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Serializes the fields of a single paragraph.
+#[derive(Default)]
+struct ParagraphSerializer {
+    fields: Vec<Field>,
+    /// The name of the field whose value is to be serialized next, for maps.
+    key: Option<String>,
+}
+
+impl ParagraphSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, name: String, value: &T) -> Result<(), Error> {
+        if !crate::common::is_valid_key(&name) {
+            return Err(Error::InvalidFieldName(name));
+        }
+        match to_value(value) {
+            Ok(Some(value)) => {
+                self.fields.push(Field { name, value });
+                Ok(())
+            }
+            Ok(None) => Ok(()),
+            Err(e) => Err(Error::InvalidValue {
+                field: name,
+                message: e.to_string(),
+            }),
+        }
+    }
+}
+
+impl ser::SerializeStruct for ParagraphSerializer {
+    type Ok = Vec<Paragraph>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.push(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        Ok(vec![Paragraph {
+            fields: self.fields,
+        }])
+    }
+}
+
+impl ser::SerializeMap for ParagraphSerializer {
+    type Ok = Vec<Paragraph>;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(to_value(key)?.ok_or(Error::Unsupported("an empty field name"))?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::Message("value serialized before key".to_string()))?;
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        ser::SerializeStruct::end(self)
+    }
+}
+
+/// Serializes the value of a field, or `None` if it should be omitted.
+struct ValueSerializer;
+
+macro_rules! serialize_display {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<Self::Ok, Error> {
+                Ok(Some(v.to_string()))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Option<String>;
+    type Error = Error;
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+    type SerializeMap = Impossible<Self::Ok, Error>;
+    type SerializeStruct = Impossible<Self::Ok, Error>;
+    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+
+    serialize_display! {
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+    }
+
+    unsupported! {
+        serialize_bytes(&[u8]) -> Self::Ok: "a byte array",
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant: "an enum with data",
+        serialize_map(Option<usize>) -> Self::SerializeMap: "a nested map",
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct: "a nested struct",
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant: "an enum with data",
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Error> {
+        Ok(Some(if v { "yes" } else { "no" }.to_string()))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Error> {
+        Ok(Some(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Error> {
+        Err(Error::Unsupported("an enum with data"))
+    }
+
+    /// Sequences are written comma-separated.
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(ListSerializer(vec![]))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        self.serialize_seq(Some(len))
+    }
+}
+
+/// Serializes the items of a comma-separated list.
+struct ListSerializer(Vec<String>);
+
+impl ser::SerializeSeq for ListSerializer {
+    type Ok = Option<String>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.0.extend(to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        Ok(Some(self.0.join(", ")))
+    }
+}
+
+impl ser::SerializeTuple for ListSerializer {
+    type Ok = Option<String>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for ListSerializer {
+    type Ok = Option<String>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    enum Priority {
+        #[serde(rename = "optional")]
+        Optional,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Package {
+        package: String,
+        priority: Option<Priority>,
+        essential: Option<bool>,
+        #[serde(rename = "Installed-Size")]
+        installed_size: Option<u64>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        depends: Vec<String>,
+        description: Option<String>,
+    }
+
+    fn hello() -> Package {
+        Package {
+            package: "hello".to_string(),
+            priority: Some(Priority::Optional),
+            essential: Some(false),
+            installed_size: Some(280),
+            depends: vec!["libc6 (>= 2.34)".to_string(), "libfoo".to_string()],
+            description: Some("example package\nThe GNU hello program.\n\nIt's great.".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_struct() {
+        assert_eq!(
+            to_string(&hello()).unwrap(),
+            r#"Package: hello
+Priority: optional
+Essential: no
+Installed-Size: 280
+Depends: libc6 (>= 2.34), libfoo
+Description: example package
+ The GNU hello program.
+ .
+ It's great.
+"#
+        );
+    }
+
+    #[test]
+    fn test_seq() {
+        let packages = vec![
+            hello(),
+            Package {
+                package: "foo".to_string(),
+                priority: None,
+                essential: None,
+                installed_size: None,
+                depends: vec![],
+                description: None,
+            },
+        ];
+        let text = to_string(&packages).unwrap();
+        assert!(text.ends_with("It's great.\n\nPackage: foo\n"), "{}", text);
+
+        let mut map = std::collections::BTreeMap::new();
+        map.insert("Package", None);
+        let mut other = std::collections::BTreeMap::new();
+        other.insert("Package", Some("foo"));
+        assert_eq!(
+            to_string(&vec![map.clone(), other, map]).unwrap(),
+            "Package: foo\n"
+        );
+        assert!(matches!(
+            to_string(&vec![vec![hello()]]),
+            Err(Error::Unsupported("a nested sequence"))
+        ));
+        assert!(matches!(
+            to_paragraph(&packages),
+            Err(Error::Unsupported("a nested sequence"))
+        ));
+    }
+
+    #[test]
+    fn test_map() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert("Package", "foo");
+        map.insert("Version", "1.0");
+        let paragraph = to_paragraph(&map).unwrap();
+        assert_eq!(paragraph.get("Version"), Some("1.0"));
+        assert_eq!(to_string(&map).unwrap(), "Package: foo\nVersion: 1.0\n");
+    }
+
+    #[test]
+    fn test_unsupported() {
+        #[derive(Serialize)]
+        struct Nested {
+            #[serde(rename = "Files")]
+            files: std::collections::BTreeMap<String, String>,
+        }
+        let err = to_string(&Nested {
+            files: Default::default(),
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value for field Files: a nested map can not be represented in deb822"
+        );
+        assert!(matches!(
+            to_string("foo"),
+            Err(Error::Unsupported("a string"))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_field_name() {
+        #[derive(Serialize)]
+        struct Invalid {
+            #[serde(rename = "Package: foo")]
+            package: String,
+        }
+        let err = to_string(&Invalid {
+            package: "bar".to_string(),
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "invalid field name: \"Package: foo\"");
+        for name in ["", "#Package", "-Package", "Pack age"] {
+            let mut map = std::collections::BTreeMap::new();
+            map.insert(name, "foo");
+            assert!(
+                matches!(to_paragraph(&map), Err(Error::InvalidFieldName(n)) if n == name),
+                "{:?}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        #[derive(Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Entry {
+            #[serde(rename = "Name")]
+            name: String,
+            #[serde(rename = "Count")]
+            count: u32,
+            #[serde(rename = "Enabled")]
+            enabled: bool,
+        }
+        let entries = vec![
+            Entry {
+                name: "a".to_string(),
+                count: 1,
+                enabled: true,
+            },
+            Entry {
+                name: "b".to_string(),
+                count: 2,
+                enabled: false,
+            },
+        ];
+        let text = to_string(&entries).unwrap();
+        assert_eq!(crate::de::from_str::<Vec<Entry>>(&text).unwrap(), entries);
+    }
+}
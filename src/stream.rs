@@ -0,0 +1,231 @@
+//! Streaming parsing of deb822 files, one paragraph at a time.
+//!
+//! Unlike [`crate::lossy::Deb822::from_reader`] and [`crate::lossless::Deb822::read`], the
+//! iterators in this module never hold more than a single paragraph in memory. This makes them
+//! suitable for processing very large files, such as the `Packages` and `Sources` indices of
+//! an archive.
+//!
+//! # Example
+//!
+//! ```rust
+//! use deb822_lossless::stream::LossyParagraphs;
+//!
+//! let input = b"Package: foo\nVersion: 1.0\n\nPackage: bar\nVersion: 2.0\n";
+//! let names = LossyParagraphs::new(&input[..])
+//!     .map(|p| p.unwrap().get("Package").unwrap().to_string())
+//!     .collect::<Vec<_>>();
+//! assert_eq!(names, vec!["foo", "bar"]);
+//! ```
+use std::io::BufRead;
+
+/// Error reading a stream of paragraphs.
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error was encountered while reading the input.
+    Io(std::io::Error),
+
+    /// A syntax error was encountered.
+    Syntax {
+        /// The line of the input on which the error occurred (0-based).
+        line: usize,
+
+        /// Description of the error.
+        message: String,
+    },
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "IO error: {}", e),
+            Self::Syntax { line, message } => write!(f, "line {}: {}", line + 1, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Reads the text of one paragraph at a time.
+struct Chunks<R> {
+    reader: R,
+    /// Number of lines consumed so far.
+    line: usize,
+}
+
+impl<R: BufRead> Chunks<R> {
+    /// Read the next paragraph, along with any surrounding comments.
+    ///
+    /// Returns the text and the (0-based) line on which it starts.
+    fn next_chunk(&mut self) -> Result<Option<(String, usize)>, std::io::Error> {
+        let mut chunk = String::new();
+        let mut start = self.line;
+        loop {
+            let len = chunk.len();
+            if self.reader.read_line(&mut chunk)? == 0 {
+                break;
+            }
+            self.line += 1;
+            if chunk[len..].chars().all(crate::common::is_newline) {
+                // An empty line terminates the paragraph, if one was started.
+                chunk.truncate(len);
+                if chunk.is_empty() {
+                    start = self.line;
+                } else {
+                    break;
+                }
+            }
+        }
+        if chunk.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some((chunk, start)))
+        }
+    }
+}
+
+/// Iterator over the paragraphs read from a [`BufRead`], parsed with the lossy parser.
+pub struct LossyParagraphs<R>(Chunks<R>);
+
+impl<R: BufRead> LossyParagraphs<R> {
+    /// Create a new iterator reading from the given reader.
+    pub fn new(reader: R) -> Self {
+        Self(Chunks { reader, line: 0 })
+    }
+}
+
+impl<R: BufRead> Iterator for LossyParagraphs<R> {
+    type Item = Result<crate::lossy::Paragraph, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (text, start) = match self.0.next_chunk() {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e.into())),
+            };
+            match crate::lossy::parse(&text) {
+                // Chunks that only contain comments don't produce a paragraph.
+                Ok(doc) => {
+                    if let Some(paragraph) = doc.into_iter().next() {
+                        return Some(Ok(paragraph));
+                    }
+                }
+                Err((e, line)) => {
+                    return Some(Err(Error::Syntax {
+                        line: start + line,
+                        message: e.to_string(),
+                    }))
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the paragraphs read from a [`BufRead`], parsed with the lossless parser.
+pub struct LosslessParagraphs<R>(Chunks<R>);
+
+impl<R: BufRead> LosslessParagraphs<R> {
+    /// Create a new iterator reading from the given reader.
+    pub fn new(reader: R) -> Self {
+        Self(Chunks { reader, line: 0 })
+    }
+}
+
+impl<R: BufRead> Iterator for LosslessParagraphs<R> {
+    type Item = Result<crate::lossless::Paragraph, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (text, start) = match self.0.next_chunk() {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e.into())),
+            };
+            let (doc, errors) = crate::lossless::Deb822::from_str_relaxed(&text);
+            if let Some(err) = errors.into_iter().next() {
+                return Some(Err(Error::Syntax {
+                    line: start + err.line,
+                    message: err.message,
+                }));
+            }
+            // Chunks that only contain comments don't produce a paragraph.
+            if let Some(paragraph) = doc.paragraphs().next() {
+                return Some(Ok(paragraph));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lossy() {
+        let input = "Package: foo\nDescription: bar\n blah\n\n\n# comment\n\nPackage: bar\n";
+        let paragraphs = LossyParagraphs::new(input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            paragraphs,
+            vec![
+                vec![
+                    ("Package".to_string(), "foo".to_string()),
+                    ("Description".to_string(), "bar\nblah".to_string())
+                ]
+                .into(),
+                vec![("Package".to_string(), "bar".to_string())].into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lossless() {
+        let input = "Package: foo\n# comment\nVersion: 1.0\n\nPackage: bar\n";
+        let paragraphs = LosslessParagraphs::new(input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(paragraphs.len(), 2);
+        assert_eq!(
+            paragraphs[0].to_string(),
+            "Package: foo\n# comment\nVersion: 1.0\n"
+        );
+        assert_eq!(paragraphs[1].get("Package").as_deref(), Some("bar"));
+    }
+
+    #[test]
+    fn test_lossy_error_line() {
+        let input = "Package: foo\n\nPackage: bar\n: 1.0\n";
+        let mut paragraphs = LossyParagraphs::new(input.as_bytes());
+        assert!(paragraphs.next().unwrap().is_ok());
+        let err = paragraphs.next().unwrap().unwrap_err();
+        assert!(matches!(err, Error::Syntax { line: 3, .. }));
+        assert_eq!(err.to_string(), "line 4: Unexpected token: :");
+    }
+
+    #[test]
+    fn test_lossless_error_line() {
+        let input = "Package: foo\n\n\nPackage: bar\nVersion\n";
+        let mut paragraphs = LosslessParagraphs::new(input.as_bytes());
+        assert!(paragraphs.next().unwrap().is_ok());
+        let err = paragraphs.next().unwrap().unwrap_err();
+        assert!(matches!(err, Error::Syntax { line: 4, .. }));
+    }
+
+    #[test]
+    fn test_sources() {
+        let text =
+            std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/bench/Sources")).unwrap();
+        let expected: crate::lossy::Deb822 = text.parse().unwrap();
+        let streamed = LossyParagraphs::new(std::io::BufReader::new(text.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(Vec::from(expected), streamed);
+    }
+}